@@ -90,6 +90,12 @@ mod tests {
         // Test that the command accepts valid settings structure
         let test_settings = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         // Test that settings can be serialized (required for Tauri commands)
@@ -113,6 +119,12 @@ mod tests {
         // Test that AppSettings can be serialized/deserialized for Tauri commands
         let settings = AppSettings {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
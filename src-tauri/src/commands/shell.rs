@@ -1,7 +1,9 @@
 // This file contains the commands that are used to interact with the shell.
 
+use crate::services::themes::active_theme::ActiveTheme;
 use dirs;
 use std::process::{Command, Stdio};
+use tauri::{AppHandle, Emitter};
 
 // Run Update script for Omarchy
 #[tauri::command]
@@ -95,45 +97,91 @@ pub fn get_omarchy_version() -> Result<String, String> {
     }
 }
 
-// Apply theme using omarchy-theme-set
+// Apply theme using omarchy-theme-set, or by relinking current/theme when it isn't installed
 #[tauri::command]
-pub async fn apply_theme(dir: String) -> Result<(), String> {
-    let output = Command::new("omarchy-theme-set").arg(&dir).spawn();
-
-    let result = match output {
-        Ok(_) => {
-            log::info!("Successfully started omarchy-theme-set for theme {dir}");
-            Ok(())
+pub async fn apply_theme(app_handle: AppHandle, dir: String) -> Result<(), String> {
+    let applied = match Command::new("omarchy-theme-set").arg(&dir).spawn() {
+        // The script does the actual switch, so only its exit status tells whether it applied
+        Ok(mut child) => match tokio::task::spawn_blocking(move || child.wait()).await {
+            Ok(Ok(status)) if status.success() => {
+                log::info!("omarchy-theme-set applied theme {dir}");
+                true
+            },
+            Ok(Ok(status)) => {
+                log::warn!("omarchy-theme-set failed for theme {dir}: {status}");
+                false
+            },
+            Ok(Err(e)) => {
+                log::warn!("Failed to wait for omarchy-theme-set: {e}");
+                false
+            },
+            Err(e) => {
+                log::warn!("Failed to wait for omarchy-theme-set: {e}");
+                false
+            },
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            log::info!("omarchy-theme-set not found, linking theme {dir} directly");
+            match ActiveTheme::new().and_then(|active| active.apply(&dir)) {
+                Ok(_) => {
+                    // Reloading apps is best-effort and slow, so don't hold up the caller
+                    tauri::async_runtime::spawn_blocking(|| {
+                        if let Err(e) = refresh_theme_adjustments() {
+                            log::warn!("Failed to reload apps after applying theme: {e}");
+                        }
+                    });
+                    true
+                },
+                Err(e) => {
+                    log::warn!("Failed to apply theme {dir}: {e}");
+                    false
+                },
+            }
         },
         Err(e) => {
             // Log the error but don't fail the process
             log::warn!("Failed to run omarchy-theme-set: {e}");
             log::info!("Continuing without theme application...");
-            // Return Ok to not stop the process
-            Ok(())
+            false
         },
     };
 
     // Invalidate cache after theme application to ensure fresh state
-    if result.is_ok() {
-        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
-            // Invalidate the specific theme that was applied
-            cache.invalidate_theme(&dir).await;
-            // Trigger background refresh to ensure cache is up to date
-            let _ = cache.trigger_background_refresh().await;
+    if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+        // Invalidate the specific theme that was applied
+        cache.invalidate_theme(&dir).await;
+        // Trigger background refresh to ensure cache is up to date
+        let _ = cache.trigger_background_refresh().await;
+    }
+
+    if applied {
+        match crate::services::themes::recents::ThemeRecents::new() {
+            Ok(recents) => {
+                if let Err(e) = recents.record_applied(&dir) {
+                    log::warn!("Failed to record theme application: {e}");
+                }
+            },
+            Err(e) => log::warn!("Failed to open theme recents: {e}"),
+        }
+
+        if let Err(e) = app_handle.emit("theme-applied", &dir) {
+            log::warn!("Failed to emit theme-applied event: {e}");
         }
     }
 
-    result
+    // Return Ok to not stop the process
+    Ok(())
 }
 
 // Refresh apps and gnome
 #[tauri::command]
 pub fn refresh_theme_adjustments() -> Result<(), String> {
+    // Resolve both through XDG_CONFIG_HOME, as ActiveTheme does for the current theme link
+    let config_dir = crate::services::themes::themes_dir::config_dir()?;
+    let theme_dir = ActiveTheme::new()?.current_link().to_path_buf();
+
     // Run a best-effort, silent bash script (no terminal)
     let script = r#"
-THEME_DIR="$HOME/.config/omarchy/current/theme"
-
 # Change GNOME modes
 if [[ -f "$THEME_DIR/light.mode" ]]; then
   if command -v gsettings >/dev/null 2>&1; then
@@ -161,14 +209,14 @@ fi
 
 # Change Chromium colors
 if command -v chromium &>/dev/null; then
-  if [[ -f ~/.config/omarchy/current/theme/light.mode ]]; then
+  if [[ -f "$THEME_DIR/light.mode" ]]; then
     chromium --no-startup-window --set-color-scheme="light"
   else
     chromium --no-startup-window --set-color-scheme="dark"
   fi
 
-  if [[ -f ~/.config/omarchy/current/theme/chromium.theme ]]; then
-    chromium --no-startup-window --set-theme-color="$(<~/.config/omarchy/current/theme/chromium.theme)"
+  if [[ -f "$THEME_DIR/chromium.theme" ]]; then
+    chromium --no-startup-window --set-theme-color="$(<"$THEME_DIR/chromium.theme")"
   else
     # Use a default, neutral grey if theme doesn't have a color
     chromium --no-startup-window --set-theme-color="28,32,39"
@@ -176,7 +224,7 @@ if command -v chromium &>/dev/null; then
 fi
 
 # Trigger Alacritty config reload
-touch "$HOME/.config/alacritty/alacritty.toml" || true
+touch "$CONFIG_DIR/alacritty/alacritty.toml" || true
 
 # Restart components to apply new theme (best-effort)
 pkill -SIGUSR2 btop 2>/dev/null || true
@@ -192,6 +240,8 @@ omarchy-theme-bg-next
     let status = Command::new("bash")
         .arg("-c")
         .arg(script)
+        .env("THEME_DIR", theme_dir)
+        .env("CONFIG_DIR", config_dir)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
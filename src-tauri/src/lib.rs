@@ -2,6 +2,7 @@ mod commands;
 pub mod services;
 pub mod types;
 
+use services::cache::cache_config::CacheConfigManager;
 use services::check_cli_args;
 use services::cli_handler::handle_cli_arguments;
 
@@ -21,39 +22,103 @@ fn create_invoke_handler() -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool +
         // Theme system commands
         services::themes::get_themes::get_themes,
         services::themes::get_sys_themes::get_sys_themes,
+        services::themes::get_sys_themes::get_sys_themes_paged,
+        services::themes::get_sys_themes::get_sys_themes_sorted,
         services::themes::get_sys_themes::get_sys_theme_by_name,
+        services::themes::get_sys_themes::find_theme,
+        services::themes::get_sys_themes::scan_external_themes_dir,
+        services::themes::get_sys_themes::repair_theme_symlinks,
+        services::themes::get_sys_themes::get_theme_image_raw,
         services::get_sys_themes::get_themes_cached,
         services::get_sys_themes::preload_themes,
         services::get_sys_themes::refresh_theme_cache,
+        services::get_sys_themes::reindex_cache,
         services::get_sys_themes::get_theme_metadata,
         services::get_sys_themes::clear_color_cache,
         services::get_sys_themes::get_cache_stats,
+        services::get_sys_themes::reset_cache_stats,
         services::get_sys_themes::invalidate_theme_cache,
         services::get_sys_themes::invalidate_themes_cache,
         services::get_sys_themes::invalidate_custom_themes_cache,
         services::get_sys_themes::invalidate_system_themes_cache,
         services::get_sys_themes::invalidate_and_refresh_cache,
+        services::themes::theme_watcher::start_theme_watcher,
+        services::themes::theme_watcher::stop_theme_watcher,
+        services::themes::theme_watcher::watch_theme,
+        services::themes::theme_watcher::unwatch_theme,
         services::themes::get_current_theme::get_system_theme_colors,
+        services::themes::recents::get_theme_last_applied,
+        services::themes::theme_size::get_theme_size,
+        services::themes::theme_size::get_themes_with_sizes,
+        services::themes::active_theme::get_active_theme,
         // Custom theme commands
         services::themes::custom_themes::create_custom_theme,
         services::themes::custom_themes::create_custom_theme_advanced,
+        services::themes::custom_themes::batch_create_themes,
+        services::themes::custom_themes::import_base16_scheme,
+        services::themes::custom_themes::export_base16_scheme,
+        services::themes::import_formats::get_supported_import_formats,
         services::themes::custom_themes::update_custom_theme,
         services::themes::custom_themes::update_custom_theme_advanced,
+        services::themes::custom_themes::set_app_raw_append,
         services::themes::custom_themes::get_custom_theme,
         services::themes::custom_themes::list_custom_themes,
+        services::themes::custom_themes::list_themes_paginated,
+        services::themes::custom_themes::get_apps_usage,
+        services::themes::custom_themes::search_themes,
+        services::themes::custom_themes::list_colors_usage,
         services::themes::custom_themes::delete_custom_theme,
+        services::themes::custom_themes::suggest_theme_name,
+        services::themes::custom_themes::export_theme_for_omarchy,
+        services::themes::custom_themes::set_selected_background,
+        services::themes::custom_themes::repair_selected_background,
+        services::themes::custom_themes::set_theme_tags,
+        services::themes::custom_themes::set_theme_description,
+        services::themes::custom_themes::set_theme_preview,
         services::themes::custom_themes::init_custom_theme,
+        services::themes::custom_themes::rename_custom_theme,
+        services::themes::custom_themes::duplicate_custom_theme,
+        services::themes::custom_themes::export_custom_theme,
+        services::themes::custom_themes::import_custom_theme,
         services::themes::custom_themes::get_app_schemas,
+        services::themes::custom_themes::get_default_app_config,
+        services::themes::custom_themes::get_generator_capabilities,
+        services::themes::custom_themes::deep_validate_theme,
+        services::themes::custom_themes::preflight_theme,
+        services::themes::custom_themes::read_theme_config,
+        services::themes::custom_themes::regenerate_configs,
+        services::themes::custom_themes::lint_theme,
+        services::themes::custom_themes::validate_theme_data,
+        services::themes::custom_themes::preview_config,
+        services::themes::theme_lint::check_theme_contrast,
+        services::themes::theme_diff::get_theme_diff,
+        services::themes::custom_themes::get_theme_colors_history,
+        services::themes::custom_themes::list_theme_backups,
+        services::themes::custom_themes::restore_theme_backup,
+        services::themes::custom_themes::recolor_from_accent,
+        services::themes::custom_themes::derive_light_variant,
+        services::themes::color_extraction::extract_colors_from_wallpaper,
         services::themes::custom_themes::get_theme_backgrounds,
+        services::themes::custom_themes::get_theme_backgrounds_detailed,
         services::themes::custom_themes::add_theme_backgrounds,
         services::themes::custom_themes::remove_theme_background,
+        services::themes::custom_themes::rename_background,
+        services::themes::custom_themes::reorder_backgrounds,
+        services::themes::custom_themes::deduplicate_backgrounds,
+        services::themes::custom_themes::convert_background_format,
         services::themes::custom_themes::get_background_image_data,
+        services::themes::custom_themes::get_background_thumbnail,
+        services::themes::custom_themes::add_theme_background_from_url,
+        services::themes::custom_themes::preview_theme_on_wallpaper,
+        services::themes::custom_themes::render_palette_swatch,
         // Configuration commands
         commands::update_config::update_config,
         // Cache commands
         services::cache::cache_config::get_cache_config,
         services::cache::cache_config::update_cache_config,
         services::cache::cache_config::reset_cache_config,
+        services::cache::cache_config::get_fallback_colors,
+        services::cache::cache_config::set_fallback_colors,
     ]
 }
 
@@ -114,19 +179,38 @@ pub fn run() {
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 // Initialize cache manager with configuration from file
-                match services::cache::cache_config::CacheConfigManager::load_config(&app_handle) {
+                match CacheConfigManager::load_config(&app_handle) {
                     Ok(config) => {
-                        // Avoid cloning the entire config, just move the theme_cache part
-                        let theme_cache_config = config.theme_cache;
-                        let preload_on_startup = theme_cache_config.preload_on_startup;
+                        CacheConfigManager::apply_themes_directory(&config);
+                        if let Err(e) =
+                            CacheConfigManager::apply_color_cache_config(&app_handle, &config).await
+                        {
+                            log::warn!("Failed to apply color cache config: {e}");
+                        }
 
-                        let _cache_manager =
+                        let preload_on_startup = config.theme_cache.preload_on_startup;
+
+                        let cache_manager =
                             services::cache::cache_manager::init_cache_manager_with_config(
-                                theme_cache_config,
+                                config.theme_cache.clone(),
                             )
                             .await;
                         log::info!("Cache manager initialized successfully");
 
+                        // A fresh snapshot from the last run makes the preload below a no-op
+                        let theme_cache = cache_manager.theme_cache();
+                        if let Err(e) = CacheConfigManager::apply_theme_cache_persistence(
+                            &app_handle,
+                            &config,
+                            theme_cache,
+                        )
+                        .await
+                        {
+                            log::warn!("Failed to enable theme cache persistence: {e}");
+                        } else if let Err(e) = theme_cache.load_snapshot().await {
+                            log::warn!("Failed to load theme cache snapshot: {e}");
+                        }
+
                         // Preload themes if configured to do so
                         if preload_on_startup {
                             if let Err(e) = services::get_sys_themes::preload_themes().await {
@@ -143,6 +227,19 @@ pub fn run() {
                         log::info!("Cache manager initialized with defaults");
                     },
                 }
+
+                // Drop what an interrupted create, import or overwrite left in the themes dir
+                match services::themes::custom_themes::CustomThemeService::new(&app_handle) {
+                    Ok(service) => {
+                        service.clean_up_staging_dirs();
+                    },
+                    Err(e) => log::warn!("Failed to clean up theme staging directories: {e}"),
+                }
+
+                // Keep the cache in step with theme files edited outside the app
+                if let Err(e) = services::themes::theme_watcher::start_theme_watcher().await {
+                    log::warn!("Failed to start theme watcher: {e}");
+                }
             });
 
             // Theme refresh is now handled via CLI commands through single instance plugin
@@ -152,6 +249,9 @@ pub fn run() {
         .on_window_event(|_window, event| {
             if let tauri::WindowEvent::CloseRequested { .. } = event {
                 log::info!("Window closing");
+                if let Err(e) = services::themes::theme_watcher::stop_watching() {
+                    log::warn!("Failed to stop theme watcher: {e}");
+                }
             }
         })
         .run(tauri::generate_context!())
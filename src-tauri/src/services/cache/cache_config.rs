@@ -1,7 +1,9 @@
-use crate::services::themes::theme_cache::CacheConfig;
-use crate::types::AppCacheConfig;
+use crate::services::themes::color_extraction::ColorExtractor;
+use crate::services::themes::theme_cache::{CacheConfig, ThemeCache};
+use crate::services::themes::themes_dir;
+use crate::types::{AppCacheConfig, ThemeColors};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 use tauri::Manager;
 
@@ -69,6 +71,74 @@ impl CacheConfigManager {
         Ok(app_data_dir.join("cache_config.toml"))
     }
 
+    /// Directory for persisted caches, or None when persistence is disabled
+    fn get_persistence_dir(
+        app_handle: &AppHandle,
+        config: &AppCacheConfig,
+    ) -> Result<Option<std::path::PathBuf>, String> {
+        if !config.enable_persistence {
+            return Ok(None);
+        }
+
+        let cache_dir = match &config.cache_directory {
+            Some(dir) => std::path::PathBuf::from(dir),
+            None => app_handle
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed to get app data directory: {e}"))?,
+        };
+
+        Ok(Some(cache_dir))
+    }
+
+    /// Get the persisted color cache file, or None when persistence is disabled
+    pub fn get_color_cache_path(
+        app_handle: &AppHandle,
+        config: &AppCacheConfig,
+    ) -> Result<Option<std::path::PathBuf>, String> {
+        Ok(Self::get_persistence_dir(app_handle, config)?.map(|dir| dir.join("color_cache.json")))
+    }
+
+    /// Get the persisted theme cache snapshot, or None when persistence is disabled
+    pub fn get_theme_cache_path(
+        app_handle: &AppHandle,
+        config: &AppCacheConfig,
+    ) -> Result<Option<std::path::PathBuf>, String> {
+        Ok(Self::get_persistence_dir(app_handle, config)?.map(|dir| dir.join("theme_cache.json")))
+    }
+
+    /// Apply the configured capacity and persistence file to the global theme loader's color cache
+    pub async fn apply_color_cache_config(
+        app_handle: &AppHandle,
+        config: &AppCacheConfig,
+    ) -> Result<(), String> {
+        use crate::services::themes::get_sys_themes;
+
+        get_sys_themes::set_color_cache_capacity(config.theme_cache.max_cache_size).await;
+        let path = Self::get_color_cache_path(app_handle, config)?;
+        get_sys_themes::set_color_cache_persistence(path).await;
+        Ok(())
+    }
+
+    /// Point theme lookups at the configured themes directory, or the default without one.
+    /// Returns whether the directory changed.
+    pub fn apply_themes_directory(config: &AppCacheConfig) -> bool {
+        let previous = themes_dir::themes_dir().ok();
+        themes_dir::set_themes_dir_override(config.themes_directory.as_ref().map(PathBuf::from));
+        previous != themes_dir::themes_dir().ok()
+    }
+
+    /// Point a theme cache at the configured snapshot file
+    pub async fn apply_theme_cache_persistence(
+        app_handle: &AppHandle,
+        config: &AppCacheConfig,
+        theme_cache: &ThemeCache,
+    ) -> Result<(), String> {
+        let path = Self::get_theme_cache_path(app_handle, config)?;
+        theme_cache.set_persistence(path).await;
+        Ok(())
+    }
+
     /// Validate cache configuration
     pub fn validate_config(config: &AppCacheConfig) -> Result<(), String> {
         // Validate theme cache config
@@ -84,6 +154,26 @@ impl CacheConfigManager {
             return Err("Background refresh interval must be greater than 0".to_string());
         }
 
+        // Validate fallback palette
+        let fallback = &config.fallback_colors;
+        for color in [
+            &fallback.primary.background,
+            &fallback.primary.foreground,
+            &fallback.terminal.red,
+            &fallback.terminal.green,
+            &fallback.terminal.yellow,
+            &fallback.terminal.blue,
+            &fallback.terminal.magenta,
+            &fallback.terminal.cyan,
+        ]
+        .into_iter()
+        .chain(fallback.accent.as_ref())
+        {
+            if ColorExtractor::normalize_color(color).is_none() {
+                return Err(format!("Invalid fallback color '{color}'"));
+            }
+        }
+
         // Validate cache directory if specified
         if let Some(cache_dir) = &config.cache_directory {
             let path = Path::new(cache_dir);
@@ -92,6 +182,12 @@ impl CacheConfigManager {
             }
         }
 
+        if let Some(themes_dir) = &config.themes_directory {
+            if !Path::new(themes_dir).is_absolute() {
+                return Err("Themes directory must be an absolute path".to_string());
+            }
+        }
+
         Ok(())
     }
 }
@@ -113,11 +209,18 @@ pub async fn update_cache_config(
 
     // Save the configuration
     CacheConfigManager::save_config(&app_handle, &config)?;
+    CacheConfigManager::apply_color_cache_config(&app_handle, &config).await?;
+    let themes_dir_changed = CacheConfigManager::apply_themes_directory(&config);
 
     // Update the global cache manager if it exists
     if let Ok(cache_manager) = crate::services::cache::cache_manager::get_cache_manager().await {
         let theme_cache = cache_manager.theme_cache();
         theme_cache.update_config(config.theme_cache.clone()).await;
+        if themes_dir_changed {
+            theme_cache.invalidate().await;
+        }
+        CacheConfigManager::apply_theme_cache_persistence(&app_handle, &config, theme_cache)
+            .await?;
     }
 
     Ok(config)
@@ -128,6 +231,8 @@ pub async fn update_cache_config(
 pub async fn reset_cache_config(app_handle: AppHandle) -> Result<AppCacheConfig, String> {
     let default_config = AppCacheConfig::default();
     CacheConfigManager::save_config(&app_handle, &default_config)?;
+    CacheConfigManager::apply_color_cache_config(&app_handle, &default_config).await?;
+    let themes_dir_changed = CacheConfigManager::apply_themes_directory(&default_config);
 
     // Update the global cache manager if it exists
     if let Ok(cache_manager) = crate::services::cache::cache_manager::get_cache_manager().await {
@@ -135,11 +240,46 @@ pub async fn reset_cache_config(app_handle: AppHandle) -> Result<AppCacheConfig,
         theme_cache
             .update_config(default_config.theme_cache.clone())
             .await;
+        if themes_dir_changed {
+            theme_cache.invalidate().await;
+        }
+        CacheConfigManager::apply_theme_cache_persistence(
+            &app_handle,
+            &default_config,
+            theme_cache,
+        )
+        .await?;
     }
 
     Ok(default_config)
 }
 
+/// Tauri command to get the palette used for themes without extractable colors
+#[tauri::command]
+pub async fn get_fallback_colors(app_handle: AppHandle) -> Result<ThemeColors, String> {
+    Ok(CacheConfigManager::load_config(&app_handle)?.fallback_colors)
+}
+
+/// Tauri command to set the palette used for themes without extractable colors
+#[tauri::command]
+pub async fn set_fallback_colors(
+    app_handle: AppHandle,
+    colors: ThemeColors,
+) -> Result<ThemeColors, String> {
+    let mut config = CacheConfigManager::load_config(&app_handle)?;
+    config.fallback_colors = colors;
+    CacheConfigManager::validate_config(&config)?;
+    CacheConfigManager::save_config(&app_handle, &config)?;
+
+    // Custom themes without colors pick up the fallback, so refresh them
+    if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+        cache.invalidate_custom_themes().await;
+        let _ = cache.trigger_background_refresh().await;
+    }
+
+    Ok(config.fallback_colors)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,6 +293,7 @@ mod tests {
         assert_eq!(config.theme_cache.max_cache_size, 1000);
         assert!(!config.enable_persistence);
         assert!(config.cache_directory.is_none());
+        assert_eq!(config.fallback_colors.primary.background, "#1a1a1a");
     }
 
     #[test]
@@ -184,6 +325,32 @@ mod tests {
         // Valid absolute path should work
         config.cache_directory = Some("/tmp/cache".to_string());
         assert!(CacheConfigManager::validate_config(&config).is_ok());
+
+        // Themes directory must be absolute too
+        config.themes_directory = Some("themes".to_string());
+        assert!(CacheConfigManager::validate_config(&config).is_err());
+        config.themes_directory = Some("/tmp/themes".to_string());
+        assert!(CacheConfigManager::validate_config(&config).is_ok());
+
+        // Invalid fallback color
+        config = AppCacheConfig::default();
+        config.fallback_colors.terminal.red = "not-a-color".to_string();
+        assert!(CacheConfigManager::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_config_without_fallback_colors_uses_defaults() {
+        let legacy = r#"
+enable_persistence = false
+
+[theme_cache]
+cache_duration_minutes = 5
+preload_on_startup = true
+background_refresh_interval = 10
+max_cache_size = 1000
+"#;
+        let config: AppCacheConfig = toml::from_str(legacy).unwrap();
+        assert_eq!(config.fallback_colors.primary.foreground, "#ffffff");
     }
 
     #[test]
@@ -197,6 +364,8 @@ mod tests {
             },
             enable_persistence: true,
             cache_directory: Some("/tmp/omarchy_cache".to_string()),
+            themes_directory: None,
+            fallback_colors: ColorExtractor::get_fallback_colors(),
         };
 
         // Test serialization
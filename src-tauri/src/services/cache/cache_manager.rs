@@ -49,18 +49,27 @@ pub async fn init_cache_manager() -> &'static CacheManager {
         .await
 }
 
-/// Initialize the global cache manager with custom configuration
+/// Initialize the global cache manager with custom configuration.
+/// If a command already initialized it with defaults, the configuration is applied to it.
 pub async fn init_cache_manager_with_config(config: CacheConfig) -> &'static CacheManager {
-    CACHE_MANAGER
-        .get_or_init(|| async { CacheManager::with_theme_config(config) })
-        .await
+    let mut pending = Some(config);
+    let manager = CACHE_MANAGER
+        .get_or_init(|| {
+            let config = pending.take().unwrap_or_default();
+            async move { CacheManager::with_theme_config(config) }
+        })
+        .await;
+
+    if let Some(config) = pending {
+        manager.theme_cache().update_config(config).await;
+    }
+    manager
 }
 
-/// Get the global cache manager instance
+/// Get the global cache manager instance, initializing it with defaults on first use.
+/// Concurrent first callers all await the same initialization.
 pub async fn get_cache_manager() -> Result<&'static CacheManager, String> {
-    CACHE_MANAGER
-        .get()
-        .ok_or_else(|| "Cache manager not initialized".to_string())
+    Ok(init_cache_manager().await)
 }
 
 /// Get the global theme cache instance
@@ -100,6 +109,25 @@ mod tests {
         assert_eq!(retrieved_config.max_cache_size, 300);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_get_theme_cache_shares_one_instance() {
+        let handles: Vec<_> = (0..32)
+            .map(|_| tokio::spawn(async { get_theme_cache().await.unwrap() }))
+            .collect();
+
+        let mut caches = Vec::new();
+        for handle in handles {
+            caches.push(handle.await.unwrap());
+        }
+
+        let first = Arc::as_ptr(&caches[0]);
+        assert!(caches.iter().all(|cache| Arc::as_ptr(cache) == first));
+        assert_eq!(
+            Arc::as_ptr(get_cache_manager().await.unwrap().theme_cache()),
+            first
+        );
+    }
+
     #[tokio::test]
     async fn test_global_cache_manager_initialization() {
         // Note: This test might interfere with other tests due to global state
@@ -15,6 +15,10 @@ impl ConfigGenerator for AlacrittyGenerator {
         "alacritty.toml"
     }
 
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let alacritty = theme_data.get("alacritty").unwrap_or(&empty_obj);
@@ -300,4 +304,15 @@ white = "{bright_white}"
         // For now, return empty - could implement TOML parsing if needed
         Ok(json!({}))
     }
+
+    fn get_validation_command(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "alacritty",
+            "migrate",
+            "--dry-run",
+            "--silent",
+            "--config-file",
+            "{file}",
+        ])
+    }
 }
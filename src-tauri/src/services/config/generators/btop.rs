@@ -1,4 +1,6 @@
 use super::ConfigGenerator;
+use crate::services::themes::color_extraction::ColorExtractor;
+use crate::types::ThemeColors;
 use serde_json::{json, Value};
 
 #[allow(unused_macros)]
@@ -22,6 +24,10 @@ impl ConfigGenerator for BtopGenerator {
         "btop.theme"
     }
 
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let btop = theme_data.get("btop").unwrap_or(&empty_obj);
@@ -36,12 +42,25 @@ impl ConfigGenerator for BtopGenerator {
                 .to_string()
         };
 
+        // Unset slots follow the theme palette (from the alacritty colors) when there is one
+        let palette = ColorExtractor::extract_from_custom_theme(theme_data);
+        let from_palette = |pick: fn(&ThemeColors) -> &String, default: &str| -> String {
+            palette.as_ref().map(|p| pick(p).clone()).unwrap_or_else(|| default.to_string())
+        };
+        let accent = palette
+            .as_ref()
+            .and_then(|p| p.accent.clone())
+            .unwrap_or_else(|| from_palette(|p| &p.terminal.blue, "#f59e0b"));
+        let gradient_start = from_palette(|p| &p.terminal.green, "#8a8a8d");
+        let gradient_mid = from_palette(|p| &p.terminal.yellow, "#f59e0b");
+        let gradient_end = from_palette(|p| &p.terminal.red, "#b91c1c");
+
         // Extract colors with defaults from template
-        let main_bg = get_color("basic", "main_bg", "");
-        let main_fg = get_color("basic", "main_fg", "#EAEAEA");
+        let main_bg = get_color("basic", "main_bg", &from_palette(|p| &p.primary.background, ""));
+        let main_fg = get_color("basic", "main_fg", &from_palette(|p| &p.primary.foreground, "#EAEAEA"));
         let title = get_color("basic", "title", "#8a8a8d");
-        let hi_fg = get_color("basic", "hi_fg", "#f59e0b");
-        let selected_bg = get_color("basic", "selected_bg", "#f59e0b");
+        let hi_fg = get_color("basic", "hi_fg", &accent);
+        let selected_bg = get_color("basic", "selected_bg", &accent);
         let selected_fg = get_color("basic", "selected_fg", "#EAEAEA");
         let inactive_fg = get_color("basic", "inactive_fg", "#333333");
         let proc_misc = get_color("basic", "proc_misc", "#8a8a8d");
@@ -50,12 +69,12 @@ impl ConfigGenerator for BtopGenerator {
         let net_box = get_color("boxes", "net_box", "#8a8a8d");
         let proc_box = get_color("boxes", "proc_box", "#8a8a8d");
         let div_line = get_color("boxes", "div_line", "#8a8a8d");
-        let temp_start = get_color("temperature", "temp_start", "#8a8a8d");
-        let temp_mid = get_color("temperature", "temp_mid", "#f59e0b");
-        let temp_end = get_color("temperature", "temp_end", "#b91c1c");
-        let cpu_start = get_color("cpu", "cpu_start", "#8a8a8d");
-        let cpu_mid = get_color("cpu", "cpu_mid", "#f59e0b");
-        let cpu_end = get_color("cpu", "cpu_end", "#b91c1c");
+        let temp_start = get_color("temperature", "temp_start", &gradient_start);
+        let temp_mid = get_color("temperature", "temp_mid", &gradient_mid);
+        let temp_end = get_color("temperature", "temp_end", &gradient_end);
+        let cpu_start = get_color("cpu", "cpu_start", &gradient_start);
+        let cpu_mid = get_color("cpu", "cpu_mid", &gradient_mid);
+        let cpu_end = get_color("cpu", "cpu_end", &gradient_end);
         let free_start = get_color("memory", "free_start", "#8a8a8d");
         let free_mid = get_color("memory", "free_mid", "#f59e0b");
         let free_end = get_color("memory", "free_end", "#b91c1c");
@@ -68,12 +87,12 @@ impl ConfigGenerator for BtopGenerator {
         let used_start = get_color("memory", "used_start", "#8a8a8d");
         let used_mid = get_color("memory", "used_mid", "#f59e0b");
         let used_end = get_color("memory", "used_end", "#b91c1c");
-        let download_start = get_color("network", "download_start", "#8a8a8d");
-        let download_mid = get_color("network", "download_mid", "#f59e0b");
-        let download_end = get_color("network", "download_end", "#b91c1c");
-        let upload_start = get_color("network", "upload_start", "#8a8a8d");
-        let upload_mid = get_color("network", "upload_mid", "#f59e0b");
-        let upload_end = get_color("network", "upload_end", "#b91c1c");
+        let download_start = get_color("network", "download_start", &gradient_start);
+        let download_mid = get_color("network", "download_mid", &gradient_mid);
+        let download_end = get_color("network", "download_end", &gradient_end);
+        let upload_start = get_color("network", "upload_start", &gradient_start);
+        let upload_mid = get_color("network", "upload_mid", &gradient_mid);
+        let upload_end = get_color("network", "upload_end", &gradient_end);
 
         Ok(format!(
             r#"# ────────────────────────────────────────────────────────────
@@ -277,3 +296,24 @@ theme[upload_end]="{upload_end}"
         Ok(json!({}))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_colors_follow_theme_palette() {
+        let theme_data = super::super::tokyo_night_theme_data();
+
+        let content = BtopGenerator.generate_config(&theme_data).unwrap();
+        let main_bg = content
+            .lines()
+            .find_map(|line| line.strip_prefix("theme[main_bg]="))
+            .expect("main_bg line");
+        let hex = main_bg.trim_matches('"');
+        assert!(ColorExtractor::normalize_color(hex).is_some(), "{hex}");
+        assert_eq!(hex, "#1a1b26");
+        assert!(content.contains(r##"theme[temp_end]="#f7768e""##));
+        assert!(content.contains(r##"theme[cpu_start]="#9ece6a""##));
+    }
+}
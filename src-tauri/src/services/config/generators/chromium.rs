@@ -15,6 +15,10 @@ impl ConfigGenerator for ChromiumGenerator {
         "chromium.theme"
     }
 
+    fn can_parse(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let chromium = theme_data.get("chromium").unwrap_or(&empty_obj);
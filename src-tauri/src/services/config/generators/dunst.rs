@@ -0,0 +1,174 @@
+use super::ConfigGenerator;
+use crate::services::themes::color_extraction::ColorExtractor;
+use serde_json::{json, Value};
+
+pub struct DunstGenerator;
+
+unsafe impl Send for DunstGenerator {}
+unsafe impl Sync for DunstGenerator {}
+
+/// Dunst urgency levels, as (theme_data key, schema title)
+const URGENCIES: &[(&str, &str)] = &[
+    ("low", "Low urgency"),
+    ("normal", "Normal urgency"),
+    ("critical", "Critical urgency"),
+];
+
+impl ConfigGenerator for DunstGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "dunst"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "dunstrc"
+    }
+
+    fn uses_accent(&self) -> bool {
+        true
+    }
+
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let empty_obj = json!({});
+        let dunst = theme_data.get("dunst").unwrap_or(&empty_obj);
+        let colors = dunst.get("colors").unwrap_or(&empty_obj);
+
+        // Unset colors follow the theme palette (taken from the alacritty colors)
+        let palette = super::theme_palette(theme_data);
+        let accent = super::theme_accent(theme_data)
+            .and_then(ColorExtractor::normalize_color)
+            .or_else(|| palette.accent.clone())
+            .unwrap_or_else(|| palette.terminal.blue.clone());
+        let dimmed = ColorExtractor::mix(
+            &palette.primary.foreground,
+            &palette.primary.background,
+            0.4,
+        );
+
+        let mut content = String::from(
+            r#"# ────────────────────────────────────────────────────────────
+# Omarchy Custom Theme for Dunst
+# Generated by Omarchist
+# ────────────────────────────────────────────────────────────
+
+[global]
+    frame_width = 2
+    separator_color = frame
+"#,
+        );
+
+        for (urgency, _) in URGENCIES {
+            let overrides = colors.get(*urgency).unwrap_or(&empty_obj);
+            let color = |key: &str, fallback: &str| {
+                overrides
+                    .get(key)
+                    .and_then(|c| c.as_str())
+                    .and_then(ColorExtractor::normalize_color)
+                    .unwrap_or_else(|| fallback.to_string())
+            };
+
+            let (foreground, frame) = match *urgency {
+                "low" => (dimmed.as_str(), dimmed.as_str()),
+                "critical" => (
+                    palette.primary.foreground.as_str(),
+                    palette.terminal.red.as_str(),
+                ),
+                _ => (palette.primary.foreground.as_str(), accent.as_str()),
+            };
+
+            content.push_str(&format!(
+                "\n[urgency_{urgency}]\n    background = \"{}\"\n    foreground = \"{}\"\n    frame_color = \"{}\"\n",
+                color("background", &palette.primary.background),
+                color("foreground", foreground),
+                color("frame_color", frame),
+            ));
+        }
+
+        Ok(content)
+    }
+
+    fn get_config_schema(&self) -> Value {
+        let color = |title: &str| json!({ "type": "string", "format": "color", "title": title });
+        let properties: serde_json::Map<String, Value> = URGENCIES
+            .iter()
+            .map(|(urgency, title)| {
+                (
+                    urgency.to_string(),
+                    json!({
+                        "type": "object",
+                        "title": title,
+                        "properties": {
+                            "background": color("Background"),
+                            "foreground": color("Text"),
+                            "frame_color": color("Frame"),
+                        }
+                    }),
+                )
+            })
+            .collect();
+
+        json!({
+            "type": "object",
+            "properties": {
+                "colors": {
+                    "type": "object",
+                    "properties": properties,
+                }
+            }
+        })
+    }
+
+    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
+        Ok(json!({}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Key/value lines of an INI-style `[section]`, up to the next section header
+    fn section<'a>(content: &'a str, name: &str) -> Vec<(&'a str, &'a str)> {
+        content
+            .lines()
+            .skip_while(|line| line.trim() != format!("[{name}]"))
+            .skip(1)
+            .take_while(|line| !line.trim_start().starts_with('['))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')))
+            .collect()
+    }
+
+    #[test]
+    fn test_urgency_sections_contain_expected_colors() {
+        let mut theme_data = super::super::tokyo_night_theme_data();
+        theme_data["accent"] = json!("#7aa2f7");
+        theme_data["dunst"] = json!({ "colors": { "critical": { "background": "#2D202A" } } });
+
+        let content = DunstGenerator.generate_config(&theme_data).unwrap();
+
+        for (urgency, _) in URGENCIES {
+            let keys: Vec<&str> = section(&content, &format!("urgency_{urgency}"))
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect();
+            assert_eq!(
+                keys,
+                ["background", "foreground", "frame_color"],
+                "{urgency}"
+            );
+        }
+
+        let normal = section(&content, "urgency_normal");
+        assert!(normal.contains(&("background", "#1a1b26")));
+        assert!(normal.contains(&("foreground", "#c0caf5")));
+        assert!(normal.contains(&("frame_color", "#7aa2f7")));
+
+        let critical = section(&content, "urgency_critical");
+        assert!(critical.contains(&("background", "#2d202a")));
+        assert!(critical.contains(&("frame_color", "#f7768e")));
+    }
+}
@@ -0,0 +1,191 @@
+use super::ConfigGenerator;
+use crate::services::themes::color_extraction::ColorExtractor;
+use serde_json::{json, Value};
+
+pub struct GtkGenerator;
+
+unsafe impl Send for GtkGenerator {}
+unsafe impl Sync for GtkGenerator {}
+
+/// Named GTK colors written by the generator, with their schema titles
+const GTK_COLORS: &[(&str, &str)] = &[
+    ("theme_bg_color", "Window background"),
+    ("theme_fg_color", "Window text"),
+    ("theme_base_color", "View background"),
+    ("theme_text_color", "View text"),
+    ("theme_selected_bg_color", "Selection background"),
+    ("theme_selected_fg_color", "Selection text"),
+    ("borders", "Borders"),
+    ("warning_color", "Warning"),
+    ("error_color", "Error"),
+    ("success_color", "Success"),
+];
+
+/// libadwaita names that alias the classic GTK colors above
+const ADWAITA_ALIASES: &[(&str, &str)] = &[
+    ("window_bg_color", "theme_bg_color"),
+    ("window_fg_color", "theme_fg_color"),
+    ("view_bg_color", "theme_base_color"),
+    ("view_fg_color", "theme_text_color"),
+    ("accent_bg_color", "theme_selected_bg_color"),
+    ("accent_fg_color", "theme_selected_fg_color"),
+    ("accent_color", "theme_selected_bg_color"),
+    ("headerbar_bg_color", "theme_bg_color"),
+    ("headerbar_fg_color", "theme_fg_color"),
+    ("popover_bg_color", "theme_base_color"),
+    ("popover_fg_color", "theme_text_color"),
+    ("card_bg_color", "theme_base_color"),
+    ("card_fg_color", "theme_text_color"),
+    ("warning_bg_color", "warning_color"),
+    ("error_bg_color", "error_color"),
+    ("success_bg_color", "success_color"),
+];
+
+impl ConfigGenerator for GtkGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "gtk"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "gtk.css"
+    }
+
+    fn uses_accent(&self) -> bool {
+        true
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let empty_obj = json!({});
+        let gtk = theme_data.get("gtk").unwrap_or(&empty_obj);
+        let colors = gtk.get("colors").unwrap_or(&empty_obj);
+
+        // Unset colors come from the theme palette (taken from the alacritty colors)
+        let palette = super::theme_palette(theme_data);
+        let background = palette.primary.background.clone();
+        let foreground = palette.primary.foreground.clone();
+        let accent = super::theme_accent(theme_data)
+            .and_then(ColorExtractor::normalize_color)
+            .or_else(|| palette.accent.clone())
+            .unwrap_or_else(|| palette.terminal.blue.clone());
+
+        let palette_color = |key: &str| match key {
+            "theme_bg_color" => background.clone(),
+            "theme_fg_color" | "theme_text_color" => foreground.clone(),
+            "theme_base_color" => ColorExtractor::mix(&background, &foreground, 0.05),
+            "theme_selected_bg_color" => accent.clone(),
+            "theme_selected_fg_color" => background.clone(),
+            "borders" => ColorExtractor::mix(&background, &foreground, 0.2),
+            "warning_color" => palette.terminal.yellow.clone(),
+            "error_color" => palette.terminal.red.clone(),
+            _ => palette.terminal.green.clone(),
+        };
+
+        let mut content = String::from(
+            "/* ────────────────────────────────────────────────────────────\n \
+             * Omarchy Custom Theme for GTK\n \
+             * Generated by Omarchist\n \
+             * ──────────────────────────────────────────────────────────── */\n\n",
+        );
+
+        for (name, _) in GTK_COLORS {
+            let value = colors
+                .get(*name)
+                .and_then(|c| c.as_str())
+                .and_then(ColorExtractor::normalize_color)
+                .unwrap_or_else(|| palette_color(name));
+            content.push_str(&format!("@define-color {name} {value};\n"));
+        }
+
+        content.push_str("\n/* libadwaita */\n");
+        for (alias, source) in ADWAITA_ALIASES {
+            content.push_str(&format!("@define-color {alias} @{source};\n"));
+        }
+
+        Ok(content)
+    }
+
+    fn get_config_schema(&self) -> Value {
+        let properties: serde_json::Map<String, Value> = GTK_COLORS
+            .iter()
+            .map(|(name, title)| {
+                (
+                    name.to_string(),
+                    json!({
+                        "type": "string",
+                        "format": "color",
+                        "title": title,
+                    }),
+                )
+            })
+            .collect();
+
+        json!({
+            "type": "object",
+            "properties": {
+                "colors": {
+                    "type": "object",
+                    "properties": properties,
+                }
+            }
+        })
+    }
+
+    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
+        Ok(json!({}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every `@define-color` line must be `@define-color <name> <#rrggbb | @ref>;`
+    fn defined_colors(content: &str) -> Vec<(String, String)> {
+        content
+            .lines()
+            .filter(|line| line.starts_with("@define-color"))
+            .map(|line| {
+                let body = line
+                    .strip_prefix("@define-color ")
+                    .and_then(|l| l.strip_suffix(';'))
+                    .unwrap_or_else(|| panic!("malformed line: {line}"));
+                let (name, value) = body.split_once(' ').expect("expected name and value");
+                assert!(
+                    name.chars().all(|c| c.is_ascii_lowercase() || c == '_'),
+                    "{line}"
+                );
+                let is_hex = value.len() == 7
+                    && value.starts_with('#')
+                    && value[1..].chars().all(|c| c.is_ascii_hexdigit());
+                assert!(is_hex || value.starts_with('@'), "{line}");
+                (name.to_string(), value.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_generated_css_defines_palette_colors() {
+        let mut theme_data = super::super::tokyo_night_theme_data();
+        theme_data["gtk"] =
+            json!({ "colors": { "theme_selected_bg_color": "#FF9E64", "borders": "nope" } });
+
+        let content = GtkGenerator.generate_config(&theme_data).unwrap();
+        let colors = defined_colors(&content);
+        assert_eq!(colors.len(), GTK_COLORS.len() + ADWAITA_ALIASES.len());
+        assert!(content.contains("@define-color theme_bg_color #1a1b26;"));
+        assert!(content.contains("@define-color theme_fg_color #c0caf5;"));
+        assert!(content.contains("@define-color theme_selected_bg_color #ff9e64;"));
+        assert!(content.contains("@define-color error_color #f7768e;"));
+        assert!(content.contains("@define-color window_bg_color @theme_bg_color;"));
+
+        // Invalid overrides fall back to the palette instead of leaking into the CSS
+        let borders = colors.iter().find(|(name, _)| name == "borders").unwrap();
+        assert!(borders.1.starts_with('#'));
+
+        let defaults = GtkGenerator.generate_config(&json!({})).unwrap();
+        assert_eq!(
+            defined_colors(&defaults).len(),
+            GTK_COLORS.len() + ADWAITA_ALIASES.len()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use super::ConfigGenerator;
+use crate::services::themes::color_extraction::ColorExtractor;
 use serde_json::{json, Value};
 
 pub struct HyprlandGenerator;
@@ -15,21 +16,49 @@ impl ConfigGenerator for HyprlandGenerator {
         "hyprland.conf"
     }
 
+    fn uses_accent(&self) -> bool {
+        true
+    }
+
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let hyprland = theme_data.get("hyprland").unwrap_or(&empty_obj);
-
-        // Extract color values with defaults from template
         let colors = hyprland.get("colors").unwrap_or(&empty_obj);
-        let mut active_border = colors
-            .get("active_border")
-            .and_then(|a| a.as_str())
-            .unwrap_or("8A8A8D")
-            .to_string();
-        // normalize to hex without leading '#'
-        if active_border.starts_with('#') {
-            active_border = active_border.trim_start_matches('#').to_string();
-        }
+        let color = |key: &str| {
+            colors
+                .get(key)
+                .and_then(|c| c.as_str())
+                .filter(|c| !c.trim().is_empty())
+        };
+
+        // Fall back to the theme palette (taken from the alacritty colors) when unset
+        let palette = ColorExtractor::extract_from_custom_theme(theme_data);
+        let palette_accent = palette
+            .as_ref()
+            .map(|p| p.accent.clone().unwrap_or_else(|| p.terminal.blue.clone()));
+        let palette_background = palette.as_ref().map(|p| p.primary.background.clone());
+
+        let active_border = hex_without_hash(
+            color("active_border")
+                .or_else(|| super::theme_accent(theme_data))
+                .or(palette_accent.as_deref())
+                .unwrap_or("8A8A8D"),
+        );
+        let inactive_border = hex_without_hash(
+            color("inactive_border")
+                .or(palette_background.as_deref())
+                .unwrap_or("595959"),
+        );
+        let group_active_border = color("group_active_border")
+            .map(hex_without_hash)
+            .unwrap_or_else(|| active_border.clone());
+        let group_inactive_border = color("group_inactive_border")
+            .map(hex_without_hash)
+            .unwrap_or_else(|| inactive_border.clone());
 
         Ok(format!(
             r#"# ────────────────────────────────────────────────────────────
@@ -39,6 +68,12 @@ impl ConfigGenerator for HyprlandGenerator {
 
 general {{
     col.active_border = rgb({active_border})
+    col.inactive_border = rgb({inactive_border})
+}}
+
+group {{
+    col.border_active = rgb({group_active_border})
+    col.border_inactive = rgb({group_inactive_border})
 }}
 
 "#
@@ -46,18 +81,26 @@ general {{
     }
 
     fn get_config_schema(&self) -> Value {
+        let border = |title: &str, default: &str| {
+            json!({
+                "type": "string",
+                "format": "color",
+                "output_format": "hex-no-hash",
+                "title": title,
+                "default": default,
+            })
+        };
+
         json!({
             "type": "object",
             "properties": {
                 "colors": {
                     "type": "object",
                     "properties": {
-                        "active_border": {
-                            "type": "string",
-                            "format": "color",
-                            "output_format": "hex-no-hash",
-                            "default": "8A8A8D",
-                        }
+                        "active_border": border("Active border", "8A8A8D"),
+                        "inactive_border": border("Inactive border", "595959"),
+                        "group_active_border": border("Active group border", "8A8A8D"),
+                        "group_inactive_border": border("Inactive group border", "595959"),
                     }
                 }
             }
@@ -68,4 +111,63 @@ general {{
         // For now, return empty - could implement conf file parsing if needed
         Ok(json!({}))
     }
+
+    fn get_validation_command(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["Hyprland", "--verify-config", "--config", "{file}"])
+    }
+}
+
+/// Hyprland `rgb()` takes hex digits without the leading '#'
+fn hex_without_hash(color: &str) -> String {
+    color.trim().trim_start_matches('#').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal structural check of Hyprland config syntax: balanced sections and
+    /// `key = value` lines, with every color written as `rgb(RRGGBB)`
+    fn assert_valid_hyprland_syntax(content: &str) {
+        let mut depth = 0;
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_suffix('{') {
+                assert!(!section.trim().is_empty(), "unnamed section");
+                depth += 1;
+            } else if line == "}" {
+                assert!(depth > 0, "unbalanced closing brace");
+                depth -= 1;
+            } else {
+                let (key, value) = line.split_once('=').expect("expected key = value");
+                assert!(!key.trim().is_empty());
+                let hex = value
+                    .trim()
+                    .strip_prefix("rgb(")
+                    .and_then(|v| v.strip_suffix(')'))
+                    .expect("expected rgb() color");
+                assert_eq!(hex.len(), 6, "{line}");
+                assert!(hex.chars().all(|c| c.is_ascii_hexdigit()), "{line}");
+            }
+        }
+        assert_eq!(depth, 0, "unclosed section");
+    }
+
+    #[test]
+    fn test_generated_config_is_valid_and_uses_fallbacks() {
+        let mut theme_data = super::super::tokyo_night_theme_data();
+        theme_data["hyprland"] = json!({ "colors": { "group_active_border": "#ff9e64" } });
+
+        let content = HyprlandGenerator.generate_config(&theme_data).unwrap();
+        assert_valid_hyprland_syntax(&content);
+        assert!(content.contains("col.inactive_border = rgb(1a1b26)"));
+        assert!(content.contains("col.border_active = rgb(ff9e64)"));
+        assert!(!content.contains("col.active_border = rgb(8A8A8D)"));
+
+        let defaults = HyprlandGenerator.generate_config(&json!({})).unwrap();
+        assert_valid_hyprland_syntax(&defaults);
+        assert!(defaults.contains("col.active_border = rgb(8A8A8D)"));
+    }
 }
@@ -15,6 +15,10 @@ impl ConfigGenerator for HyprlockGenerator {
         "hyprlock.conf"
     }
 
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let hyprlock = theme_data.get("hyprlock").unwrap_or(&empty_obj);
@@ -15,6 +15,10 @@ impl ConfigGenerator for IconsGenerator {
         "icons.theme"
     }
 
+    fn can_parse(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let icons = theme_data.get("icons").unwrap_or(&empty_obj);
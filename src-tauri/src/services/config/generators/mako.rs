@@ -1,4 +1,6 @@
 use super::ConfigGenerator;
+use crate::services::themes::color_extraction::ColorExtractor;
+use crate::types::ThemeColors;
 use serde_json::{json, Value};
 
 pub struct MakoGenerator;
@@ -15,25 +17,60 @@ impl ConfigGenerator for MakoGenerator {
         "mako.ini"
     }
 
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let mako = theme_data.get("mako").unwrap_or(&empty_obj);
 
-        // Extract color values with defaults from template
+        // Unset colors follow the theme palette, then the template defaults
+        let palette = ColorExtractor::extract_from_custom_theme(theme_data);
+        let palette_color = |pick: fn(&ThemeColors) -> &String, default: &str| {
+            palette
+                .as_ref()
+                .map(|p| pick(p).clone())
+                .unwrap_or_else(|| default.to_string())
+        };
         let colors = mako.get("colors").unwrap_or(&empty_obj);
-        let normal = colors.get("normal").unwrap_or(&empty_obj);
-        let text_color = normal
-            .get("text_color")
-            .and_then(|t| t.as_str())
-            .unwrap_or("#8A8A8D");
-        let border_color = normal
-            .get("border_color")
-            .and_then(|b| b.as_str())
-            .unwrap_or("#8A8A8D");
-        let background_color = normal
-            .get("background_color")
-            .and_then(|bg| bg.as_str())
-            .unwrap_or("#1E1E1E");
+        let urgency_color = |urgency: &str, key: &str| {
+            colors
+                .get(urgency)
+                .and_then(|u| u.get(key))
+                .and_then(|c| c.as_str())
+                .filter(|c| !c.trim().is_empty())
+                .map(str::to_string)
+        };
+
+        let text_color = urgency_color("normal", "text_color")
+            .unwrap_or_else(|| palette_color(|p| &p.primary.foreground, "#8A8A8D"));
+        let border_color = urgency_color("normal", "border_color")
+            .unwrap_or_else(|| palette_color(|p| &p.terminal.blue, "#8A8A8D"));
+        let background_color = urgency_color("normal", "background_color")
+            .unwrap_or_else(|| palette_color(|p| &p.primary.background, "#1E1E1E"));
+
+        // Per-urgency blocks only list the colors that differ from the normal ones;
+        // critical notifications get a red border unless overridden
+        let critical_border = palette_color(|p| &p.terminal.red, "#F7768E");
+        let mut urgency_sections = String::new();
+        for urgency in ["low", "critical"] {
+            let mut lines = Vec::new();
+            for key in ["text_color", "border_color", "background_color"] {
+                let value = match urgency_color(urgency, key) {
+                    Some(value) => value,
+                    None if urgency == "critical" && key == "border_color" => {
+                        critical_border.clone()
+                    },
+                    None => continue,
+                };
+                lines.push(format!("{}={value}", key.replace('_', "-")));
+            }
+            if !lines.is_empty() {
+                urgency_sections
+                    .push_str(&format!("\n[urgency={urgency}]\n{}\n", lines.join("\n")));
+            }
+        }
 
         Ok(format!(
             r#"# ────────────────────────────────────────────────────────────
@@ -53,7 +90,7 @@ anchor=top-right
 outer-margin=20
 default-timeout=5000
 max-icon-size=32
-
+{urgency_sections}
 [app-name=Spotify]
 invisible=1
 
@@ -98,7 +135,9 @@ invisible=false
                                     "default": "#8A8A8D",
                                 },
                             }
-                        },                      
+                        },
+                        "low": urgency_schema("Low urgency"),
+                        "critical": urgency_schema("Critical urgency"),
                     }
                 }
             }
@@ -110,3 +149,38 @@ invisible=false
         Ok(json!({}))
     }
 }
+
+/// Optional color overrides for one urgency level; unset colors inherit the normal ones
+fn urgency_schema(title: &str) -> Value {
+    let color = |title: &str| json!({ "type": "string", "format": "color", "title": title });
+    json!({
+        "type": "object",
+        "title": title,
+        "properties": {
+            "border_color": color("Border Color"),
+            "background_color": color("Background Color"),
+            "text_color": color("Text Color"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urgency_sections_use_overrides_and_palette() {
+        let mut theme_data = super::super::tokyo_night_theme_data();
+        theme_data["mako"] = json!({ "colors": { "low": { "text_color": "#565f89" } } });
+
+        let content = MakoGenerator.generate_config(&theme_data).unwrap();
+        let (global, rest) = content.split_once("[urgency=low]").unwrap();
+        assert!(global.contains("text-color=#c0caf5"));
+        assert!(global.contains("border-color=#7aa2f7"));
+        assert!(global.contains("background-color=#1a1b26"));
+
+        let (low, critical) = rest.split_once("[urgency=critical]").unwrap();
+        assert_eq!(low.trim(), "text-color=#565f89");
+        assert!(critical.trim_start().starts_with("border-color=#f7768e"));
+    }
+}
@@ -1,16 +1,24 @@
-use serde_json::Value;
+use crate::services::themes::color_extraction::ColorExtractor;
+use crate::types::ThemeColors;
+use serde_json::{json, Value};
+use std::io::Write;
 
 pub mod alacritty;
 pub mod btop;
 pub mod chromium;
+pub mod dunst;
+pub mod gtk;
 pub mod hyprland;
 pub mod hyprlock;
 pub mod icons;
 pub mod mako;
 pub mod neovim;
+pub mod raw_files;
+pub mod rofi;
 pub mod swayosd;
 pub mod walker;
 pub mod waybar;
+pub mod wofi;
 
 pub trait ConfigGenerator: Send + Sync {
     fn get_app_name(&self) -> &'static str;
@@ -18,6 +26,177 @@ pub trait ConfigGenerator: Send + Sync {
     fn generate_config(&self, theme_data: &Value) -> Result<String, String>;
     fn get_config_schema(&self) -> Value;
     fn parse_existing_config(&self, content: &str) -> Result<Value, String>;
+
+    /// Stream the generated config into a writer (defaults to writing `generate_config` output)
+    fn write_config(&self, theme_data: &Value, writer: &mut dyn Write) -> Result<(), String> {
+        let content = self.generate_config(theme_data)?;
+        writer
+            .write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to write config: {e}"))
+    }
+
+    /// Command that checks a config with the app's own parser; `{file}` is replaced by its path
+    fn get_validation_command(&self) -> Option<Vec<&'static str>> {
+        None
+    }
+
+    /// Whether raw user text (`raw_append`) may be appended after the generated output
+    fn supports_raw_append(&self) -> bool {
+        false
+    }
+
+    /// Whether `parse_existing_config` recovers theme data instead of returning an empty stub
+    fn can_parse(&self) -> bool {
+        false
+    }
+
+    /// Whether the generator writes more than its single `get_file_name` output
+    fn is_multi_file(&self) -> bool {
+        false
+    }
+
+    /// Whether the output picks up the theme-level accent color
+    fn uses_accent(&self) -> bool {
+        false
+    }
+
+    /// Baseline theme_data subtree for this app, built from the schema defaults
+    fn get_default_theme_data(&self) -> Value {
+        defaults_from_schema(&self.get_config_schema())
+    }
+}
+
+/// Generate an app's config and append its `raw_append` text verbatim when supported
+pub fn generate_with_raw_append(
+    generator: &dyn ConfigGenerator,
+    theme_data: &Value,
+) -> Result<String, String> {
+    let mut buffer = Vec::new();
+    write_with_raw_append(generator, theme_data, &mut buffer)?;
+    String::from_utf8(buffer).map_err(|e| format!("Generated config is not valid UTF-8: {e}"))
+}
+
+/// Stream an app's config into a writer, followed by its `raw_append` text when supported
+pub fn write_with_raw_append(
+    generator: &dyn ConfigGenerator,
+    theme_data: &Value,
+    writer: &mut dyn Write,
+) -> Result<(), String> {
+    let mut tracking = TrackingWriter {
+        inner: writer,
+        last_byte: None,
+    };
+    generator.write_config(theme_data, &mut tracking)?;
+
+    if !generator.supports_raw_append() {
+        return Ok(());
+    }
+
+    let raw_append = theme_data
+        .get(generator.get_app_name())
+        .and_then(|app| app.get("raw_append"))
+        .and_then(|raw| raw.as_str())
+        .unwrap_or_default();
+
+    if !raw_append.is_empty() {
+        let mut tail = String::with_capacity(raw_append.len() + 2);
+        if tracking.last_byte.is_some_and(|b| b != b'\n') {
+            tail.push('\n');
+        }
+        tail.push_str(raw_append);
+        if !raw_append.ends_with('\n') {
+            tail.push('\n');
+        }
+        tracking
+            .write_all(tail.as_bytes())
+            .map_err(|e| format!("Failed to write raw config text: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Writer wrapper that remembers the last byte written
+struct TrackingWriter<'a> {
+    inner: &'a mut dyn Write,
+    last_byte: Option<u8>,
+}
+
+impl Write for TrackingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if written > 0 {
+            self.last_byte = Some(buf[written - 1]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Collect the `default` values of a JSON schema into a matching object tree
+pub fn defaults_from_schema(schema: &Value) -> Value {
+    if let Some(default) = schema.get("default") {
+        return default.clone();
+    }
+
+    let mut defaults = serde_json::Map::new();
+    if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+        for (key, property) in properties {
+            let value = defaults_from_schema(property);
+            let is_empty_object = value.as_object().is_some_and(|o| o.is_empty());
+            if !value.is_null() && !is_empty_object {
+                defaults.insert(key.clone(), value);
+            }
+        }
+    }
+    Value::Object(defaults)
+}
+
+/// Accent color the theme service injects next to the app configs, if one was extracted
+pub fn theme_accent(theme_data: &Value) -> Option<&str> {
+    theme_data.get("accent").and_then(|a| a.as_str())
+}
+
+/// Key under which the theme service injects its configured fallback palette
+pub const FALLBACK_COLORS_KEY: &str = "fallback_colors";
+
+/// Palette for output that follows the theme colors: the theme's own alacritty colors,
+/// else the fallback palette the theme service injects, else the built-in one
+pub fn theme_palette(theme_data: &Value) -> ThemeColors {
+    ColorExtractor::extract_from_custom_theme(theme_data)
+        .or_else(|| {
+            theme_data
+                .get(FALLBACK_COLORS_KEY)
+                .and_then(|colors| serde_json::from_value(colors.clone()).ok())
+        })
+        .unwrap_or_else(ColorExtractor::get_fallback_colors)
+}
+
+/// App keys renamed in the registry, as (old key, current key)
+pub const RENAMED_APP_KEYS: &[(&str, &str)] = &[("nvim", "neovim")];
+
+/// Move theme data stored under renamed app keys to their current names.
+/// When both keys exist the current one wins. Returns the migrations that happened.
+pub fn migrate_app_keys(apps: &mut Value) -> Vec<(&'static str, &'static str)> {
+    let Some(apps) = apps.as_object_mut() else {
+        return Vec::new();
+    };
+
+    let mut migrated = Vec::new();
+    for &(old_key, new_key) in RENAMED_APP_KEYS {
+        let Some(data) = apps.remove(old_key) else {
+            continue;
+        };
+        if apps.contains_key(new_key) {
+            log::warn!("Dropping app data under '{old_key}': '{new_key}' is already present");
+        } else {
+            apps.insert(new_key.to_string(), data);
+        }
+        migrated.push((old_key, new_key));
+    }
+    migrated
 }
 
 pub struct ConfigGeneratorRegistry {
@@ -48,6 +227,10 @@ impl ConfigGeneratorRegistry {
         registry.register(Box::new(walker::WalkerGenerator));
         registry.register(Box::new(neovim::NeovimGenerator));
         registry.register(Box::new(icons::IconsGenerator));
+        registry.register(Box::new(gtk::GtkGenerator));
+        registry.register(Box::new(rofi::RofiGenerator));
+        registry.register(Box::new(wofi::WofiGenerator));
+        registry.register(Box::new(dunst::DunstGenerator));
 
         registry
     }
@@ -68,4 +251,112 @@ impl ConfigGeneratorRegistry {
     pub fn get_schema_for_app(&self, app_name: &str) -> Option<Value> {
         self.get_generator(app_name).map(|g| g.get_config_schema())
     }
+
+    /// Default theme_data subtree and generated content for an app
+    pub fn get_default_config(&self, app_name: &str) -> Result<(Value, String), String> {
+        let generator = self
+            .get_generator(app_name)
+            .ok_or_else(|| format!("Unknown app '{app_name}'"))?;
+
+        let theme_data = generator.get_default_theme_data();
+        let content = generator.generate_config(&json!({ app_name: theme_data.clone() }))?;
+        Ok((theme_data, content))
+    }
+}
+
+/// Theme data holding only a Tokyo Night Alacritty palette, shared by generator tests
+#[cfg(test)]
+pub(crate) fn tokyo_night_theme_data() -> Value {
+    json!({
+        "alacritty": {
+            "colors": {
+                "primary": { "background": "#1a1b26", "foreground": "#c0caf5" },
+                "normal": {
+                    "red": "#f7768e", "green": "#9ece6a", "yellow": "#e0af68",
+                    "blue": "#7aa2f7", "magenta": "#bb9af7", "cyan": "#7dcfff"
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_from_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "colors": {
+                    "type": "object",
+                    "properties": {
+                        "background": {"type": "string", "default": "#000000"},
+                        "foreground": {"type": "string"}
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            defaults_from_schema(&schema),
+            json!({"colors": {"background": "#000000"}})
+        );
+    }
+
+    #[test]
+    fn test_alacritty_default_config_is_valid_toml() {
+        let registry = ConfigGeneratorRegistry::new();
+        let (theme_data, content) = registry.get_default_config("alacritty").unwrap();
+
+        assert!(theme_data.is_object());
+        let parsed: toml::Value = toml::from_str(&content).expect("default config should parse");
+        assert!(parsed.get("colors").is_some());
+    }
+
+    #[test]
+    fn test_raw_append_is_appended_verbatim() {
+        let registry = ConfigGeneratorRegistry::new();
+        let generator = registry.get_generator("hyprland").unwrap();
+        let raw = "bind = SUPER, T, exec, kitty\nbind = SUPER, Q, killactive";
+        let theme_data = json!({"hyprland": {"raw_append": raw}});
+
+        let content = generate_with_raw_append(generator, &theme_data).unwrap();
+        assert!(content.ends_with(&format!("{raw}\n")));
+    }
+
+    #[test]
+    fn test_streamed_output_matches_string_version() {
+        let registry = ConfigGeneratorRegistry::new();
+        let theme_data = json!({
+            "neovim": {"raw_config": "return { { \"folke/tokyonight.nvim\" } }"},
+            "alacritty": {"colors": {"primary": {"background": "#101010"}}}
+        });
+
+        for app_name in registry.get_all_apps() {
+            let generator = registry.get_generator(app_name).unwrap();
+            let expected = generator.generate_config(&theme_data).unwrap();
+
+            let mut streamed = Vec::new();
+            generator.write_config(&theme_data, &mut streamed).unwrap();
+            assert_eq!(String::from_utf8(streamed).unwrap(), expected, "{app_name}");
+        }
+    }
+
+    #[test]
+    fn test_raw_append_ignored_when_unsupported() {
+        let registry = ConfigGeneratorRegistry::new();
+        let generator = registry.get_generator("chromium").unwrap();
+        let theme_data = json!({"chromium": {"raw_append": "extra"}});
+
+        let content = generate_with_raw_append(generator, &theme_data).unwrap();
+        assert!(!content.contains("extra"));
+    }
+
+    #[test]
+    fn test_default_config_unknown_app() {
+        let registry = ConfigGeneratorRegistry::new();
+        assert!(registry.get_default_config("not-an-app").is_err());
+    }
 }
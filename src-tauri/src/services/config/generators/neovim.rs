@@ -1,5 +1,31 @@
 use super::ConfigGenerator;
+use crate::services::themes::color_extraction::ColorExtractor;
+use crate::types::ThemeColors;
 use serde_json::{json, Value};
+use std::io::Write;
+
+const NEOVIM_HEADER: &str = "-- ────────────────────────────────────────────────────────────\n-- Omarchy Custom Theme for Neovim\n-- Generated by Omarchist\n-- ────────────────────────────────────────────────────────────\n\n";
+
+const NEOVIM_DEFAULT_CONFIG: &str = r#"return {
+  { "tahayvr/matteblack.nvim", lazy = false, priority = 1000 },
+  {
+		"LazyVim/LazyVim",
+		opts = {
+			colorscheme = "matteblack",
+		},
+	},
+}"#;
+
+/// Syntax groups that can be overridden, with the palette color each defaults to
+const SYNTAX_GROUPS: &[(&str, &str)] = &[
+    ("String", "green"),
+    ("Function", "blue"),
+    ("Keyword", "magenta"),
+    ("Type", "yellow"),
+    ("Constant", "yellow"),
+    ("Special", "cyan"),
+    ("Error", "red"),
+];
 
 pub struct NeovimGenerator;
 
@@ -15,30 +41,53 @@ impl ConfigGenerator for NeovimGenerator {
         "neovim.lua"
     }
 
+    fn can_parse(&self) -> bool {
+        true
+    }
+
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let mut buffer = Vec::new();
+        self.write_config(theme_data, &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| format!("Generated config is not valid UTF-8: {e}"))
+    }
+
+    fn write_config(&self, theme_data: &Value, writer: &mut dyn Write) -> Result<(), String> {
         let empty_obj = json!({});
         let neovim = theme_data.get("neovim").unwrap_or(&empty_obj);
 
-        // Check if raw_config is provided
+        // Check if raw_config is provided; stream it without building a second copy
         if let Some(raw_config) = neovim.get("raw_config").and_then(|rc| rc.as_str()) {
             if !raw_config.trim().is_empty() {
-                return Ok(format!(
-                    "-- ────────────────────────────────────────────────────────────\n-- Omarchy Custom Theme for Neovim\n-- Generated by Omarchist\n-- ────────────────────────────────────────────────────────────\n\n{raw_config}"
-                ));
+                return writer
+                    .write_all(NEOVIM_HEADER.as_bytes())
+                    .and_then(|_| writer.write_all(raw_config.as_bytes()))
+                    .map_err(|e| format!("Failed to write neovim config: {e}"));
             }
         }
 
+        // Build a colorscheme from the theme palette when asked to
+        let use_palette = neovim
+            .get("use_theme_palette")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if use_palette {
+            let palette = super::theme_palette(theme_data);
+            let overrides = neovim.get("highlights").unwrap_or(&empty_obj);
+            let content = palette_colorscheme(&palette, overrides);
+            return writer
+                .write_all(NEOVIM_HEADER.as_bytes())
+                .and_then(|_| writer.write_all(content.as_bytes()))
+                .map_err(|e| format!("Failed to write neovim config: {e}"));
+        }
+
         // Fallback to default template if no raw config provided
-        Ok(r#"return {
-  { "tahayvr/matteblack.nvim", lazy = false, priority = 1000 },
-  {
-		"LazyVim/LazyVim",
-		opts = {
-			colorscheme = "matteblack",
-		},
-	},
-}"#
-        .to_string())
+        writer
+            .write_all(NEOVIM_DEFAULT_CONFIG.as_bytes())
+            .map_err(|e| format!("Failed to write neovim config: {e}"))
     }
 
     fn get_config_schema(&self) -> Value {
@@ -50,6 +99,17 @@ impl ConfigGenerator for NeovimGenerator {
                     "title": "Neovim Configuration",
                     "description": "Raw Lua configuration for Neovim theme",
                     "x-component": "textarea"
+                },
+                "use_theme_palette": {
+                    "type": "boolean",
+                    "title": "Generate From Theme Palette",
+                    "description": "Set highlight groups from the theme colors instead of using a colorscheme plugin",
+                    "default": false
+                },
+                "highlights": {
+                    "type": "object",
+                    "title": "Highlight Overrides",
+                    "properties": highlight_override_schema()
                 }
             }
         })
@@ -78,3 +138,132 @@ impl ConfigGenerator for NeovimGenerator {
         }))
     }
 }
+
+/// Schema properties for the overridable highlight colors
+fn highlight_override_schema() -> serde_json::Map<String, Value> {
+    let mut properties = serde_json::Map::new();
+    let groups = ["Normal", "Comment", "StatusLine"]
+        .into_iter()
+        .chain(SYNTAX_GROUPS.iter().map(|(group, _)| *group));
+    for group in groups {
+        properties.insert(
+            group.to_string(),
+            json!({"type": "string", "format": "color", "title": group}),
+        );
+    }
+    properties
+}
+
+/// A LazyVim spec whose colorscheme sets core highlight groups from the palette.
+/// Overrides replace a group's foreground.
+fn palette_colorscheme(palette: &ThemeColors, overrides: &Value) -> String {
+    let override_for = |group: &str| {
+        overrides
+            .get(group)
+            .and_then(|v| v.as_str())
+            .and_then(ColorExtractor::normalize_color)
+    };
+    let background = &palette.primary.background;
+    let foreground = override_for("Normal").unwrap_or_else(|| palette.primary.foreground.clone());
+    let terminal = &palette.terminal;
+    let slot = |name: &str| match name {
+        "red" => terminal.red.clone(),
+        "green" => terminal.green.clone(),
+        "yellow" => terminal.yellow.clone(),
+        "blue" => terminal.blue.clone(),
+        "magenta" => terminal.magenta.clone(),
+        _ => terminal.cyan.clone(),
+    };
+
+    let mut highlights = vec![
+        (
+            "Normal",
+            format!(r#"fg = "{foreground}", bg = "{background}""#),
+        ),
+        (
+            "Comment",
+            format!(
+                r#"fg = "{}", italic = true"#,
+                override_for("Comment").unwrap_or_else(|| ColorExtractor::mix(
+                    &foreground,
+                    background,
+                    0.45
+                ))
+            ),
+        ),
+    ];
+    for (group, color) in SYNTAX_GROUPS {
+        let fg = override_for(group).unwrap_or_else(|| slot(color));
+        highlights.push((group, format!(r#"fg = "{fg}""#)));
+    }
+    let status_fg = override_for("StatusLine").unwrap_or_else(|| foreground.clone());
+    highlights.push((
+        "StatusLine",
+        format!(
+            r#"fg = "{status_fg}", bg = "{}""#,
+            ColorExtractor::mix(background, &foreground, 0.15)
+        ),
+    ));
+    highlights.push((
+        "StatusLineNC",
+        format!(
+            r#"fg = "{}", bg = "{}""#,
+            ColorExtractor::mix(&foreground, background, 0.45),
+            ColorExtractor::mix(background, &foreground, 0.08)
+        ),
+    ));
+
+    let is_light = ColorExtractor::relative_luminance(background).is_some_and(|l| l > 0.5);
+    let mut lua = String::from(
+        "return {\n  {\n    \"LazyVim/LazyVim\",\n    opts = {\n      colorscheme = function()\n",
+    );
+    lua.push_str("        vim.cmd(\"highlight clear\")\n");
+    lua.push_str(&format!(
+        "        vim.o.background = \"{}\"\n",
+        if is_light { "light" } else { "dark" }
+    ));
+    lua.push_str("        vim.g.colors_name = \"omarchist\"\n");
+    for (group, attributes) in highlights {
+        lua.push_str(&format!(
+            "        vim.api.nvim_set_hl(0, \"{group}\", {{ {attributes} }})\n"
+        ));
+    }
+    lua.push_str("      end,\n    },\n  },\n}\n");
+    lua
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_colorscheme_sets_each_group() {
+        let mut theme_data = super::super::tokyo_night_theme_data();
+        theme_data["neovim"] = json!({
+            "use_theme_palette": true,
+            "highlights": { "Keyword": "#ff9e64" }
+        });
+
+        let lua = NeovimGenerator.generate_config(&theme_data).unwrap();
+        for group in ["Normal", "Comment", "StatusLine"]
+            .into_iter()
+            .chain(SYNTAX_GROUPS.iter().map(|(group, _)| *group))
+        {
+            assert!(
+                lua.contains(&format!("vim.api.nvim_set_hl(0, \"{group}\", {{")),
+                "missing {group}"
+            );
+        }
+        assert!(lua
+            .contains(r##"vim.api.nvim_set_hl(0, "Normal", { fg = "#c0caf5", bg = "#1a1b26" })"##));
+        assert!(lua.contains(r##"vim.api.nvim_set_hl(0, "String", { fg = "#9ece6a" })"##));
+        assert!(lua.contains(r##"vim.api.nvim_set_hl(0, "Keyword", { fg = "#ff9e64" })"##));
+        assert!(lua.contains("vim.o.background = \"dark\""));
+    }
+
+    #[test]
+    fn test_plugin_template_stays_the_default() {
+        let lua = NeovimGenerator.generate_config(&json!({})).unwrap();
+        assert_eq!(lua, NEOVIM_DEFAULT_CONFIG);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::services::util::atomic_file::write_atomic;
+use serde_json::Value;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Files inside a theme that raw files must never replace
+const RESERVED_PATHS: &[&str] = &["custom_theme.json", "backups"];
+
+/// Passthrough for apps without a typed generator: `theme_data.files` maps paths relative to
+/// the theme directory to contents that are written verbatim
+pub struct RawFilesGenerator;
+
+impl RawFilesGenerator {
+    /// Key in theme_data holding the raw files
+    pub const KEY: &'static str = "files";
+
+    /// Check that `path` is a plain relative path that stays inside the theme directory
+    fn validate_path(path: &str) -> Result<PathBuf, String> {
+        let relative = Path::new(path);
+        let mut components = relative.components().peekable();
+        if components.peek().is_none() {
+            return Err("Raw file path is empty".to_string());
+        }
+        if !components.all(|c| matches!(c, Component::Normal(_))) {
+            return Err(format!(
+                "Raw file path '{path}' must be relative and may not contain '..'"
+            ));
+        }
+
+        let first = relative
+            .components()
+            .next()
+            .and_then(|c| c.as_os_str().to_str());
+        if first.is_some_and(|first| RESERVED_PATHS.contains(&first)) {
+            return Err(format!("Raw file path '{path}' is reserved"));
+        }
+        Ok(relative.to_path_buf())
+    }
+
+    /// The raw files in theme data as validated (relative path, contents) pairs
+    pub fn files(theme_data: &Value) -> Result<Vec<(PathBuf, String)>, String> {
+        let Some(files) = theme_data.get(Self::KEY) else {
+            return Ok(Vec::new());
+        };
+        let files = files
+            .as_object()
+            .ok_or_else(|| format!("'{}' must map file paths to contents", Self::KEY))?;
+
+        files
+            .iter()
+            .map(|(path, content)| {
+                let content = content
+                    .as_str()
+                    .ok_or_else(|| format!("Contents of raw file '{path}' must be a string"))?;
+                Ok((Self::validate_path(path)?, content.to_string()))
+            })
+            .collect()
+    }
+
+    /// Write every raw file in theme data into `theme_dir`
+    pub fn write_files(theme_dir: &Path, theme_data: &Value) -> Result<(), String> {
+        let files = Self::files(theme_data)?;
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let root = theme_dir
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve theme directory: {e}"))?;
+        for (relative, content) in files {
+            let path = theme_dir.join(&relative);
+            let parent = path.parent().unwrap_or(theme_dir);
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory for raw file: {e}"))?;
+
+            // A symlinked subdirectory could still lead outside the theme
+            let resolved = parent
+                .canonicalize()
+                .map_err(|e| format!("Failed to resolve raw file directory: {e}"))?;
+            if !resolved.starts_with(&root) || path.is_symlink() {
+                return Err(format!(
+                    "Raw file '{}' resolves outside the theme directory",
+                    relative.display()
+                ));
+            }
+
+            write_atomic(&path, content)
+                .map_err(|e| format!("Failed to write raw file '{}': {e}", relative.display()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_raw_files_are_written_and_escapes_rejected() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let theme_data = json!({
+            "files": {
+                "starship.toml": "[character]\nsuccess_symbol = \"[>](green)\"\n",
+                "zathura/zathurarc": "set default-bg \"#1a1b26\"\n"
+            }
+        });
+
+        RawFilesGenerator::write_files(temp.path(), &theme_data).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp.path().join("zathura/zathurarc")).unwrap(),
+            "set default-bg \"#1a1b26\"\n"
+        );
+        assert!(temp.path().join("starship.toml").is_file());
+
+        for path in [
+            "../escape.txt",
+            "/etc/passwd",
+            "a/../../b",
+            "",
+            "custom_theme.json",
+        ] {
+            let theme_data = json!({ "files": { path: "x" } });
+            assert!(
+                RawFilesGenerator::write_files(temp.path(), &theme_data).is_err(),
+                "{path:?} should be rejected"
+            );
+        }
+        assert!(RawFilesGenerator::files(&json!({ "files": { "a.conf": 1 } })).is_err());
+        assert!(RawFilesGenerator::files(&json!({})).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+use super::ConfigGenerator;
+use crate::services::themes::color_extraction::ColorExtractor;
+use serde_json::{json, Value};
+
+pub struct RofiGenerator;
+
+unsafe impl Send for RofiGenerator {}
+unsafe impl Sync for RofiGenerator {}
+
+/// Colors shared by the launcher generators (rofi and wofi), with their schema titles
+const LAUNCHER_COLORS: &[(&str, &str)] = &[
+    ("background", "Background"),
+    ("foreground", "Text"),
+    ("selected_background", "Selected background"),
+    ("selected_foreground", "Selected text"),
+    ("border", "Border"),
+    ("urgent", "Urgent"),
+];
+
+/// Resolved launcher colors: overrides from the app's `colors` section, else the theme palette
+pub(super) struct LauncherColors {
+    pub background: String,
+    pub foreground: String,
+    pub selected_background: String,
+    pub selected_foreground: String,
+    pub border: String,
+    pub urgent: String,
+}
+
+impl LauncherColors {
+    pub(super) fn resolve(theme_data: &Value, app: &str) -> Self {
+        let empty_obj = json!({});
+        let colors = theme_data
+            .get(app)
+            .and_then(|section| section.get("colors"))
+            .unwrap_or(&empty_obj);
+        let color = |key: &str| {
+            colors
+                .get(key)
+                .and_then(|c| c.as_str())
+                .and_then(ColorExtractor::normalize_color)
+        };
+
+        let palette = super::theme_palette(theme_data);
+        let accent = super::theme_accent(theme_data)
+            .and_then(ColorExtractor::normalize_color)
+            .or_else(|| palette.accent.clone())
+            .unwrap_or_else(|| palette.terminal.blue.clone());
+
+        let background = color("background").unwrap_or(palette.primary.background);
+        let foreground = color("foreground").unwrap_or(palette.primary.foreground);
+        Self {
+            selected_background: color("selected_background").unwrap_or_else(|| accent.clone()),
+            selected_foreground: color("selected_foreground").unwrap_or_else(|| background.clone()),
+            border: color("border").unwrap_or(accent),
+            urgent: color("urgent").unwrap_or(palette.terminal.red),
+            background,
+            foreground,
+        }
+    }
+}
+
+/// Schema for a launcher's `colors` section; unset colors follow the palette
+pub(super) fn launcher_schema() -> Value {
+    let properties: serde_json::Map<String, Value> = LAUNCHER_COLORS
+        .iter()
+        .map(|(name, title)| {
+            (
+                name.to_string(),
+                json!({ "type": "string", "format": "color", "title": title }),
+            )
+        })
+        .collect();
+
+    json!({
+        "type": "object",
+        "properties": {
+            "colors": {
+                "type": "object",
+                "properties": properties,
+            }
+        }
+    })
+}
+
+impl ConfigGenerator for RofiGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "rofi"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "rofi.rasi"
+    }
+
+    fn uses_accent(&self) -> bool {
+        true
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let LauncherColors {
+            background,
+            foreground,
+            selected_background,
+            selected_foreground,
+            border,
+            urgent,
+        } = LauncherColors::resolve(theme_data, "rofi");
+
+        Ok(format!(
+            r#"/* ────────────────────────────────────────────────────────────
+ * Omarchy Custom Theme for Rofi
+ * Generated by Omarchist
+ * ──────────────────────────────────────────────────────────── */
+
+* {{
+    background: {background};
+    foreground: {foreground};
+    selected-background: {selected_background};
+    selected-foreground: {selected_foreground};
+    border-color: {border};
+    urgent: {urgent};
+
+    background-color: transparent;
+    text-color: @foreground;
+}}
+
+window {{
+    background-color: @background;
+    border: 2px;
+    border-color: @border-color;
+}}
+
+inputbar {{
+    text-color: @foreground;
+}}
+
+element {{
+    background-color: transparent;
+    text-color: @foreground;
+}}
+
+element selected {{
+    background-color: @selected-background;
+    text-color: @selected-foreground;
+}}
+
+element urgent {{
+    text-color: @urgent;
+}}
+
+element-text, element-icon {{
+    background-color: inherit;
+    text-color: inherit;
+}}
+"#
+        ))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        launcher_schema()
+    }
+
+    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
+        Ok(json!({}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_rasi_uses_overrides_and_palette() {
+        let mut theme_data = super::super::tokyo_night_theme_data();
+        theme_data["rofi"] = json!({ "colors": { "selected_background": "#FF9E64" } });
+
+        let content = RofiGenerator.generate_config(&theme_data).unwrap();
+        assert!(content.contains("    background: #1a1b26;"));
+        assert!(content.contains("    foreground: #c0caf5;"));
+        assert!(content.contains("    selected-background: #ff9e64;"));
+        assert!(content.contains("    selected-foreground: #1a1b26;"));
+        assert!(content.contains("    urgent: #f7768e;"));
+        assert!(content.contains("element selected {"));
+        assert_eq!(content.matches('{').count(), content.matches('}').count());
+    }
+}
@@ -15,6 +15,10 @@ impl ConfigGenerator for SwayosdGenerator {
         "swayosd.css"
     }
 
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let swayosd = theme_data.get("swayosd").unwrap_or(&empty_obj);
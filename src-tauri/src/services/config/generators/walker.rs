@@ -15,6 +15,10 @@ impl ConfigGenerator for WalkerGenerator {
         "walker.css"
     }
 
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let walker = theme_data.get("walker").unwrap_or(&empty_obj);
@@ -15,6 +15,14 @@ impl ConfigGenerator for WaybarGenerator {
         "waybar.css"
     }
 
+    fn uses_accent(&self) -> bool {
+        true
+    }
+
+    fn supports_raw_append(&self) -> bool {
+        true
+    }
+
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let waybar = theme_data.get("waybar").unwrap_or(&empty_obj);
@@ -31,6 +39,9 @@ impl ConfigGenerator for WaybarGenerator {
             .get("foreground")
             .and_then(|f| f.as_str())
             .unwrap_or("#8a8a8d");
+        let accent_line = super::theme_accent(theme_data)
+            .map(|accent| format!("@define-color accent {accent};\n"))
+            .unwrap_or_default();
         Ok(format!(
             r#"/* ────────────────────────────────────────────────────────────
  * Omarchy Custom Theme for Waybar
@@ -40,7 +51,7 @@ impl ConfigGenerator for WaybarGenerator {
 
 @define-color background {bg};
 @define-color foreground {fg};
-"#
+{accent_line}"#
         ))
     }
 
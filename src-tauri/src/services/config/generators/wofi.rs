@@ -0,0 +1,109 @@
+use super::rofi::{launcher_schema, LauncherColors};
+use super::ConfigGenerator;
+use serde_json::{json, Value};
+
+pub struct WofiGenerator;
+
+unsafe impl Send for WofiGenerator {}
+unsafe impl Sync for WofiGenerator {}
+
+impl ConfigGenerator for WofiGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "wofi"
+    }
+
+    /// Wofi's `style.css`, named per app like the other theme files
+    fn get_file_name(&self) -> &'static str {
+        "wofi.css"
+    }
+
+    fn uses_accent(&self) -> bool {
+        true
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let LauncherColors {
+            background,
+            foreground,
+            selected_background,
+            selected_foreground,
+            border,
+            urgent,
+        } = LauncherColors::resolve(theme_data, "wofi");
+
+        Ok(format!(
+            r#"/* ────────────────────────────────────────────────────────────
+ * Omarchy Custom Theme for Wofi
+ * Generated by Omarchist
+ * ──────────────────────────────────────────────────────────── */
+
+@define-color background {background};
+@define-color foreground {foreground};
+@define-color selected_background {selected_background};
+@define-color selected_foreground {selected_foreground};
+@define-color border {border};
+@define-color urgent {urgent};
+
+window {{
+    background-color: @background;
+    color: @foreground;
+    border: 2px solid @border;
+}}
+
+#input {{
+    background-color: @background;
+    color: @foreground;
+    border: 1px solid @border;
+}}
+
+#entry {{
+    color: @foreground;
+}}
+
+#entry:selected {{
+    background-color: @selected_background;
+    color: @selected_foreground;
+}}
+
+#entry:selected #text {{
+    color: @selected_foreground;
+}}
+
+#entry.urgent #text {{
+    color: @urgent;
+}}
+"#
+        ))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        launcher_schema()
+    }
+
+    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
+        Ok(json!({}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_css_uses_overrides_and_palette() {
+        let mut theme_data = super::super::tokyo_night_theme_data();
+        theme_data["accent"] = json!("#bb9af7");
+        theme_data["wofi"] =
+            json!({ "colors": { "foreground": "#a9b1d6", "urgent": "not a color" } });
+
+        let content = WofiGenerator.generate_config(&theme_data).unwrap();
+        assert!(content.contains("@define-color background #1a1b26;"));
+        assert!(content.contains("@define-color foreground #a9b1d6;"));
+        assert!(content.contains("@define-color selected_background #bb9af7;"));
+        assert!(content.contains("@define-color urgent #f7768e;"));
+        assert_eq!(content.matches('{').count(), content.matches('}').count());
+
+        let defaults = WofiGenerator.generate_config(&json!({})).unwrap();
+        assert!(defaults.contains("@define-color background #"));
+    }
+}
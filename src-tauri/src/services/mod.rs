@@ -4,6 +4,7 @@ pub mod config;
 pub mod nvidia_detection;
 pub mod settings;
 pub mod themes;
+pub mod util;
 
 // Utility services that don't fit into specific domains
 pub mod cli_handler;
@@ -307,6 +307,16 @@ pub fn validate_settings(settings: &AppSettings) -> SettingsResult<()> {
         settings.auto_apply_theme
     );
 
+    // A zero background limit would reject every image
+    if settings.max_background_width == 0
+        || settings.max_background_height == 0
+        || settings.max_background_megabytes == 0
+    {
+        return Err(SettingsError::Validation(
+            "Background size limits must be greater than zero".to_string(),
+        ));
+    }
+
     // All validations passed
     log::debug!("Settings validation completed successfully");
     Ok(())
@@ -319,6 +329,12 @@ pub fn validate_and_sanitize_settings(settings: AppSettings) -> SettingsResult<A
     // Create a sanitized copy of the settings
     let sanitized_settings = AppSettings {
         auto_apply_theme: settings.auto_apply_theme, // Boolean is already safe
+        fork_system_theme_on_edit: settings.fork_system_theme_on_edit,
+        compact_theme_json: settings.compact_theme_json,
+        theme_backups_to_keep: settings.theme_backups_to_keep,
+        max_background_width: settings.max_background_width,
+        max_background_height: settings.max_background_height,
+        max_background_megabytes: settings.max_background_megabytes,
     };
 
     // Validate the sanitized settings
@@ -407,6 +423,12 @@ mod tests {
     fn test_app_settings_serialization() {
         let settings = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -431,19 +453,46 @@ mod tests {
     fn test_validate_settings_valid_cases() {
         let settings_true = AppSettings {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
         assert!(validate_settings(&settings_true).is_ok());
 
         let settings_false = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
         assert!(validate_settings(&settings_false).is_ok());
     }
 
+    #[test]
+    fn test_validate_settings_rejects_zero_background_limits() {
+        let settings = AppSettings {
+            max_background_width: 0,
+            ..AppSettings::default()
+        };
+        assert!(validate_settings(&settings).is_err());
+    }
+
     #[test]
     fn test_validate_and_sanitize_settings() {
         let settings = AppSettings {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
         let result = validate_and_sanitize_settings(settings.clone());
         assert!(result.is_ok());
@@ -451,6 +500,12 @@ mod tests {
 
         let settings = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
         let result = validate_and_sanitize_settings(settings.clone());
         assert!(result.is_ok());
@@ -474,6 +529,12 @@ mod tests {
 
         let test_settings = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         // Save settings
@@ -496,6 +557,12 @@ mod tests {
 
         let test_settings = AppSettings {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         // Save should create the directory
@@ -516,6 +583,12 @@ mod tests {
 
         let test_settings = AppSettings {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         // Save settings
@@ -543,6 +616,12 @@ mod tests {
 
         let initial_settings = AppSettings {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         // Save initial settings
@@ -562,6 +641,12 @@ mod tests {
         // Save updated settings
         let updated_settings = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
         save_settings_with_override(updated_settings, Some(temp_dir.path().to_path_buf()))
             .await
@@ -646,6 +731,12 @@ mod tests {
 
             let test_settings = AppSettings {
                 auto_apply_theme: true,
+                fork_system_theme_on_edit: true,
+                compact_theme_json: false,
+                theme_backups_to_keep: 5,
+                max_background_width: 8000,
+                max_background_height: 8000,
+                max_background_megabytes: 40,
             };
 
             // Save should fail
@@ -677,6 +768,12 @@ mod tests {
         let settings_file = settings_dir.join("settings.json");
         let legacy_settings = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
         let legacy_json = serde_json::to_string_pretty(&legacy_settings).unwrap();
         fs::write(&settings_file, legacy_json).unwrap();
@@ -702,6 +799,12 @@ mod tests {
             version: "2.0.0".to_string(),
             settings: AppSettings {
                 auto_apply_theme: false,
+                fork_system_theme_on_edit: true,
+                compact_theme_json: false,
+                theme_backups_to_keep: 5,
+                max_background_width: 8000,
+                max_background_height: 8000,
+                max_background_megabytes: 40,
             },
             metadata: SettingsMetadata {
                 created_at: Utc::now(),
@@ -725,9 +828,21 @@ mod tests {
 
         let settings1 = AppSettings {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
         let settings2 = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         // Start multiple saves concurrently
@@ -756,6 +871,12 @@ mod tests {
         // Save settings and verify the file format
         let test_settings = AppSettings {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         save_settings_with_override(test_settings.clone(), Some(temp_dir.path().to_path_buf()))
@@ -0,0 +1,137 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Omarchy's themes directory and the `current/theme` link that selects one of them
+pub struct ActiveTheme {
+    themes_dir: PathBuf,
+    current_link: PathBuf,
+}
+
+impl ActiveTheme {
+    /// Use the configured themes directory and omarchy's current theme link
+    pub fn new() -> Result<Self, String> {
+        let omarchy_dir = super::themes_dir::config_dir()?.join("omarchy");
+
+        Ok(Self::with_paths(
+            super::themes_dir::themes_dir()?,
+            omarchy_dir.join("current").join("theme"),
+        ))
+    }
+
+    /// Use explicit themes directory and current theme link locations
+    pub fn with_paths(themes_dir: PathBuf, current_link: PathBuf) -> Self {
+        Self {
+            themes_dir,
+            current_link,
+        }
+    }
+
+    /// Point the current theme link at `dir` in the themes directory. System themes are
+    /// symlinks themselves; the link targets the entry in the themes directory either way.
+    pub fn apply(&self, dir: &str) -> Result<PathBuf, String> {
+        let mut components = Path::new(dir).components();
+        if !matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        ) {
+            return Err(format!("Invalid theme directory name '{dir}'"));
+        }
+
+        let target = self.themes_dir.join(dir);
+        if !target.is_dir() {
+            return Err(format!("Theme '{dir}' not found"));
+        }
+
+        replace_symlink(&target, &self.current_link)
+            .map_err(|e| format!("Failed to set current theme to '{dir}': {e}"))?;
+        Ok(target)
+    }
+
+    /// Location of the current theme link
+    pub fn current_link(&self) -> &Path {
+        &self.current_link
+    }
+
+    /// Directory name of the theme the current theme link points at, if it is one of ours
+    pub fn current(&self) -> Option<String> {
+        let target = fs::read_link(&self.current_link).ok()?;
+        let name = target.file_name()?.to_str()?.to_string();
+        self.themes_dir.join(&name).is_dir().then_some(name)
+    }
+}
+
+/// Replace `link` with a symlink to `target` by renaming a freshly created link over it,
+/// so `link` never goes missing. An existing real directory at `link` is left alone.
+fn replace_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    if let Ok(metadata) = fs::symlink_metadata(link) {
+        if metadata.is_dir() {
+            return Err(io::Error::other(format!(
+                "{} is a directory, not a symlink",
+                link.display()
+            )));
+        }
+    }
+
+    let parent = link.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+
+    let file_name = link
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp_link = parent.join(format!(".{file_name}.omarchist-tmp"));
+    let _ = fs::remove_file(&temp_link);
+
+    std::os::unix::fs::symlink(target, &temp_link)?;
+    fs::rename(&temp_link, link).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_link);
+    })
+}
+
+/// Directory name of the currently applied theme
+#[tauri::command]
+pub async fn get_active_theme() -> Result<Option<String>, String> {
+    Ok(ActiveTheme::new()?.current())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_apply_switches_link_for_custom_and_system_themes() {
+        let temp = TempDir::new().unwrap();
+        let themes_dir = temp.path().join("themes");
+        let install_dir = temp.path().join("install").join("nord");
+        fs::create_dir_all(themes_dir.join("my-theme")).unwrap();
+        fs::create_dir_all(&install_dir).unwrap();
+        std::os::unix::fs::symlink(&install_dir, themes_dir.join("nord")).unwrap();
+
+        let active = ActiveTheme::with_paths(
+            themes_dir.clone(),
+            temp.path().join("current").join("theme"),
+        );
+        assert_eq!(active.current(), None);
+
+        active.apply("my-theme").unwrap();
+        assert_eq!(active.current().as_deref(), Some("my-theme"));
+
+        active.apply("nord").unwrap();
+        assert_eq!(active.current().as_deref(), Some("nord"));
+        assert_eq!(
+            fs::read_link(temp.path().join("current/theme")).unwrap(),
+            themes_dir.join("nord")
+        );
+        assert_eq!(
+            fs::read_dir(temp.path().join("current")).unwrap().count(),
+            1
+        );
+
+        for dir in ["missing", "../install", "", "nord/sub"] {
+            assert!(active.apply(dir).is_err(), "{dir:?} should be rejected");
+        }
+        assert_eq!(active.current().as_deref(), Some("nord"));
+    }
+}
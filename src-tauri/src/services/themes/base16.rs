@@ -0,0 +1,310 @@
+use super::color::{parse_color, to_hex};
+use super::color_extraction::ColorExtractor;
+use crate::types::ThemeColors;
+use serde_json::{json, Value};
+
+/// A base16 (or base24) color scheme with normalized `#rrggbb` colors
+#[derive(Debug, Clone, PartialEq)]
+pub struct Base16Scheme {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    /// `base00`..`base0F`, followed by `base10`..`base17` for base24 schemes
+    pub bases: Vec<String>,
+}
+
+impl Base16Scheme {
+    fn base(&self, index: usize) -> &str {
+        &self.bases[index]
+    }
+
+    /// A base24 bright slot, or its base16 counterpart when the scheme has no base24 colors
+    fn bright(&self, base24_index: usize, base16_index: usize) -> &str {
+        self.bases
+            .get(base24_index)
+            .unwrap_or(&self.bases[base16_index])
+    }
+}
+
+/// Read a scalar YAML value as text; unquoted all-digit hex colors arrive as numbers
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.trim().to_string()),
+        Value::Number(number) => Some(format!("{:0>6}", number.to_string())),
+        _ => None,
+    }
+}
+
+/// Parse a base16/base24 scheme in either the classic layout (`scheme:` and top-level
+/// `base00:` keys) or the newer one with a `palette:` map
+pub fn parse_scheme(yaml: &str) -> Result<Base16Scheme, String> {
+    let document: Value =
+        serde_yaml::from_str(yaml).map_err(|e| format!("Failed to parse base16 scheme: {e}"))?;
+    let document = document
+        .as_object()
+        .ok_or_else(|| "Base16 scheme must be a YAML mapping".to_string())?;
+    let palette = document
+        .get("palette")
+        .and_then(Value::as_object)
+        .unwrap_or(document);
+
+    let lookup = |key: &str| {
+        palette
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .and_then(|(_, value)| scalar_text(value))
+    };
+    let color = |index: usize| -> Result<Option<String>, String> {
+        let key = format!("base{index:02X}");
+        lookup(&key)
+            .map(|value| {
+                parse_color(&value)
+                    .map(to_hex)
+                    .map_err(|e| format!("Invalid {key}: {e}"))
+            })
+            .transpose()
+    };
+
+    let mut bases = Vec::with_capacity(24);
+    for index in 0..16 {
+        bases.push(
+            color(index)?.ok_or_else(|| format!("Base16 scheme is missing base{index:02X}"))?,
+        );
+    }
+    // base24 colors are only used when the whole extension is present
+    let extension = (0x10..0x18).map(color).collect::<Result<Vec<_>, _>>()?;
+    if extension.iter().all(Option::is_some) {
+        bases.extend(extension.into_iter().flatten());
+    }
+
+    let text = |key: &str| {
+        document
+            .get(key)
+            .and_then(scalar_text)
+            .filter(|text| !text.is_empty())
+    };
+    Ok(Base16Scheme {
+        name: text("name").or_else(|| text("scheme")),
+        author: text("author"),
+        bases,
+    })
+}
+
+/// Theme data for a scheme using the standard base16 terminal mapping: base00 background,
+/// base05 foreground, base08..base0E for the colors and base0D as the accent
+pub fn theme_data_from_scheme(scheme: &Base16Scheme) -> Value {
+    let base = |index: usize| scheme.base(index);
+    let mut theme_data = json!({
+        "alacritty": {
+            "colors": {
+                "accent": base(0x0D),
+                "primary": {
+                    "background": base(0x00),
+                    "foreground": base(0x05),
+                    "dim_foreground": base(0x04)
+                },
+                "cursor": { "text": base(0x00), "cursor": base(0x05) },
+                "selection": { "background": base(0x02) },
+                "normal": {
+                    "black": base(0x00),
+                    "red": base(0x08),
+                    "green": base(0x0B),
+                    "yellow": base(0x0A),
+                    "blue": base(0x0D),
+                    "magenta": base(0x0E),
+                    "cyan": base(0x0C),
+                    "white": base(0x05)
+                },
+                "bright": {
+                    "black": base(0x03),
+                    "red": scheme.bright(0x12, 0x08),
+                    "green": scheme.bright(0x14, 0x0B),
+                    "yellow": scheme.bright(0x13, 0x0A),
+                    "blue": scheme.bright(0x16, 0x0D),
+                    "magenta": scheme.bright(0x17, 0x0E),
+                    "cyan": scheme.bright(0x15, 0x0C),
+                    "white": base(0x07)
+                }
+            }
+        },
+        "waybar": {
+            "colors": {
+                "main": { "background": base(0x00), "foreground": base(0x05) }
+            }
+        }
+    });
+    if let Some(author) = &scheme.author {
+        theme_data["meta"] = json!({ "author": author });
+    }
+    theme_data
+}
+
+/// Build a base16 scheme from a theme palette by reversing the import mapping. Grayscale
+/// slots the palette doesn't carry are interpolated between background and foreground,
+/// base09 is blended from red and yellow and base0F from red and the background.
+pub fn scheme_from_colors(
+    name: &str,
+    author: Option<&str>,
+    colors: &ThemeColors,
+) -> Result<Base16Scheme, String> {
+    let hex = |slot: &str, value: &str| {
+        parse_color(value)
+            .map(to_hex)
+            .map_err(|e| format!("Invalid {slot} color: {e}"))
+    };
+    let background = hex("background", &colors.primary.background)?;
+    let foreground = hex("foreground", &colors.primary.foreground)?;
+    let terminal = &colors.terminal;
+    let red = hex("red", &terminal.red)?;
+    let yellow = hex("yellow", &terminal.yellow)?;
+
+    // base06/base07 continue past the foreground towards white (or black on light themes)
+    let is_dark = ColorExtractor::is_dark(colors).unwrap_or(true);
+    let extreme = if is_dark { "#ffffff" } else { "#000000" };
+    let gray = |amount: f32| ColorExtractor::mix(&background, &foreground, amount);
+
+    let bases = vec![
+        background.clone(),
+        gray(0.08),
+        gray(0.17),
+        gray(0.5),
+        gray(0.83),
+        foreground.clone(),
+        ColorExtractor::mix(&foreground, extreme, 0.5),
+        ColorExtractor::mix(&foreground, extreme, 0.85),
+        red.clone(),
+        ColorExtractor::mix(&red, &yellow, 0.5),
+        yellow,
+        hex("green", &terminal.green)?,
+        hex("cyan", &terminal.cyan)?,
+        hex("blue", &terminal.blue)?,
+        hex("magenta", &terminal.magenta)?,
+        ColorExtractor::mix(&red, &background, 0.35),
+    ];
+
+    Ok(Base16Scheme {
+        name: Some(name.to_string()),
+        author: author.map(str::to_string),
+        bases,
+    })
+}
+
+/// Write a scheme in the classic base16 YAML layout with unprefixed hex colors
+pub fn scheme_to_yaml(scheme: &Base16Scheme) -> String {
+    // A JSON string literal is also a valid double-quoted YAML scalar
+    let quote = |text: &str| Value::String(text.to_string()).to_string();
+
+    let mut yaml = String::new();
+    if let Some(name) = &scheme.name {
+        yaml.push_str(&format!("scheme: {}\n", quote(name)));
+    }
+    if let Some(author) = &scheme.author {
+        yaml.push_str(&format!("author: {}\n", quote(author)));
+    }
+    for (index, color) in scheme.bases.iter().enumerate() {
+        let color = color.trim_start_matches('#');
+        yaml.push_str(&format!("base{index:02X}: \"{color}\"\n"));
+    }
+    yaml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trimmed from the classic base16 "Tomorrow Night" scheme
+    const TOMORROW_NIGHT: &str = r#"
+scheme: "Tomorrow Night"
+author: "Chris Kempson (http://chriskempson.com)"
+base00: "1d1f21"
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666"
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"#;
+
+    #[test]
+    fn test_parse_classic_scheme_and_map_to_terminal_colors() {
+        let scheme = parse_scheme(TOMORROW_NIGHT).unwrap();
+        assert_eq!(scheme.name.as_deref(), Some("Tomorrow Night"));
+        assert_eq!(scheme.bases.len(), 16);
+        assert_eq!(scheme.bases[0x0F], "#a3685a");
+
+        let theme_data = theme_data_from_scheme(&scheme);
+        let colors = &theme_data["alacritty"]["colors"];
+        assert_eq!(colors["primary"]["background"], "#1d1f21");
+        assert_eq!(colors["primary"]["foreground"], "#c5c8c6");
+        assert_eq!(colors["normal"]["red"], "#cc6666");
+        assert_eq!(colors["normal"]["green"], "#b5bd68");
+        assert_eq!(colors["normal"]["yellow"], "#f0c674");
+        assert_eq!(colors["normal"]["blue"], "#81a2be");
+        assert_eq!(colors["normal"]["magenta"], "#b294bb");
+        assert_eq!(colors["normal"]["cyan"], "#8abeb7");
+        assert_eq!(colors["bright"]["black"], "#969896");
+        assert_eq!(colors["bright"]["red"], "#cc6666");
+        assert_eq!(
+            theme_data["meta"]["author"],
+            "Chris Kempson (http://chriskempson.com)"
+        );
+    }
+
+    #[test]
+    fn test_parse_palette_layout_with_base24_brights() {
+        let mut yaml = String::from("system: \"base24\"\nname: \"Test\"\npalette:\n");
+        for index in 0..0x18 {
+            yaml.push_str(&format!(
+                "  base{index:02X}: \"#{index:02x}{index:02x}{index:02x}\"\n"
+            ));
+        }
+        let scheme = parse_scheme(&yaml).unwrap();
+        assert_eq!(scheme.bases.len(), 24);
+
+        let theme_data = theme_data_from_scheme(&scheme);
+        assert_eq!(
+            theme_data["alacritty"]["colors"]["bright"]["red"],
+            "#121212"
+        );
+        assert!(theme_data.get("meta").is_none());
+
+        assert!(parse_scheme("base00: \"000000\"").is_err());
+        assert!(parse_scheme("- not\n- a mapping").is_err());
+    }
+
+    #[test]
+    fn test_export_round_trips_mapped_slots() {
+        let scheme = parse_scheme(TOMORROW_NIGHT).unwrap();
+        let theme_data = theme_data_from_scheme(&scheme);
+        let colors = ColorExtractor::extract_from_custom_theme(&theme_data).unwrap();
+
+        let exported = scheme_from_colors("Tomorrow \"Night\"", None, &colors).unwrap();
+        let yaml = scheme_to_yaml(&exported);
+        assert!(yaml.contains("base0D: \"81a2be\""));
+
+        let reparsed = parse_scheme(&yaml).unwrap();
+        assert_eq!(reparsed.name.as_deref(), Some("Tomorrow \"Night\""));
+        assert_eq!(reparsed.author, None);
+        for index in [0x00, 0x05, 0x08, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E] {
+            assert_eq!(
+                reparsed.bases[index], scheme.bases[index],
+                "base{index:02X}"
+            );
+        }
+
+        // Derived grayscale slots get lighter from base00 to base07 on a dark theme
+        let luminance =
+            |index: usize| ColorExtractor::relative_luminance(&reparsed.bases[index]).unwrap();
+        for index in 1..8 {
+            assert!(luminance(index) > luminance(index - 1), "base{index:02X}");
+        }
+    }
+}
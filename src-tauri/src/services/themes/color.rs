@@ -0,0 +1,408 @@
+use serde_json::Value;
+
+/// An opaque 8-bit RGB color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// Parse a color written as hex (`#rgb`, `#rrggbb`, `#rrggbbaa`, '#' optional), `rgb()`,
+/// `rgba()` or `hsl()`. Out-of-range channels are clamped and alpha is dropped.
+pub fn parse_color(input: &str) -> Result<Rgb, String> {
+    let color = input.trim().to_ascii_lowercase();
+    let invalid = || format!("Unrecognized color '{}'", input.trim());
+
+    if let Some((name, args)) = function_args(&color) {
+        return match name {
+            "rgb" | "rgba" => parse_rgb_args(&args),
+            "hsl" | "hsla" => parse_hsl_args(&args),
+            _ => None,
+        }
+        .ok_or_else(invalid);
+    }
+    parse_hex(&color).ok_or_else(invalid)
+}
+
+/// CSS named colors, sorted by name
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+/// Look up a CSS named color such as `rebeccapurple`, ignoring case
+pub fn named_color(name: &str) -> Option<Rgb> {
+    let name = name.trim().to_ascii_lowercase();
+    let index = NAMED_COLORS
+        .binary_search_by(|(candidate, _)| candidate.cmp(&name.as_str()))
+        .ok()?;
+    let [_, r, g, b] = NAMED_COLORS[index].1.to_be_bytes();
+    Some(Rgb { r, g, b })
+}
+
+/// Normalize a color given as a CSS name or in any form `parse_color` accepts to `#rrggbb`
+pub fn normalize_color(input: &str) -> Result<String, String> {
+    match named_color(input) {
+        Some(color) => Ok(to_hex(color)),
+        None => parse_color(input).map(to_hex),
+    }
+}
+
+/// Format a color as `#rrggbb`
+pub fn to_hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// Split `name(a, b, c)` (commas, spaces or '/' between arguments) into its parts
+fn function_args(color: &str) -> Option<(&str, Vec<&str>)> {
+    let (name, rest) = color.split_once('(')?;
+    let args = rest.strip_suffix(')')?;
+    let args = args
+        .split([',', '/', ' '])
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    Some((name.trim(), args))
+}
+
+fn parse_hex(color: &str) -> Option<Rgb> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex[..6].to_string(),
+        _ => return None,
+    };
+
+    let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+    Some(Rgb {
+        r: channel(0)?,
+        g: channel(2)?,
+        b: channel(4)?,
+    })
+}
+
+/// A number with an optional '%' suffix, as a fraction of `max` when it is a percentage
+fn parse_number(arg: &str, max: f64) -> Option<f64> {
+    match arg.strip_suffix('%') {
+        Some(percent) => Some(percent.trim().parse::<f64>().ok()? / 100.0 * max),
+        None => arg.parse().ok(),
+    }
+    .filter(|n: &f64| n.is_finite())
+}
+
+fn to_channel(value: f64) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+fn parse_rgb_args(args: &[&str]) -> Option<Rgb> {
+    if !matches!(args.len(), 3 | 4) {
+        return None;
+    }
+    let channel = |i: usize| parse_number(args[i], 255.0).map(to_channel);
+    Some(Rgb {
+        r: channel(0)?,
+        g: channel(1)?,
+        b: channel(2)?,
+    })
+}
+
+fn parse_hsl_args(args: &[&str]) -> Option<Rgb> {
+    if !matches!(args.len(), 3 | 4) {
+        return None;
+    }
+    // Hue in degrees; saturation and lightness in percent, with or without '%'
+    let number = |arg: &str| arg.parse::<f64>().ok().filter(|n| n.is_finite());
+    let hue = number(args[0].strip_suffix("deg").unwrap_or(args[0]))?;
+    let percent = |arg: &str| Some(number(arg.trim_end_matches('%'))?.clamp(0.0, 100.0) / 100.0);
+    let saturation = percent(args[1])?;
+    let lightness = percent(args[2])?;
+
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    Some(Rgb {
+        r: to_channel((r + m) * 255.0),
+        g: to_channel((g + m) * 255.0),
+        b: to_channel((b + m) * 255.0),
+    })
+}
+
+/// Rewrite `rgb()`/`rgba()`/`hsl()` and short `#rgb` strings anywhere in theme data as
+/// `#rrggbb`. Hex with an alpha channel (`#rrggbbaa`) and bare hex without `#` are left
+/// as written: apps like walker take alpha hex, and bare hex can't be told apart from
+/// ordinary words. Returns whether anything changed.
+pub fn normalize_color_values(value: &mut Value) -> bool {
+    match value {
+        Value::String(text) => {
+            let trimmed = text.trim();
+            let is_short_hex = trimmed.len() == 4 && trimmed.starts_with('#');
+            if !is_short_hex && !trimmed.ends_with(')') {
+                return false;
+            }
+            match parse_color(trimmed) {
+                Ok(color) => {
+                    *text = to_hex(color);
+                    true
+                },
+                Err(_) => false,
+            }
+        },
+        Value::Array(items) => items.iter_mut().fold(false, |changed, item| {
+            normalize_color_values(item) | changed
+        }),
+        Value::Object(map) => map.values_mut().fold(false, |changed, item| {
+            normalize_color_values(item) | changed
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn hex(input: &str) -> String {
+        to_hex(parse_color(input).unwrap())
+    }
+
+    #[test]
+    fn test_parse_color_hex_forms() {
+        assert_eq!(hex("#1E1E2E"), "#1e1e2e");
+        assert_eq!(hex("1e1e2e"), "#1e1e2e");
+        assert_eq!(hex("#fa0"), "#ffaa00");
+        assert_eq!(hex("#1e1e2e80"), "#1e1e2e");
+        assert_eq!(hex("EAEAEA88"), "#eaeaea");
+        for invalid in ["#12345", "#ggg", "", "blue"] {
+            assert!(parse_color(invalid).is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_color_rgb_and_rgba() {
+        assert_eq!(
+            parse_color("rgb(30, 30, 46)"),
+            Ok(Rgb {
+                r: 30,
+                g: 30,
+                b: 46
+            })
+        );
+        assert_eq!(hex("RGBA(30, 30, 46, 0.5)"), "#1e1e2e");
+        assert_eq!(hex("rgb(30 30 46 / 50%)"), "#1e1e2e");
+        assert_eq!(hex("rgb(100%, 0%, 50%)"), "#ff0080");
+        // Out-of-range channels are clamped
+        assert_eq!(hex("rgb(300, -20, 46)"), "#ff002e");
+        assert_eq!(hex("rgb(150%, 0, 0)"), "#ff0000");
+        assert!(parse_color("rgb(30, 30)").is_err());
+        assert!(parse_color("rgb(a, b, c)").is_err());
+    }
+
+    #[test]
+    fn test_parse_color_hsl() {
+        assert_eq!(hex("hsl(240, 21%, 15%)"), "#1e1e2e");
+        assert_eq!(hex("hsl(0, 100%, 50%)"), "#ff0000");
+        assert_eq!(hex("hsl(120deg 100% 25%)"), "#008000");
+        assert_eq!(hex("hsla(0, 0%, 100%, 0.3)"), "#ffffff");
+        // Hue wraps around, saturation and lightness are clamped
+        assert_eq!(hex("hsl(480, 100%, 50%)"), "#00ff00");
+        assert_eq!(hex("hsl(0, 150%, 120%)"), "#ffffff");
+        assert!(parse_color("hsl(0, 50%)").is_err());
+    }
+
+    #[test]
+    fn test_normalize_color_accepts_names_and_hex() {
+        assert_eq!(normalize_color("red").unwrap(), "#ff0000");
+        assert_eq!(normalize_color(" RebeccaPurple ").unwrap(), "#663399");
+        assert_eq!(normalize_color("#ABC").unwrap(), "#aabbcc");
+        assert_eq!(normalize_color("#1e1e2e").unwrap(), "#1e1e2e");
+        assert!(normalize_color("#GGG").is_err());
+        assert!(normalize_color("reddish").is_err());
+        assert!(NAMED_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_normalize_color_values() {
+        let mut theme_data = json!({
+            "alacritty": {
+                "colors": {
+                    "primary": { "background": "rgb(30, 30, 46)", "foreground": "#cdd6f4" },
+                    "normal": { "red": "hsl(0, 100%, 50%)", "green": "#0f0" }
+                }
+            },
+            "walker": { "border": "EAEAEA88" },
+            "hyprland": { "shadow": "#1a1a1aee" },
+            "waybar": { "font": "JetBrains Mono", "note": "see (docs)" }
+        });
+
+        assert!(normalize_color_values(&mut theme_data));
+        let colors = &theme_data["alacritty"]["colors"];
+        assert_eq!(colors["primary"]["background"], "#1e1e2e");
+        assert_eq!(colors["primary"]["foreground"], "#cdd6f4");
+        assert_eq!(colors["normal"]["red"], "#ff0000");
+        assert_eq!(colors["normal"]["green"], "#00ff00");
+        assert_eq!(theme_data["walker"]["border"], "EAEAEA88");
+        assert_eq!(theme_data["hyprland"]["shadow"], "#1a1a1aee");
+        assert_eq!(theme_data["waybar"]["note"], "see (docs)");
+        assert!(!normalize_color_values(&mut theme_data));
+    }
+}
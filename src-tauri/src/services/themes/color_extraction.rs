@@ -1,5 +1,8 @@
+use super::color::{parse_color, to_hex};
 use crate::types::{PrimaryColors, TerminalColors, ThemeColors};
+use regex::Regex;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use toml;
@@ -23,6 +26,7 @@ impl ColorExtractor {
         let bright = colors.get("bright");
 
         let terminal_colors = Self::extract_terminal_colors(normal, bright)?;
+        let accent = Self::extract_accent(colors, &foreground, &terminal_colors);
 
         Some(ThemeColors {
             primary: PrimaryColors {
@@ -30,6 +34,7 @@ impl ColorExtractor {
                 foreground,
             },
             terminal: terminal_colors,
+            accent,
         })
     }
 
@@ -50,6 +55,7 @@ impl ColorExtractor {
         let bright = colors.get("bright");
 
         let terminal_colors = Self::extract_terminal_colors(normal, bright)?;
+        let accent = Self::extract_accent(colors, &foreground, &terminal_colors);
 
         Some(ThemeColors {
             primary: PrimaryColors {
@@ -57,28 +63,124 @@ impl ColorExtractor {
                 foreground,
             },
             terminal: terminal_colors,
+            accent,
         })
     }
 
-    /// Extract terminal colors with fallback logic (normal -> bright -> defaults)
+    /// Extract colors from a kitty config: `background`, `foreground` and `color0`..`color15`.
+    /// Comment lines are skipped and later directives win, as in kitty itself.
+    pub fn extract_from_kitty_config(config_path: &Path) -> Option<ThemeColors> {
+        let content = fs::read_to_string(config_path).ok()?;
+
+        let mut directives = HashMap::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+                directives.insert(key, value);
+            }
+        }
+        let color = |key: &str| {
+            directives
+                .get(key)
+                .and_then(|value| Self::normalize_color(value))
+        };
+
+        let background = color("background")?;
+        let foreground = color("foreground")?;
+        let ansi: Vec<Option<String>> = (0..16).map(|i| color(&format!("color{i}"))).collect();
+
+        Self::from_ansi_colors(background, foreground, &ansi)
+    }
+
+    /// Extract colors from a WezTerm color table (`wezterm.lua` or `colors.lua`):
+    /// `background`, `foreground` and the `ansi`/`brights` arrays.
+    /// This is a line scan for those keys, not a Lua parser; the first definition wins.
+    pub fn extract_from_wezterm_config(config_path: &Path) -> Option<ThemeColors> {
+        let content = fs::read_to_string(config_path).ok()?;
+
+        // Drop `--` comments so commented-out colors are ignored
+        let code: String = content
+            .lines()
+            .map(|line| line.split_once("--").map_or(line, |(code, _)| code))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let string_value = |key: &str| {
+            let pattern = format!(r#"\b{key}\s*=\s*["']([^"']+)["']"#);
+            let captures = Regex::new(&pattern).ok()?.captures(&code)?;
+            Self::normalize_color(&captures[1])
+        };
+        let array_values = |key: &str| -> Vec<Option<String>> {
+            let pattern = format!(r"\b{key}\s*=\s*\{{([^}}]*)\}}");
+            let Some(captures) = Regex::new(&pattern).ok().and_then(|re| re.captures(&code)) else {
+                return Vec::new();
+            };
+            let quoted = Regex::new(r#"["']([^"']+)["']"#).expect("valid regex");
+            quoted
+                .captures_iter(&captures[1])
+                .map(|c| Self::normalize_color(&c[1]))
+                .collect()
+        };
+
+        let background = string_value("background")?;
+        let foreground = string_value("foreground")?;
+        let mut ansi = array_values("ansi");
+        ansi.resize(8, None);
+        ansi.extend(array_values("brights"));
+
+        Self::from_ansi_colors(background, foreground, &ansi)
+    }
+
+    /// Build theme colors from a 16-entry ANSI palette (normal 0-7, bright 8-15).
+    /// Each terminal slot prefers its normal color and falls back to the bright one.
+    fn from_ansi_colors(
+        background: String,
+        foreground: String,
+        ansi: &[Option<String>],
+    ) -> Option<ThemeColors> {
+        let slot = |index: usize| {
+            ansi.get(index)
+                .cloned()
+                .flatten()
+                .or_else(|| ansi.get(index + 8).cloned().flatten())
+        };
+
+        let terminal = TerminalColors {
+            red: slot(1)?,
+            green: slot(2)?,
+            yellow: slot(3)?,
+            blue: slot(4)?,
+            magenta: slot(5)?,
+            cyan: slot(6)?,
+        };
+        let accent = Self::pick_accent(&terminal, &foreground);
+
+        Some(ThemeColors {
+            primary: PrimaryColors {
+                background,
+                foreground,
+            },
+            terminal,
+            accent,
+        })
+    }
+
+    /// Extract terminal colors, preferring the normal set over the bright one
     fn extract_terminal_colors(
         normal: Option<&Value>,
         bright: Option<&Value>,
     ) -> Option<TerminalColors> {
         let color_source = normal.or(bright)?;
 
-        let red = Self::normalize_color(color_source.get("red")?.as_str()?)
-            .or_else(|| Self::get_fallback_terminal_color("red"))?;
-        let green = Self::normalize_color(color_source.get("green")?.as_str()?)
-            .or_else(|| Self::get_fallback_terminal_color("green"))?;
-        let yellow = Self::normalize_color(color_source.get("yellow")?.as_str()?)
-            .or_else(|| Self::get_fallback_terminal_color("yellow"))?;
-        let blue = Self::normalize_color(color_source.get("blue")?.as_str()?)
-            .or_else(|| Self::get_fallback_terminal_color("blue"))?;
-        let magenta = Self::normalize_color(color_source.get("magenta")?.as_str()?)
-            .or_else(|| Self::get_fallback_terminal_color("magenta"))?;
-        let cyan = Self::normalize_color(color_source.get("cyan")?.as_str()?)
-            .or_else(|| Self::get_fallback_terminal_color("cyan"))?;
+        let red = Self::normalize_color(color_source.get("red")?.as_str()?)?;
+        let green = Self::normalize_color(color_source.get("green")?.as_str()?)?;
+        let yellow = Self::normalize_color(color_source.get("yellow")?.as_str()?)?;
+        let blue = Self::normalize_color(color_source.get("blue")?.as_str()?)?;
+        let magenta = Self::normalize_color(color_source.get("magenta")?.as_str()?)?;
+        let cyan = Self::normalize_color(color_source.get("cyan")?.as_str()?)?;
 
         Some(TerminalColors {
             red,
@@ -90,6 +192,402 @@ impl ColorExtractor {
         })
     }
 
+    /// Pick an accent color: a dedicated `accent` key wins, otherwise the most vivid terminal color
+    fn extract_accent(
+        colors: &Value,
+        foreground: &str,
+        terminal: &TerminalColors,
+    ) -> Option<String> {
+        let dedicated = colors
+            .get("accent")
+            .or_else(|| colors.get("primary").and_then(|p| p.get("accent")))
+            .and_then(|a| a.as_str())
+            .and_then(Self::normalize_color);
+
+        dedicated.or_else(|| Self::pick_accent(terminal, foreground))
+    }
+
+    /// Choose the most saturated terminal color that differs from the foreground
+    pub fn pick_accent(terminal: &TerminalColors, foreground: &str) -> Option<String> {
+        let candidates = [
+            &terminal.red,
+            &terminal.green,
+            &terminal.yellow,
+            &terminal.blue,
+            &terminal.magenta,
+            &terminal.cyan,
+        ];
+
+        let mut best: Option<(&String, f32, f32)> = None;
+        for color in candidates {
+            if color.eq_ignore_ascii_case(foreground) {
+                continue;
+            }
+            let Some((saturation, chroma)) = Self::saturation_and_chroma(color) else {
+                continue;
+            };
+            let is_better = match best {
+                Some((_, best_sat, best_chroma)) => {
+                    saturation > best_sat || (saturation == best_sat && chroma > best_chroma)
+                },
+                None => true,
+            };
+            if is_better {
+                best = Some((color, saturation, chroma));
+            }
+        }
+
+        best.map(|(color, _, _)| color.clone())
+    }
+
+    /// Parse a `#rgb` or `#rrggbb` color into its channels
+    pub fn hex_to_rgb(color: &str) -> Option<(u8, u8, u8)> {
+        let hex = color.strip_prefix('#')?;
+        let expanded: String = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 => hex.to_string(),
+            _ => return None,
+        };
+
+        let channel = |i: usize| u8::from_str_radix(expanded.get(i..i + 2)?, 16).ok();
+        Some((channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// HSL saturation and chroma of a normalized hex color
+    fn saturation_and_chroma(color: &str) -> Option<(f32, f32)> {
+        let (r, g, b) = Self::hex_to_rgb(color)?;
+        let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if chroma == 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        Some((saturation, chroma))
+    }
+
+    /// WCAG relative luminance of a hex color (0 for black, 1 for white)
+    pub fn relative_luminance(color: &str) -> Option<f64> {
+        let (r, g, b) = Self::hex_to_rgb(&Self::normalize_color(color)?)?;
+        let linear = |channel: u8| {
+            let c = f64::from(channel) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        Some(0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b))
+    }
+
+    /// Whether a palette is dark: its background is closer to black than to white in
+    /// contrast terms (relative luminance below ~0.18). `None` if the background is unparseable.
+    pub fn is_dark(colors: &ThemeColors) -> Option<bool> {
+        // Luminance at which black and white text have equal contrast
+        const CROSSOVER: f64 = 0.179;
+        Self::relative_luminance(&colors.primary.background).map(|l| l < CROSSOVER)
+    }
+
+    /// WCAG contrast ratio between two hex colors (1 to 21); 3- and 6-digit colors are
+    /// accepted with or without the leading '#'
+    pub fn contrast_ratio(a: &str, b: &str) -> Option<f64> {
+        let (la, lb) = (Self::relative_luminance(a)?, Self::relative_luminance(b)?);
+        let (lighter, darker) = if la > lb { (la, lb) } else { (lb, la) };
+        Some((lighter + 0.05) / (darker + 0.05))
+    }
+
+    /// Convert a hex color to HSL (hue in degrees, saturation and lightness in 0..=1)
+    pub fn hex_to_hsl(color: &str) -> Option<(f32, f32, f32)> {
+        let (r, g, b) = Self::hex_to_rgb(color)?;
+        let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        if chroma == 0.0 {
+            return Some((0.0, 0.0, lightness));
+        }
+
+        let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / chroma + 2.0)
+        } else {
+            60.0 * ((r - g) / chroma + 4.0)
+        };
+
+        Some((hue, saturation, lightness))
+    }
+
+    /// Convert HSL (hue in degrees, saturation and lightness in 0..=1) to a `#rrggbb` color
+    pub fn hsl_to_hex(hue: f32, saturation: f32, lightness: f32) -> String {
+        let hue = hue.rem_euclid(360.0);
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+        let m = lightness - chroma / 2.0;
+        let (r, g, b) = match hue as u32 / 60 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let channel = |value: f32| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        format!("#{:02x}{:02x}{:02x}", channel(r), channel(g), channel(b))
+    }
+
+    /// Blend two hex colors, `amount` of the way from `from` to `to`.
+    /// Returns `from` unchanged when either color can't be parsed.
+    pub fn mix(from: &str, to: &str, amount: f32) -> String {
+        let (Some(a), Some(b)) = (Self::hex_to_rgb(from), Self::hex_to_rgb(to)) else {
+            return from.to_string();
+        };
+        let channel =
+            |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * amount).round() as u8;
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(a.0, b.0),
+            channel(a.1, b.1),
+            channel(a.2, b.2)
+        )
+    }
+
+    /// Derive normal and bright terminal palettes around an accent color.
+    /// Hues are shifted so the slot nearest the accent lands on it exactly.
+    pub fn palette_from_accent(accent: &str) -> Option<(TerminalColors, TerminalColors)> {
+        const SLOTS: [f32; 6] = [0.0, 120.0, 45.0, 215.0, 300.0, 185.0]; // r, g, y, b, m, c
+
+        let (hue, saturation, lightness) = Self::hex_to_hsl(accent)?;
+        let hue_distance = |slot: f32| {
+            let d = (slot - hue).rem_euclid(360.0);
+            if d > 180.0 {
+                d - 360.0
+            } else {
+                d
+            }
+        };
+        let nearest = SLOTS
+            .iter()
+            .copied()
+            .min_by(|a, b| hue_distance(*a).abs().total_cmp(&hue_distance(*b).abs()))?;
+        let shift = -hue_distance(nearest);
+
+        let saturation = saturation.max(0.45);
+        let lightness = lightness.clamp(0.45, 0.65);
+        let build = |lightness: f32| {
+            let [red, green, yellow, blue, magenta, cyan] =
+                SLOTS.map(|slot| Self::hsl_to_hex(slot + shift, saturation, lightness));
+            TerminalColors {
+                red,
+                green,
+                yellow,
+                blue,
+                magenta,
+                cyan,
+            }
+        };
+
+        Some((build(lightness), build((lightness + 0.1).min(0.8))))
+    }
+
+    /// Invert a dark palette into a light one in HSL: background and foreground lightness
+    /// are flipped, and terminal colors keep their hue and saturation but are darkened
+    /// until they stay readable on the new background. Returns the palette and a darker
+    /// "bright" row for the terminal.
+    pub fn light_variant(colors: &ThemeColors) -> (ThemeColors, TerminalColors) {
+        const MIN_TEXT_CONTRAST: f64 = 4.5;
+        const MIN_COLOR_CONTRAST: f64 = 3.0;
+
+        // Lower lightness step by step until `color` reaches `min_contrast` on `background`
+        let darken_until = |color: &str, background: &str, min_contrast: f64| {
+            let Some((h, s, mut l)) = Self::hex_to_hsl(color) else {
+                return color.to_string();
+            };
+            let mut candidate = Self::hsl_to_hex(h, s, l);
+            while l > 0.0
+                && Self::contrast_ratio(&candidate, background).unwrap_or(0.0) < min_contrast
+            {
+                l = (l - 0.02).max(0.0);
+                candidate = Self::hsl_to_hex(h, s, l);
+            }
+            candidate
+        };
+
+        let background =
+            Self::adjust_lightness(&colors.primary.background, |l| (1.0 - l).max(0.94));
+        let foreground = darken_until(
+            &Self::adjust_lightness(&colors.primary.foreground, |l| (1.0 - l).min(0.25)),
+            &background,
+            MIN_TEXT_CONTRAST,
+        );
+
+        let adapt = |color: &str, lightness_cap: f32, min_contrast: f64| {
+            let capped = Self::adjust_lightness(color, |l| l.min(lightness_cap));
+            darken_until(&capped, &background, min_contrast)
+        };
+        let row = |lightness_cap: f32, min_contrast: f64| TerminalColors {
+            red: adapt(&colors.terminal.red, lightness_cap, min_contrast),
+            green: adapt(&colors.terminal.green, lightness_cap, min_contrast),
+            yellow: adapt(&colors.terminal.yellow, lightness_cap, min_contrast),
+            blue: adapt(&colors.terminal.blue, lightness_cap, min_contrast),
+            magenta: adapt(&colors.terminal.magenta, lightness_cap, min_contrast),
+            cyan: adapt(&colors.terminal.cyan, lightness_cap, min_contrast),
+        };
+
+        let terminal = row(0.45, MIN_COLOR_CONTRAST);
+        let bright = row(0.35, MIN_TEXT_CONTRAST);
+        let accent = colors
+            .accent
+            .as_deref()
+            .map(|accent| adapt(accent, 0.45, MIN_COLOR_CONTRAST));
+
+        (
+            ThemeColors {
+                primary: PrimaryColors {
+                    background,
+                    foreground,
+                },
+                terminal,
+                accent,
+            },
+            bright,
+        )
+    }
+
+    /// Apply `adjust` to a color's HSL lightness; unparseable colors are returned unchanged
+    fn adjust_lightness(color: &str, adjust: impl Fn(f32) -> f32) -> String {
+        match Self::hex_to_hsl(color) {
+            Some((h, s, l)) => Self::hsl_to_hex(h, s, adjust(l)),
+            None => color.to_string(),
+        }
+    }
+
+    /// Derive a palette from an image, pywal-style: the image is quantized into dominant
+    /// colors, a dark one becomes the background, a contrasting one the foreground, and
+    /// terminal slots take the dominant color nearest their hue (or a synthesized one).
+    pub fn generate_colors_from_image(image_path: &Path) -> Result<ThemeColors, String> {
+        // Sampling a small copy keeps quantization cheap for large wallpapers
+        let image = super::theme_preview::decode_image(image_path)?
+            .thumbnail(128, 128)
+            .to_rgb8();
+
+        // Bucket by the top 4 bits of each channel, keeping sums to average each bucket
+        let mut buckets: HashMap<(u8, u8, u8), (u64, [u64; 3])> = HashMap::new();
+        for pixel in image.pixels() {
+            let [r, g, b] = pixel.0;
+            let (count, sums) = buckets.entry((r >> 4, g >> 4, b >> 4)).or_default();
+            *count += 1;
+            sums[0] += u64::from(r);
+            sums[1] += u64::from(g);
+            sums[2] += u64::from(b);
+        }
+        if buckets.is_empty() {
+            return Err(format!("Image {} has no pixels", image_path.display()));
+        }
+
+        let mut dominant: Vec<(String, u64)> = buckets
+            .into_values()
+            .map(|(count, sums)| {
+                let [r, g, b] = sums.map(|sum| (sum / count) as u8);
+                (format!("#{r:02x}{g:02x}{b:02x}"), count)
+            })
+            .collect();
+        dominant.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let luminance = |color: &str| Self::relative_luminance(color).unwrap_or_default();
+        let hsl = |color: &str| Self::hex_to_hsl(color).unwrap_or_default();
+
+        // Background: the most common dark color, or the darkest one pushed darker
+        let background = dominant
+            .iter()
+            .find(|(color, _)| luminance(color) < 0.05)
+            .map(|(color, _)| color.clone())
+            .unwrap_or_else(|| {
+                let darkest = dominant
+                    .iter()
+                    .map(|(color, _)| color)
+                    .min_by(|a, b| luminance(a).total_cmp(&luminance(b)))
+                    .cloned()
+                    .unwrap_or_default();
+                let (hue, saturation, lightness) = hsl(&darkest);
+                Self::hsl_to_hex(hue, saturation.min(0.5), lightness.min(0.1))
+            });
+
+        // Foreground: the most common color with strong contrast, or a light background tint
+        let foreground = dominant
+            .iter()
+            .find(|(color, _)| Self::contrast_ratio(color, &background).unwrap_or_default() >= 7.0)
+            .map(|(color, _)| color.clone())
+            .unwrap_or_else(|| {
+                let (hue, saturation, _) = hsl(&background);
+                Self::hsl_to_hex(hue, saturation.min(0.2), 0.9)
+            });
+
+        // Terminal slots: nearest chromatic dominant color within reach of each hue
+        let chromatic: Vec<(f32, f32, u64)> = dominant
+            .iter()
+            .map(|(color, count)| {
+                let (hue, saturation, lightness) = hsl(color);
+                (hue, saturation, lightness, *count)
+            })
+            .filter(|(_, saturation, lightness, _)| {
+                *saturation >= 0.25 && (0.15..=0.9).contains(lightness)
+            })
+            .map(|(hue, saturation, _, count)| (hue, saturation, count))
+            .collect();
+        let fallback_saturation = chromatic
+            .first()
+            .map_or(0.5, |(_, saturation, _)| saturation.max(0.45));
+
+        let slot = |slot_hue: f32| {
+            let hue_distance = |hue: f32| {
+                let d = (hue - slot_hue).rem_euclid(360.0);
+                d.min(360.0 - d)
+            };
+            let (hue, saturation) = chromatic
+                .iter()
+                .filter(|(hue, _, _)| hue_distance(*hue) <= 30.0)
+                .max_by_key(|(_, _, count)| *count)
+                .map_or((slot_hue, fallback_saturation), |(hue, saturation, _)| {
+                    (*hue, *saturation)
+                });
+            // Keep every slot readable on a dark background
+            Self::hsl_to_hex(hue, saturation.max(0.45), 0.6)
+        };
+        let terminal = TerminalColors {
+            red: slot(0.0),
+            green: slot(120.0),
+            yellow: slot(50.0),
+            blue: slot(220.0),
+            magenta: slot(300.0),
+            cyan: slot(185.0),
+        };
+
+        let accent = Self::pick_accent(&terminal, &foreground);
+        Ok(ThemeColors {
+            primary: PrimaryColors {
+                background,
+                foreground,
+            },
+            terminal,
+            accent,
+        })
+    }
+
     /// Normalize and validate color format to hex
     pub fn normalize_color(color: &str) -> Option<String> {
         let trimmed = color.trim();
@@ -107,6 +605,60 @@ impl ColorExtractor {
         None
     }
 
+    /// Strip surrounding whitespace from color-like strings anywhere in theme data.
+    /// Returns whether anything changed.
+    pub fn trim_color_values(value: &mut Value) -> bool {
+        match value {
+            Value::String(text) => {
+                let trimmed = text.trim();
+                let is_color = trimmed.starts_with('#') || Self::normalize_color(trimmed).is_some();
+                if trimmed.len() != text.len() && is_color {
+                    *text = trimmed.to_string();
+                    true
+                } else {
+                    false
+                }
+            },
+            Value::Array(items) => {
+                let mut changed = false;
+                for item in items {
+                    changed |= Self::trim_color_values(item);
+                }
+                changed
+            },
+            Value::Object(map) => {
+                let mut changed = false;
+                for item in map.values_mut() {
+                    changed |= Self::trim_color_values(item);
+                }
+                changed
+            },
+            _ => false,
+        }
+    }
+
+    /// Strip surrounding whitespace from every color in a palette
+    pub fn trim_theme_colors(colors: &mut ThemeColors) {
+        for color in [
+            &mut colors.primary.background,
+            &mut colors.primary.foreground,
+            &mut colors.terminal.red,
+            &mut colors.terminal.green,
+            &mut colors.terminal.yellow,
+            &mut colors.terminal.blue,
+            &mut colors.terminal.magenta,
+            &mut colors.terminal.cyan,
+        ]
+        .into_iter()
+        .chain(colors.accent.as_mut())
+        {
+            let trimmed = color.trim();
+            if trimmed.len() != color.len() {
+                *color = trimmed.to_string();
+            }
+        }
+    }
+
     /// Validate if a string is a valid hex color
     fn is_valid_hex_color(color: &str) -> bool {
         if !color.starts_with('#') {
@@ -133,7 +685,11 @@ impl ColorExtractor {
             return Some(format!("#{}", color.to_lowercase()));
         }
 
-        // TODO: Add support for rgb(), rgba(), hsl() formats if needed
+        // Functional notation: rgb(), rgba(), hsl()
+        if color.ends_with(')') {
+            return parse_color(color).ok().map(to_hex);
+        }
+
         None
     }
 
@@ -152,20 +708,7 @@ impl ColorExtractor {
                 magenta: "#ff79c6".to_string(),
                 cyan: "#8be9fd".to_string(),
             },
-        }
-    }
-
-    /// Get fallback color for a specific terminal color
-    fn get_fallback_terminal_color(color_name: &str) -> Option<String> {
-        let fallback_colors = Self::get_fallback_colors();
-        match color_name {
-            "red" => Some(fallback_colors.terminal.red),
-            "green" => Some(fallback_colors.terminal.green),
-            "yellow" => Some(fallback_colors.terminal.yellow),
-            "blue" => Some(fallback_colors.terminal.blue),
-            "magenta" => Some(fallback_colors.terminal.magenta),
-            "cyan" => Some(fallback_colors.terminal.cyan),
-            _ => None,
+            accent: Some("#ff79c6".to_string()),
         }
     }
 
@@ -175,11 +718,74 @@ impl ColorExtractor {
     }
 }
 
+#[tauri::command]
+pub async fn extract_colors_from_wallpaper(image_path: String) -> Result<ThemeColors, String> {
+    ColorExtractor::generate_colors_from_image(Path::new(&image_path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    /// Write a PNG made of horizontal bands, each `(height, color)`
+    fn write_banded_png(path: &Path, bands: &[(u32, [u8; 3])]) {
+        let height = bands.iter().map(|(rows, _)| rows).sum();
+        let mut image = image::RgbImage::new(32, height);
+        let mut y = 0;
+        for (rows, color) in bands {
+            for row in y..y + rows {
+                for x in 0..32 {
+                    image.put_pixel(x, row, image::Rgb(*color));
+                }
+            }
+            y += rows;
+        }
+        image.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_palette_from_dark_wallpaper_uses_dominant_colors() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("night.png");
+        write_banded_png(
+            &path,
+            &[
+                (20, [0x10, 0x18, 0x28]),
+                (4, [0xe0, 0x30, 0x30]),
+                (4, [0x30, 0x60, 0xe0]),
+                (4, [0xee, 0xee, 0xe0]),
+            ],
+        );
+
+        let colors = ColorExtractor::generate_colors_from_image(&path).unwrap();
+        assert_eq!(colors.primary.background, "#101828");
+        assert_eq!(colors.primary.foreground, "#eeeee0");
+
+        let hue = |color: &str| ColorExtractor::hex_to_hsl(color).unwrap().0;
+        assert!(hue(&colors.terminal.red) < 1.0);
+        assert!((hue(&colors.terminal.blue) - hue("#3060e0")).abs() < 1.0);
+        assert!(colors.accent.is_some());
+    }
+
+    #[test]
+    fn test_palette_from_bright_wallpaper_stays_readable() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("snow.png");
+        write_banded_png(&path, &[(24, [0xf4, 0xf4, 0xf0]), (8, [0x40, 0xb0, 0x50])]);
+
+        let colors = ColorExtractor::generate_colors_from_image(&path).unwrap();
+        let background = &colors.primary.background;
+        assert!(ColorExtractor::relative_luminance(background).unwrap() < 0.05);
+        assert!(
+            ColorExtractor::contrast_ratio(background, &colors.primary.foreground).unwrap() >= 7.0
+        );
+        let green_hue = ColorExtractor::hex_to_hsl(&colors.terminal.green)
+            .unwrap()
+            .0;
+        assert!((green_hue - ColorExtractor::hex_to_hsl("#40b050").unwrap().0).abs() < 1.0);
+    }
+
     #[test]
     fn test_normalize_valid_hex_colors() {
         assert_eq!(
@@ -225,6 +831,67 @@ mod tests {
         assert_eq!(ColorExtractor::normalize_color(""), None);
     }
 
+    #[test]
+    fn test_padded_color_strings_normalize_cleanly() {
+        let mut theme_data = json!({
+            "alacritty": {
+                "colors": {
+                    "primary": { "background": " #1E1E2E ", "foreground": "\t#cdd6f4\n" },
+                    "normal": {
+                        "red": " #f38ba8", "green": "#a6e3a1 ", "yellow": " #f9e2af ",
+                        "blue": "#89b4fa", "magenta": " #f5c2e7", "cyan": "#94e2d5\n"
+                    }
+                }
+            },
+            "waybar": { "font": " JetBrains Mono " }
+        });
+
+        let colors = ColorExtractor::extract_from_custom_theme(&theme_data).unwrap();
+        assert_eq!(colors.primary.background, "#1e1e2e");
+        assert_eq!(colors.primary.foreground, "#cdd6f4");
+        assert_eq!(colors.terminal.cyan, "#94e2d5");
+
+        assert!(ColorExtractor::trim_color_values(&mut theme_data));
+        assert_eq!(
+            theme_data["alacritty"]["colors"]["primary"]["background"],
+            "#1E1E2E"
+        );
+        assert_eq!(
+            theme_data["alacritty"]["colors"]["normal"]["red"],
+            "#f38ba8"
+        );
+        // Non-color strings are left alone
+        assert_eq!(theme_data["waybar"]["font"], " JetBrains Mono ");
+        assert!(!ColorExtractor::trim_color_values(&mut theme_data));
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let config_path = temp.path().join("alacritty.toml");
+        let toml_config = toml::to_string(&theme_data["alacritty"]).unwrap();
+        fs::write(&config_path, toml_config.replace("#1E1E2E", "  #1E1E2E\t")).unwrap();
+        let from_file = ColorExtractor::extract_from_alacritty_config(&config_path).unwrap();
+        assert_eq!(from_file.primary.background, "#1e1e2e");
+
+        let mut padded = colors.clone();
+        padded.primary.background = " #1e1e2e ".to_string();
+        padded.accent = Some("#f38ba8 ".to_string());
+        ColorExtractor::trim_theme_colors(&mut padded);
+        assert_eq!(padded.primary.background, "#1e1e2e");
+        assert_eq!(padded.accent.as_deref(), Some("#f38ba8"));
+    }
+
+    #[test]
+    fn test_palette_from_accent_keeps_accent_hue() {
+        let (h, s, l) = ColorExtractor::hex_to_hsl("#ff5555").unwrap();
+        assert_eq!(ColorExtractor::hsl_to_hex(h, s, l), "#ff5555");
+
+        let (normal, bright) = ColorExtractor::palette_from_accent("#3b82f6").unwrap();
+        let accent_hue = ColorExtractor::hex_to_hsl("#3b82f6").unwrap().0;
+        let blue_hue = ColorExtractor::hex_to_hsl(&normal.blue).unwrap().0;
+        assert!((accent_hue - blue_hue).abs() < 1.5);
+        assert_ne!(normal.red, bright.red);
+        assert!(ColorExtractor::palette_from_accent("not-a-color").is_none());
+    }
+
     #[test]
     fn test_is_valid_hex_color() {
         assert!(ColorExtractor::is_valid_hex_color("#123456"));
@@ -346,6 +1013,65 @@ mod tests {
         assert_eq!(fallback.terminal.cyan, "#8be9fd");
     }
 
+    #[test]
+    fn test_accent_picks_most_saturated_color() {
+        let theme_data = json!({
+            "alacritty": {
+                "colors": {
+                    "primary": {
+                        "background": "#1e1e2e",
+                        "foreground": "#cdd6f4"
+                    },
+                    "normal": {
+                        "red": "#bf616a",
+                        "green": "#a3be8c",
+                        "yellow": "#ebcb8b",
+                        "blue": "#0077ff",
+                        "magenta": "#b48ead",
+                        "cyan": "#88c0d0"
+                    }
+                }
+            }
+        });
+
+        let colors = ColorExtractor::extract_from_custom_theme(&theme_data).unwrap();
+        assert_eq!(colors.accent, Some("#0077ff".to_string()));
+    }
+
+    #[test]
+    fn test_accent_prefers_dedicated_key() {
+        let theme_data = json!({
+            "alacritty": {
+                "colors": {
+                    "accent": "#E5C07B",
+                    "primary": {
+                        "background": "#1e1e2e",
+                        "foreground": "#cdd6f4"
+                    },
+                    "normal": {
+                        "red": "#ff0000",
+                        "green": "#a3be8c",
+                        "yellow": "#ebcb8b",
+                        "blue": "#81a1c1",
+                        "magenta": "#b48ead",
+                        "cyan": "#88c0d0"
+                    }
+                }
+            }
+        });
+
+        let colors = ColorExtractor::extract_from_custom_theme(&theme_data).unwrap();
+        assert_eq!(colors.accent, Some("#e5c07b".to_string()));
+    }
+
+    #[test]
+    fn test_hex_to_rgb() {
+        assert_eq!(ColorExtractor::hex_to_rgb("#ff8000"), Some((255, 128, 0)));
+        assert_eq!(ColorExtractor::hex_to_rgb("#fff"), Some((255, 255, 255)));
+        assert_eq!(ColorExtractor::hex_to_rgb("ff8000"), None);
+        assert_eq!(ColorExtractor::hex_to_rgb("#zzzzzz"), None);
+    }
+
     #[test]
     fn test_validate_and_sanitize_color() {
         assert_eq!(
@@ -358,4 +1084,110 @@ mod tests {
         );
         assert_eq!(ColorExtractor::validate_and_sanitize_color("invalid"), None);
     }
+
+    #[test]
+    fn test_extract_from_kitty_config() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("kitty.conf");
+        fs::write(
+            &path,
+            r#"# vim:ft=kitty
+## name: Tokyo Night
+
+background   #1a1b26
+foreground      #c0caf5
+selection_background #283457
+
+  # normal
+color0  #15161e
+color1  #f7768e
+color2  #9ece6a
+color3  #e0af68
+color4  #7aa2f7
+color5  #bb9af7
+
+# bright; color6 is only set here
+color8  #414868
+color14 #7dcfff
+color9  #ff0000
+"#,
+        )
+        .unwrap();
+
+        let colors = ColorExtractor::extract_from_kitty_config(&path).unwrap();
+        assert_eq!(colors.primary.background, "#1a1b26");
+        assert_eq!(colors.primary.foreground, "#c0caf5");
+        assert_eq!(colors.terminal.red, "#f7768e");
+        assert_eq!(colors.terminal.green, "#9ece6a");
+        assert_eq!(colors.terminal.yellow, "#e0af68");
+        assert_eq!(colors.terminal.blue, "#7aa2f7");
+        assert_eq!(colors.terminal.magenta, "#bb9af7");
+        assert_eq!(colors.terminal.cyan, "#7dcfff");
+        assert!(colors.accent.is_some());
+
+        fs::write(&path, "foreground #c0caf5\ncolor1 #f7768e\n").unwrap();
+        assert!(ColorExtractor::extract_from_kitty_config(&path).is_none());
+    }
+
+    #[test]
+    fn test_extract_from_wezterm_config() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("wezterm.lua");
+        fs::write(
+            &path,
+            r##"local wezterm = require 'wezterm'
+local config = wezterm.config_builder()
+
+config.colors = {
+  -- background = "#000000",
+  foreground = "#cdd6f4",
+  background = '#1e1e2e',
+  cursor_bg = "#f5e0dc",
+  selection_bg = "#585b70",
+
+  ansi = {
+    "#45475a", -- black
+    "#f38ba8", -- red
+    "#a6e3a1", -- green
+    "#f9e2af", -- yellow
+    "#89b4fa", -- blue
+    "#f5c2e7", -- magenta
+    "#94e2d5", -- cyan
+    "#bac2de", -- white
+  },
+  brights = {
+    "#585b70", "#f38ba8", "#a6e3a1", "#f9e2af",
+    "#89b4fa", "#f5c2e7", "#94e2d5", "#a6adc8",
+  },
+  tab_bar = {
+    background = "#11111b",
+  },
+}
+
+return config
+"##,
+        )
+        .unwrap();
+
+        let colors = ColorExtractor::extract_from_wezterm_config(&path).unwrap();
+        assert_eq!(colors.primary.background, "#1e1e2e");
+        assert_eq!(colors.primary.foreground, "#cdd6f4");
+        assert_eq!(colors.terminal.red, "#f38ba8");
+        assert_eq!(colors.terminal.green, "#a6e3a1");
+        assert_eq!(colors.terminal.yellow, "#f9e2af");
+        assert_eq!(colors.terminal.blue, "#89b4fa");
+        assert_eq!(colors.terminal.magenta, "#f5c2e7");
+        assert_eq!(colors.terminal.cyan, "#94e2d5");
+
+        // Only brights: terminal slots fall back to them
+        fs::write(
+            &path,
+            r##"return { background = "#000000", foreground = "#ffffff",
+  brights = { "#111111", "#ff5555", "#50fa7b", "#f1fa8c", "#bd93f9", "#ff79c6", "#8be9fd" } }"##,
+        )
+        .unwrap();
+        let colors = ColorExtractor::extract_from_wezterm_config(&path).unwrap();
+        assert_eq!(colors.terminal.red, "#ff5555");
+        assert_eq!(colors.terminal.cyan, "#8be9fd");
+    }
 }
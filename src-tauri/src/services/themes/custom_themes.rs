@@ -1,40 +1,155 @@
+use super::base16;
+use super::color;
 use super::color_extraction::ColorExtractor;
-use crate::services::config::generators::ConfigGeneratorRegistry;
+use super::get_sys_themes::{reload_cached_theme, theme_cache_key_in};
+use super::theme_archive;
+use super::theme_lint;
+use super::theme_preview;
+use crate::services::config::generators::raw_files::RawFilesGenerator;
+use crate::services::config::generators::{
+    migrate_app_keys, write_with_raw_append, ConfigGenerator, ConfigGeneratorRegistry,
+    FALLBACK_COLORS_KEY,
+};
+use crate::services::util::atomic_file::{write_atomic, write_atomic_with};
+use crate::services::util::base64;
 use crate::types::{
-    AlacrittyColors, AlacrittyConfig, AlacrittyPrimaryColors, CustomTheme, ThemeColors,
+    AddBackgroundsResult, AlacrittyColors, AlacrittyConfig, AlacrittyPrimaryColors,
+    AppValidationResult, BackgroundInfo, BatchThemeResult, ColorUsage, CustomTheme,
+    DeduplicatedBackgrounds, DefaultAppConfig, GeneratorCapabilities, LintFinding, MergeStrategy,
+    Page, PreflightItem, PreflightReport, PreflightStatus, RegeneratedConfigs, ThemeBackup,
+    ThemeColors, ThemeColorsSnapshot, ThemeError, ValidationError, ValidationSeverity,
 };
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
+/// Optional JSON list of background file names in display order; unlisted files follow
+/// alphabetically
+const BACKGROUND_ORDER_FILE: &str = "order.json";
+/// Directory inside a theme holding snapshots of earlier metadata
+const BACKUPS_DIR: &str = "backups";
+/// Apps whose configs a theme can't be applied without; other missing configs only warn
+const PREFLIGHT_REQUIRED_APPS: [&str; 3] = ["alacritty", "hyprland", "waybar"];
+/// Default number of metadata snapshots kept per theme
+const DEFAULT_MAX_THEME_BACKUPS: usize = 5;
+/// Default largest background image accepted, in pixels
+const DEFAULT_MAX_BACKGROUND_DIMENSIONS: (u32, u32) = (8000, 8000);
+/// Default largest background file accepted, in bytes
+const DEFAULT_MAX_BACKGROUND_BYTES: u64 = 40 * 1024 * 1024;
+/// How long a background download may take before it is abandoned
+const BACKGROUND_DOWNLOAD_TIMEOUT_SECS: u64 = 30;
+/// Name prefixes of the directories creates, imports and overwrites stage themes in
+const STAGING_DIR_PREFIXES: [&str; 3] = [".create-", ".import-", ".replaced-"];
+
 pub struct CustomThemeService {
     themes_dir: PathBuf,
     generator_registry: ConfigGeneratorRegistry,
-    app_handle: AppHandle,
+    app_handle: Option<AppHandle>,
+    fork_system_theme_on_edit: bool,
+    compact_theme_json: bool,
+    max_background_dimensions: (u32, u32),
+    max_background_bytes: u64,
+    max_theme_backups: usize,
+    fallback_colors: ThemeColors,
 }
 
 impl CustomThemeService {
-    pub fn new(app_handle: &AppHandle) -> Result<Self, String> {
-        // Use the same directory structure as system themes: ~/.config/omarchy/themes/
-        let home_dir =
-            dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
-
-        let themes_dir = home_dir.join(".config").join("omarchy").join("themes");
+    pub fn new(app_handle: &AppHandle) -> Result<Self, ThemeError> {
+        // Custom themes live alongside system themes
+        let themes_dir = super::themes_dir::themes_dir().map_err(ThemeError::Io)?;
 
         // Create themes directory if it doesn't exist
         fs::create_dir_all(&themes_dir)
-            .map_err(|e| format!("Failed to create themes directory: {e}"))?;
+            .map_err(|e| ThemeError::Io(format!("Failed to create themes directory: {e}")))?;
+
+        Ok(Self {
+            themes_dir,
+            generator_registry: ConfigGeneratorRegistry::new(),
+            app_handle: Some(app_handle.clone()),
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            max_background_dimensions: DEFAULT_MAX_BACKGROUND_DIMENSIONS,
+            max_background_bytes: DEFAULT_MAX_BACKGROUND_BYTES,
+            max_theme_backups: DEFAULT_MAX_THEME_BACKUPS,
+            fallback_colors: ColorExtractor::get_fallback_colors(),
+        })
+    }
+
+    /// Create a service rooted at an explicit themes directory (no Tauri resources available)
+    pub fn with_themes_dir(themes_dir: PathBuf) -> Result<Self, ThemeError> {
+        fs::create_dir_all(&themes_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create themes directory: {e}")))?;
 
         Ok(Self {
             themes_dir,
             generator_registry: ConfigGeneratorRegistry::new(),
-            app_handle: app_handle.clone(),
+            app_handle: None,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            max_background_dimensions: DEFAULT_MAX_BACKGROUND_DIMENSIONS,
+            max_background_bytes: DEFAULT_MAX_BACKGROUND_BYTES,
+            max_theme_backups: DEFAULT_MAX_THEME_BACKUPS,
+            fallback_colors: ColorExtractor::get_fallback_colors(),
         })
     }
 
-    /// Sanitize theme name for directory usage (optimized to reduce allocations)
-    pub fn sanitize_name(name: &str) -> String {
+    /// Create a service configured from the user's app settings
+    pub async fn with_settings(app_handle: &AppHandle) -> Result<Self, ThemeError> {
+        let mut service = Self::new(app_handle)?;
+        match crate::services::settings::app_settings::load_settings(app_handle).await {
+            Ok(settings) => {
+                service.set_fork_system_theme_on_edit(settings.fork_system_theme_on_edit);
+                service.set_compact_theme_json(settings.compact_theme_json);
+                service.set_max_theme_backups(settings.theme_backups_to_keep);
+                service.set_background_limits(
+                    (
+                        settings.max_background_width,
+                        settings.max_background_height,
+                    ),
+                    settings.max_background_megabytes * 1024 * 1024,
+                );
+            },
+            Err(e) => log::warn!("Failed to load settings for theme service, using defaults: {e}"),
+        }
+        match crate::services::cache::cache_config::CacheConfigManager::load_config(app_handle) {
+            Ok(config) => service.set_fallback_colors(config.fallback_colors),
+            Err(e) => log::warn!("Failed to load fallback colors, using defaults: {e}"),
+        }
+        Ok(service)
+    }
+
+    /// Control whether editing a read-only system theme forks it into a custom copy
+    pub fn set_fork_system_theme_on_edit(&mut self, enabled: bool) {
+        self.fork_system_theme_on_edit = enabled;
+    }
+
+    /// Control whether custom_theme.json is written minified instead of pretty-printed
+    pub fn set_compact_theme_json(&mut self, enabled: bool) {
+        self.compact_theme_json = enabled;
+    }
+
+    /// Set how many metadata snapshots are kept per theme; 0 disables backups
+    pub fn set_max_theme_backups(&mut self, max_backups: usize) {
+        self.max_theme_backups = max_backups;
+    }
+
+    /// Set the palette given to themes whose colors cannot be extracted
+    pub fn set_fallback_colors(&mut self, colors: ThemeColors) {
+        self.fallback_colors = colors;
+    }
+
+    /// Set the largest background image (width, height and file size) that may be added
+    pub fn set_background_limits(&mut self, max_dimensions: (u32, u32), max_bytes: u64) {
+        self.max_background_dimensions = max_dimensions;
+        self.max_background_bytes = max_bytes;
+    }
+
+    /// Sanitize theme name for directory usage (optimized to reduce allocations).
+    /// Fails for names without a letter or digit, which would otherwise resolve to the
+    /// themes directory itself or a meaningless one like "---".
+    pub fn sanitize_name(name: &str) -> Result<String, ThemeError> {
         let mut result = String::with_capacity(name.len()); // Pre-allocate capacity
 
         for ch in name.chars() {
@@ -47,28 +162,22 @@ impl CustomThemeService {
             }
         }
 
-        result
+        // Whitespace- or punctuation-only names would become "" or "---"
+        if !result.chars().any(char::is_alphanumeric) {
+            return Err(ThemeError::Validation(format!(
+                "Invalid theme name '{name}'"
+            )));
+        }
+        Ok(result)
     }
 
-    /// Extract colors from theme data with fallback to Alacritty config file
+    /// Extract colors from theme data, then from the terminal configs in `theme_dir`, and
+    /// fall back to the configured palette
     fn extract_theme_colors(&self, theme_dir: &Path, theme_data: &Value) -> Option<ThemeColors> {
-        // First try to extract from theme data (custom theme JSON)
-        if let Some(colors) = ColorExtractor::extract_from_custom_theme(theme_data) {
-            return Some(colors);
-        }
-
-        // Fallback: try to extract from Alacritty config file
-        let alacritty_config_path = theme_dir.join("alacritty.toml");
-        if alacritty_config_path.exists() {
-            if let Some(colors) =
-                ColorExtractor::extract_from_alacritty_config(&alacritty_config_path)
-            {
-                return Some(colors);
-            }
-        }
-
-        // If all else fails, return fallback colors
-        Some(ColorExtractor::get_fallback_colors())
+        let colors = ColorExtractor::extract_from_custom_theme(theme_data)
+            .or_else(|| super::get_sys_themes::extract_theme_colors(theme_dir, false))
+            .unwrap_or_else(|| self.fallback_colors.clone());
+        Some(colors)
     }
 
     /// Create a new custom theme with modern multi-app support
@@ -76,82 +185,271 @@ impl CustomThemeService {
         &self,
         name: String,
         theme_data: Value,
-    ) -> Result<CustomTheme, String> {
-        let sanitized_name = Self::sanitize_name(&name);
+    ) -> Result<CustomTheme, ThemeError> {
+        let staging_dir = self
+            .themes_dir
+            .join(format!(".create-{}", uuid::Uuid::new_v4()));
+        self.create_theme_via_staging(&staging_dir, name, theme_data)
+    }
+
+    /// Write a new theme into `staging_dir` and move it into place once every file is written,
+    /// so an I/O error never leaves a half-created theme behind. Generator content errors are
+    /// logged and skipped.
+    fn create_theme_via_staging(
+        &self,
+        staging_dir: &Path,
+        name: String,
+        theme_data: Value,
+    ) -> Result<CustomTheme, ThemeError> {
+        let sanitized_name = Self::sanitize_name(&name)?;
         let theme_dir = self.themes_dir.join(&sanitized_name);
 
         // Check if theme already exists
         if theme_dir.exists() {
-            return Err(format!("Theme '{name}' already exists"));
+            return Err(ThemeError::AlreadyExists(format!(
+                "Theme '{name}' already exists"
+            )));
         }
 
-        // Create theme directory
-        fs::create_dir_all(&theme_dir)
-            .map_err(|e| format!("Failed to create theme directory: {e}"))?;
+        let result = self
+            .stage_new_theme(staging_dir, &name, theme_data)
+            .and_then(|theme| {
+                if theme_dir.exists() {
+                    return Err(ThemeError::AlreadyExists(format!(
+                        "Theme '{name}' already exists"
+                    )));
+                }
+                fs::rename(staging_dir, &theme_dir).map_err(|e| {
+                    ThemeError::Io(format!("Failed to create theme directory: {e}"))
+                })?;
+                Ok(theme)
+            });
+        if staging_dir.exists() {
+            let _ = fs::remove_dir_all(staging_dir);
+        }
+        let theme = result?;
+
+        log::info!(
+            "Created custom theme '{}' in directory: {}",
+            name,
+            theme_dir.display()
+        );
+
+        Ok(theme)
+    }
 
-        // Create backgrounds subdirectory
+    /// Write a new theme's backgrounds directory, app configs and metadata into `theme_dir`
+    fn stage_new_theme(
+        &self,
+        theme_dir: &Path,
+        name: &str,
+        mut theme_data: Value,
+    ) -> Result<CustomTheme, ThemeError> {
+        ColorExtractor::trim_color_values(&mut theme_data);
+        color::normalize_color_values(&mut theme_data);
+        // Reject unsafe raw file paths before anything is written
+        RawFilesGenerator::files(&theme_data).map_err(ThemeError::Validation)?;
+
+        // Create theme directory with its backgrounds subdirectory
         let backgrounds_dir = theme_dir.join("backgrounds");
         fs::create_dir_all(&backgrounds_dir)
-            .map_err(|e| format!("Failed to create backgrounds directory: {e}"))?;
+            .map_err(|e| ThemeError::Io(format!("Failed to create backgrounds directory: {e}")))?;
 
         let now = chrono::Utc::now().to_rfc3339();
 
         // Extract colors from theme data
-        let colors = self.extract_theme_colors(&theme_dir, &theme_data);
+        let colors = self.extract_theme_colors(theme_dir, &theme_data);
 
         // Create theme metadata
-        let theme = CustomTheme {
-            name: name.clone(),
+        let meta = Self::take_theme_meta(&mut theme_data);
+        let mut theme = CustomTheme {
+            name: name.to_string(),
             created_at: now.clone(),
             modified_at: now,
             apps: theme_data.clone(),
             colors,
+            selected_background: None,
+            tags: Vec::new(),
+            author: None,
+            version: None,
+            description: String::new(),
+            preview: None,
         };
+        if let Some(meta) = &meta {
+            Self::apply_theme_meta(&mut theme, meta);
+        }
 
-        // Generate config files for each app using the generator registry
+        // Generate config files for each app using the generator registry. Generators look
+        // up their own section in the whole theme, next to the palette and accent.
+        let generator_input = self.generator_input(&theme);
         for app_name in self.generator_registry.get_all_apps() {
             if let Some(generator) = self.generator_registry.get_generator(app_name) {
-                // Extract the specific config for this app from the theme_data
-                if let Some(app_config) = theme_data.get(app_name) {
-                    match generator.generate_config(app_config) {
-                        Ok(config_content) => {
-                            let config_path = theme_dir.join(generator.get_file_name());
-                            fs::write(&config_path, config_content)
-                                .map_err(|e| format!("Failed to write {app_name} config: {e}"))?;
-                        },
-                        Err(e) => {
-                            log::warn!("Failed to generate {app_name} config for '{name}': {e}");
-                        },
+                if theme_data.get(app_name).is_some() {
+                    let config_path = theme_dir.join(generator.get_file_name());
+                    if let Err(e) =
+                        Self::stream_config_to_file(generator, &generator_input, &config_path)?
+                    {
+                        log::warn!("Failed to generate {app_name} config for '{name}': {e}");
                     }
                 } else {
                     log::warn!("No config data found for app '{app_name}' in new theme '{name}'");
                 }
             }
         }
+        RawFilesGenerator::write_files(theme_dir, &theme_data).map_err(ThemeError::Io)?;
 
         // Save theme metadata
-        let metadata_path = theme_dir.join("custom_theme.json");
-        let metadata_content = serde_json::to_string_pretty(&theme)
-            .map_err(|e| format!("Failed to serialize theme metadata: {e}"))?;
-        fs::write(&metadata_path, metadata_content)
-            .map_err(|e| format!("Failed to write theme metadata: {e}"))?;
-
-        log::info!(
-            "Created custom theme '{}' in directory: {}",
-            name,
-            theme_dir.display()
-        );
+        self.write_theme_metadata(theme_dir, &theme)?;
+
+        // Unreadable colors are worth a note but never block creation
+        for warning in theme_lint::validate_theme_contrast(&theme_data) {
+            log::warn!(
+                "Theme '{name}': {} {} on {} has a contrast ratio of {:.2}",
+                warning.element,
+                warning.color,
+                warning.background,
+                warning.ratio
+            );
+        }
 
         Ok(theme)
     }
 
+    /// Stream a generated config into its file through a temporary file.
+    /// The outer error is an I/O failure; the inner one a generation failure that leaves
+    /// any existing file untouched.
+    fn stream_config_to_file(
+        generator: &dyn ConfigGenerator,
+        theme_data: &Value,
+        config_path: &Path,
+    ) -> Result<Result<(), String>, ThemeError> {
+        let app_name = generator.get_app_name();
+
+        let mut generation_error = None;
+        let written = write_atomic_with(config_path, |file| {
+            let mut writer = std::io::BufWriter::new(file);
+            if let Err(e) = write_with_raw_append(generator, theme_data, &mut writer) {
+                generation_error = Some(e);
+                return Err(std::io::Error::other("config generation failed"));
+            }
+            std::io::Write::flush(&mut writer)
+        });
+
+        if let Some(e) = generation_error {
+            return Ok(Err(e));
+        }
+        written.map_err(|e| ThemeError::Io(format!("Failed to write {app_name} config: {e}")))?;
+        Ok(Ok(()))
+    }
+
+    /// Suggest a non-colliding name for a copy of `base_name`: "X (copy)", "X (copy 2)", ...
+    pub fn suggest_theme_name(&self, base_name: &str) -> String {
+        let base_name = base_name.trim();
+        let mut attempt = 1;
+        loop {
+            let candidate = if attempt == 1 {
+                format!("{base_name} (copy)")
+            } else {
+                format!("{base_name} (copy {attempt})")
+            };
+            // The " (copy)" suffix always leaves usable characters
+            if Self::sanitize_name(&candidate).is_ok_and(|dir| !self.themes_dir.join(dir).exists())
+            {
+                return candidate;
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Create several themes in one go, continuing past individual failures. Names that
+    /// map to the same directory as an earlier entry in the batch are rejected.
+    pub fn batch_create_themes(&self, items: Vec<(String, Value)>) -> Vec<BatchThemeResult> {
+        let mut seen = std::collections::HashSet::new();
+        items
+            .into_iter()
+            .map(|(name, theme_data)| {
+                let created = match Self::sanitize_name(&name) {
+                    Ok(dir) if !seen.insert(dir.clone()) => Err(ThemeError::AlreadyExists(
+                        format!("Theme '{name}' appears more than once in this batch"),
+                    )),
+                    _ => self.create_theme_advanced(name.clone(), theme_data),
+                };
+                match created {
+                    Ok(theme) => BatchThemeResult {
+                        name,
+                        theme: Some(theme),
+                        error: None,
+                    },
+                    Err(e) => {
+                        log::warn!("Batch creation of theme '{name}' failed: {e}");
+                        BatchThemeResult {
+                            name,
+                            theme: None,
+                            error: Some(e.to_string()),
+                        }
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Create a theme from a base16/base24 YAML scheme; an empty `name` uses the scheme's own
+    pub fn import_base16(&self, name: &str, yaml_content: &str) -> Result<CustomTheme, ThemeError> {
+        let scheme = base16::parse_scheme(yaml_content).map_err(ThemeError::Parse)?;
+        let name = match name.trim() {
+            "" => scheme.name.clone().ok_or_else(|| {
+                ThemeError::Validation("Base16 scheme has no name; pass one".to_string())
+            })?,
+            name => name.to_string(),
+        };
+        self.create_theme_advanced(name, base16::theme_data_from_scheme(&scheme))
+    }
+
+    /// Export a theme's palette as a base16 YAML scheme
+    pub fn export_base16(&self, name: &str) -> Result<String, ThemeError> {
+        let theme = self.get_theme(name)?;
+        let colors = theme.colors.ok_or_else(|| {
+            ThemeError::Validation(format!("Theme '{name}' has no color palette to export"))
+        })?;
+        let scheme = base16::scheme_from_colors(&theme.name, theme.author.as_deref(), &colors)
+            .map_err(ThemeError::Validation)?;
+        Ok(base16::scheme_to_yaml(&scheme))
+    }
+
+    /// Normalize the named colors given to the legacy methods to `#rrggbb`, failing with
+    /// every field that isn't a color so generated configs stay loadable
+    fn normalize_legacy_colors<const N: usize>(
+        fields: [(&str, String); N],
+    ) -> Result<[String; N], ThemeError> {
+        let mut invalid = Vec::new();
+        let normalized = fields.map(|(field, value)| {
+            color::normalize_color(&value).unwrap_or_else(|_| {
+                invalid.push(format!("{field} '{}'", value.trim()));
+                value
+            })
+        });
+
+        if !invalid.is_empty() {
+            return Err(ThemeError::Validation(format!(
+                "Invalid colors: {}",
+                invalid.join(", ")
+            )));
+        }
+        Ok(normalized)
+    }
+
     /// Create a new custom theme (legacy method for backwards compatibility)
     pub fn create_theme(
         &self,
         name: String,
         background: String,
         foreground: String,
-    ) -> Result<CustomTheme, String> {
+    ) -> Result<CustomTheme, ThemeError> {
+        let [background, foreground] = Self::normalize_legacy_colors([
+            ("background", background),
+            ("foreground", foreground),
+        ])?;
+
         // Convert legacy parameters to new format
         let theme_data = serde_json::json!({
             "alacritty": {
@@ -171,723 +469,5644 @@ impl CustomThemeService {
     pub fn update_theme_advanced(
         &self,
         name: &str,
-        theme_data: Value,
-    ) -> Result<CustomTheme, String> {
-        let sanitized_name = Self::sanitize_name(name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
+        mut theme_data: Value,
+        strategy: MergeStrategy,
+    ) -> Result<CustomTheme, ThemeError> {
+        ColorExtractor::trim_color_values(&mut theme_data);
+        color::normalize_color_values(&mut theme_data);
+        let mut sanitized_name = Self::sanitize_name(name)?;
+        let mut theme_dir = self.themes_dir.join(&sanitized_name);
 
         if !theme_dir.exists() {
-            return Err(format!("Theme '{name}' not found"));
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
+        }
+
+        // System themes are symlinks into the Omarchy install and must not be edited in place
+        if Self::is_read_only_system_theme(&theme_dir) {
+            if !self.fork_system_theme_on_edit {
+                return Err(ThemeError::Validation(format!(
+                    "Theme '{name}' is a read-only system theme"
+                )));
+            }
+            sanitized_name = self.move_theme_to_custom_writable(name)?;
+            theme_dir = self.themes_dir.join(&sanitized_name);
         }
 
         // Load existing theme metadata
         let mut theme = self.load_theme_metadata(&sanitized_name)?;
+        if let Some(meta) = Self::take_theme_meta(&mut theme_data) {
+            Self::apply_theme_meta(&mut theme, &meta);
+        }
 
         // Deep-merge incoming app data into existing apps so we don't wipe other apps
         let mut merged_apps = theme.apps.clone();
-        Self::deep_merge(&mut merged_apps, &theme_data);
+        Self::deep_merge(&mut merged_apps, &theme_data, strategy);
         theme.apps = merged_apps;
         theme.modified_at = chrono::Utc::now().to_rfc3339();
 
         // Re-extract colors after update
         theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
 
-        // Regenerate config files for each app
-        for app_name in self.generator_registry.get_all_apps() {
-            if let Some(generator) = self.generator_registry.get_generator(app_name) {
-                match generator.generate_config(&theme.apps) {
-                    Ok(config_content) => {
-                        let config_path = theme_dir.join(generator.get_file_name());
-                        log::debug!("Writing {} config to {}", app_name, config_path.display());
-                        fs::write(&config_path, config_content)
-                            .map_err(|e| format!("Failed to write {app_name} config: {e}"))?;
-                    },
-                    Err(e) => {
-                        log::warn!("Failed to generate {app_name} config: {e}");
-                    },
-                }
-            }
-        }
+        self.regenerate_app_configs(&theme_dir, &theme)?;
 
-        // Update the metadata file
-        let metadata_path = theme_dir.join("custom_theme.json");
-        let metadata_content = serde_json::to_string_pretty(&theme)
-            .map_err(|e| format!("Failed to serialize theme metadata: {e}"))?;
-        fs::write(&metadata_path, metadata_content)
-            .map_err(|e| format!("Failed to write theme metadata: {e}"))?;
+        // Keep the previous state around, then update the metadata file
+        if let Err(e) = self.snapshot_theme_metadata(&theme_dir) {
+            log::warn!("Failed to snapshot metadata of '{name}': {e}");
+        }
+        self.write_theme_metadata(&theme_dir, &theme)?;
 
         log::info!("Updated custom theme '{name}'");
 
         Ok(theme)
     }
 
-    /// Deep-merge JSON values: when both sides are objects, merge keys recursively.
-    /// Otherwise, overwrite target with source.
-    fn deep_merge(target: &mut Value, src: &Value) {
-        use serde_json::Value::*;
-        match (target, src) {
-            (Object(t_map), Object(s_map)) => {
-                for (k, v) in s_map {
-                    match (t_map.get_mut(k), v) {
-                        (Some(t_child), Object(_)) => {
-                            Self::deep_merge(t_child, v);
-                        },
-                        (Some(t_child), _) => {
-                            *t_child = v.clone();
-                        },
-                        (core::option::Option::None, _) => {
-                            t_map.insert(k.clone(), v.clone());
-                        },
-                    }
-                }
-            },
-            (t, s) => {
-                *t = s.clone();
-            },
-        }
-    }
-
-    /// Update an existing theme (legacy method for backwards compatibility)
-    pub fn update_theme(
-        &self,
-        name: &str,
-        alacritty_config: AlacrittyConfig,
-    ) -> Result<CustomTheme, String> {
-        // Convert legacy config to new format
+    /// Re-skin a theme around a new accent: derive terminal palettes from it, write them into
+    /// the alacritty colors and regenerate. Backgrounds and other metadata are left alone.
+    pub fn recolor_from_accent(&self, name: &str, accent: &str) -> Result<CustomTheme, ThemeError> {
+        let accent = ColorExtractor::normalize_color(accent)
+            .ok_or_else(|| ThemeError::Validation(format!("Invalid accent color '{accent}'")))?;
+        let (normal, bright) = ColorExtractor::palette_from_accent(&accent)
+            .ok_or_else(|| ThemeError::Validation(format!("Invalid accent color '{accent}'")))?;
+
+        let to_json = |colors: &crate::types::TerminalColors| {
+            serde_json::to_value(colors)
+                .map_err(|e| ThemeError::Parse(format!("Failed to serialize palette: {e}")))
+        };
         let theme_data = serde_json::json!({
             "alacritty": {
                 "colors": {
-                    "primary": {
-                        "background": alacritty_config.colors.primary.background,
-                        "foreground": alacritty_config.colors.primary.foreground,
-                        "dim_foreground": alacritty_config.colors.primary.dim_foreground,
-                    }
+                    "accent": accent,
+                    "normal": to_json(&normal)?,
+                    "bright": to_json(&bright)?,
                 }
             }
         });
 
-        self.update_theme_advanced(name, theme_data)
+        self.update_theme_advanced(name, theme_data, MergeStrategy::Replace)
     }
 
-    /// Get available app schemas for the UI
-    pub fn get_app_schemas(&self) -> Value {
-        let mut schemas = serde_json::Map::new();
+    /// Create `new_name` as a light counterpart of a dark theme: a copy of the source (so
+    /// backgrounds and app settings carry over) whose palette is inverted in HSL, with every
+    /// app config regenerated from it
+    pub fn derive_light_variant(
+        &self,
+        source_name: &str,
+        new_name: &str,
+    ) -> Result<CustomTheme, ThemeError> {
+        let source = self.get_theme(source_name)?;
+        let colors = source.colors.ok_or_else(|| {
+            ThemeError::Validation(format!(
+                "Theme '{source_name}' has no palette to derive from"
+            ))
+        })?;
+        if ColorExtractor::relative_luminance(&colors.primary.background).is_some_and(|l| l > 0.5) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{source_name}' is already a light theme"
+            )));
+        }
 
-        for app_name in self.generator_registry.get_all_apps() {
-            if let Some(schema) = self.generator_registry.get_schema_for_app(app_name) {
-                schemas.insert(app_name.to_string(), schema);
-            }
+        let (light, bright) = ColorExtractor::light_variant(&colors);
+        let (background, foreground) = (&light.primary.background, &light.primary.foreground);
+        let contrast = ColorExtractor::contrast_ratio(foreground, background).unwrap_or(0.0);
+        if contrast < 4.5 {
+            return Err(ThemeError::Validation(format!(
+                "Derived palette is not readable enough (contrast {contrast:.2}:1)"
+            )));
         }
 
-        Value::Object(schemas)
+        let to_json = |colors: &crate::types::TerminalColors| {
+            serde_json::to_value(colors)
+                .map_err(|e| ThemeError::Parse(format!("Failed to serialize palette: {e}")))
+        };
+        let mut normal = to_json(&light.terminal)?;
+        normal["black"] = Value::String(foreground.clone());
+        normal["white"] = Value::String(ColorExtractor::mix(background, foreground, 0.25));
+        let mut bright = to_json(&bright)?;
+        bright["black"] = Value::String(ColorExtractor::mix(foreground, background, 0.35));
+        bright["white"] = Value::String(ColorExtractor::mix(background, foreground, 0.15));
+
+        let mut alacritty_colors = serde_json::json!({
+            "primary": { "background": background, "foreground": foreground },
+            "normal": normal,
+            "bright": bright,
+        });
+        if let Some(accent) = &light.accent {
+            alacritty_colors["accent"] = Value::String(accent.clone());
+        }
+
+        self.duplicate_theme(source_name, new_name)?;
+        let result = self.update_theme_advanced(
+            new_name,
+            serde_json::json!({ "alacritty": { "colors": alacritty_colors } }),
+            MergeStrategy::Replace,
+        );
+        if result.is_err() {
+            let _ = fs::remove_dir_all(self.themes_dir.join(Self::sanitize_name(new_name)?));
+        }
+        result
     }
 
-    /// Get a theme by name
-    pub fn get_theme(&self, name: &str) -> Result<CustomTheme, String> {
-        let sanitized_name = Self::sanitize_name(name);
-        self.load_theme_metadata(&sanitized_name)
+    /// Advisory palette findings for a theme (contrast, duplicate slots, bright/normal order)
+    pub fn lint_theme(&self, name: &str) -> Result<Vec<LintFinding>, ThemeError> {
+        let theme = self.get_theme(name)?;
+        Ok(theme_lint::lint_palette(&theme.apps, theme.colors.as_ref()))
     }
 
-    /// List all custom themes (only returns themes with our custom metadata file)
-    pub fn list_themes(&self) -> Result<Vec<CustomTheme>, String> {
-        let mut themes = Vec::new();
+    /// Check that a theme (custom or system) is complete enough to apply cleanly: its
+    /// directory and metadata, each app's config, a background or image, and its colors
+    pub fn preflight_theme(&self, name: &str) -> Result<PreflightReport, ThemeError> {
+        let dir = theme_cache_key_in(&self.themes_dir, name);
+        let theme_dir = self.themes_dir.join(&dir);
+        let item = |check: &str, status: PreflightStatus, message: String| PreflightItem {
+            check: check.to_string(),
+            status,
+            message,
+        };
+        let mut items = Vec::new();
 
-        let entries = fs::read_dir(&self.themes_dir)
-            .map_err(|e| format!("Failed to read themes directory: {e}"))?;
+        if !theme_dir.is_dir() {
+            let message = if theme_dir.is_symlink() {
+                format!("Theme '{dir}' links to a directory that no longer exists")
+            } else {
+                format!("Theme '{dir}' not found")
+            };
+            items.push(item("directory", PreflightStatus::Fail, message));
+            return Ok(PreflightReport {
+                theme: dir,
+                ok: false,
+                items,
+            });
+        }
+        items.push(item(
+            "directory",
+            PreflightStatus::Pass,
+            format!("Found {}", theme_dir.display()),
+        ));
 
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
-            let path = entry.path();
+        let metadata_path = theme_dir.join("custom_theme.json");
+        let mut custom_theme = None;
+        if metadata_path.is_file() {
+            let parsed = fs::read_to_string(&metadata_path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<CustomTheme>(&content).map_err(|e| e.to_string())
+                });
+            items.push(match parsed {
+                Ok(theme) => {
+                    custom_theme = Some(theme);
+                    item(
+                        "metadata",
+                        PreflightStatus::Pass,
+                        "custom_theme.json is valid".to_string(),
+                    )
+                },
+                Err(e) => item(
+                    "metadata",
+                    PreflightStatus::Fail,
+                    format!("custom_theme.json can't be read: {e}"),
+                ),
+            });
+        }
 
-            if path.is_dir() {
-                if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                    // Only include themes that have our custom metadata file
-                    let metadata_path = path.join("custom_theme.json");
-                    if metadata_path.exists() {
-                        match self.load_theme_metadata(dir_name) {
-                            Ok(theme) => themes.push(theme),
-                            Err(e) => {
-                                log::warn!("Failed to load custom theme '{dir_name}': {e}")
-                            },
-                        }
-                    }
-                }
-            }
+        let mut apps = self.generator_registry.get_all_apps();
+        apps.sort_unstable();
+        for app_name in apps {
+            let Some(generator) = self.generator_registry.get_generator(app_name) else {
+                continue;
+            };
+            let file_name = generator.get_file_name();
+            let check = format!("config:{app_name}");
+            items.push(if theme_dir.join(file_name).is_file() {
+                item(
+                    &check,
+                    PreflightStatus::Pass,
+                    format!("{file_name} present"),
+                )
+            } else if PREFLIGHT_REQUIRED_APPS.contains(&app_name) {
+                item(
+                    &check,
+                    PreflightStatus::Fail,
+                    format!("{file_name} is missing"),
+                )
+            } else {
+                item(
+                    &check,
+                    PreflightStatus::Warn,
+                    format!("{file_name} is missing; {app_name} keeps its current look"),
+                )
+            });
         }
 
-        Ok(themes)
-    }
+        let has_image = |dir: &Path| {
+            fs::read_dir(dir).is_ok_and(|entries| {
+                entries.flatten().any(|entry| {
+                    let path = entry.path();
+                    path.is_file()
+                        && path.extension().is_some_and(|ext| {
+                            Self::is_supported_background_extension(&ext.to_string_lossy())
+                        })
+                })
+            })
+        };
+        items.push(
+            if has_image(&theme_dir.join("backgrounds")) || has_image(&theme_dir) {
+                item(
+                    "images",
+                    PreflightStatus::Pass,
+                    "Has a background or preview image".to_string(),
+                )
+            } else {
+                item(
+                    "images",
+                    PreflightStatus::Warn,
+                    "No background or preview image; the wallpaper won't change".to_string(),
+                )
+            },
+        );
 
-    /// Delete a theme
-    pub fn delete_theme(&self, name: &str) -> Result<(), String> {
-        let sanitized_name = Self::sanitize_name(name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let colors = match &custom_theme {
+            Some(theme) => theme
+                .colors
+                .clone()
+                .or_else(|| ColorExtractor::extract_from_custom_theme(&theme.apps)),
+            None => {
+                ColorExtractor::extract_from_alacritty_config(&theme_dir.join("alacritty.toml"))
+            },
+        };
+        items.push(match colors {
+            Some(_) => item(
+                "colors",
+                PreflightStatus::Pass,
+                "Color palette extracted".to_string(),
+            ),
+            None => item(
+                "colors",
+                PreflightStatus::Warn,
+                "No color palette could be extracted".to_string(),
+            ),
+        });
 
-        if !theme_dir.exists() {
-            return Err(format!("Theme '{name}' not found"));
-        }
+        Ok(PreflightReport {
+            theme: dir,
+            ok: items
+                .iter()
+                .all(|item| item.status != PreflightStatus::Fail),
+            items,
+        })
+    }
 
-        fs::remove_dir_all(&theme_dir)
-            .map_err(|e| format!("Failed to delete theme directory: {e}"))?;
+    /// Contents of the config file a theme has for `app_name`, as generated on disk
+    pub fn read_theme_config(&self, name: &str, app_name: &str) -> Result<String, ThemeError> {
+        let generator = self
+            .generator_registry
+            .get_generator(app_name)
+            .ok_or_else(|| ThemeError::NotFound(format!("Unknown app '{app_name}'")))?;
+        let dir = theme_cache_key_in(&self.themes_dir, name);
+        let theme_dir = self.themes_dir.join(&dir);
+        if !theme_dir.is_dir() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
+        }
 
-        Ok(())
+        let file_name = generator.get_file_name();
+        let config_path = theme_dir.join(file_name);
+        if !config_path.is_file() {
+            return Err(ThemeError::NotFound(format!(
+                "Theme '{name}' has no {file_name}; the {app_name} config hasn't been generated"
+            )));
+        }
+        fs::read_to_string(&config_path)
+            .map_err(|e| ThemeError::Io(format!("Failed to read {file_name}: {e}")))
     }
 
-    /// Initialize a new custom theme by copying template files
-    pub fn init_theme(&self, name: String, description: String) -> Result<CustomTheme, String> {
-        let sanitized_name = Self::sanitize_name(&name);
+    /// Rebuild the config files of every app a theme has data for from its stored metadata,
+    /// without changing the metadata itself
+    pub fn regenerate_configs(&self, name: &str) -> Result<RegeneratedConfigs, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
         let theme_dir = self.themes_dir.join(&sanitized_name);
-
-        // Check if theme already exists
-        if theme_dir.exists() {
-            return Err(format!("Theme '{name}' already exists"));
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{name}' is a read-only system theme"
+            )));
         }
-
-        // Create theme directory
-        fs::create_dir_all(&theme_dir)
-            .map_err(|e| format!("Failed to create theme directory: {e}"))?;
-
-        // Copy template files
-        self.copy_template_files(&theme_dir, &name, &description)?;
-
-        // Load the created theme metadata (this will automatically extract colors)
         let theme = self.load_theme_metadata(&sanitized_name)?;
+        RawFilesGenerator::files(&theme.apps).map_err(ThemeError::Validation)?;
+        let generator_input = self.generator_input(&theme);
+
+        let mut apps = self.generator_registry.get_all_apps();
+        apps.sort_unstable();
+        let mut report = RegeneratedConfigs::default();
+        for app_name in apps {
+            let Some(generator) = self.generator_registry.get_generator(app_name) else {
+                continue;
+            };
+            if theme.apps.get(app_name).is_none() {
+                report.skipped.push(app_name.to_string());
+                continue;
+            }
+            let config_path = theme_dir.join(generator.get_file_name());
+            match Self::stream_config_to_file(generator, &generator_input, &config_path)? {
+                Ok(()) => report.regenerated.push(app_name.to_string()),
+                Err(e) => report.failed.push(format!("{app_name}: {e}")),
+            }
+        }
+
+        RawFilesGenerator::write_files(&theme_dir, &theme.apps).map_err(ThemeError::Io)?;
 
         log::info!(
-            "Initialized custom theme '{}' in directory: {}",
-            name,
-            theme_dir.display()
+            "Regenerated {} configs for theme '{name}'",
+            report.regenerated.len()
         );
-
-        Ok(theme)
+        Ok(report)
     }
 
-    /// Copy all template files to the new theme directory
-    fn copy_template_files(
+    /// Rewrite every app's config file and raw file from a theme's metadata
+    fn regenerate_app_configs(
         &self,
         theme_dir: &Path,
-        name: &str,
-        description: &str,
-    ) -> Result<(), String> {
-        // Get template directory path from Tauri resources
-        let resource_dir = self
-            .app_handle
-            .path()
-            .resource_dir()
-            .map_err(|e| format!("Failed to get resource directory: {e}"))?;
+        theme: &CustomTheme,
+    ) -> Result<(), ThemeError> {
+        // Reject unsafe raw file paths before anything is written
+        RawFilesGenerator::files(&theme.apps).map_err(ThemeError::Validation)?;
+        let generator_input = self.generator_input(theme);
 
-        // The resources are copied to target/debug/resources/ in development
-        let template_dir = resource_dir.join("resources").join("template");
-
-        if !template_dir.exists() {
-            return Err(format!(
-                "Template directory not found in resources at: {}",
-                template_dir.display()
-            ));
+        for app_name in self.generator_registry.get_all_apps() {
+            if let Some(generator) = self.generator_registry.get_generator(app_name) {
+                let config_path = theme_dir.join(generator.get_file_name());
+                log::debug!("Writing {} config to {}", app_name, config_path.display());
+                if let Err(e) =
+                    Self::stream_config_to_file(generator, &generator_input, &config_path)?
+                {
+                    log::warn!("Failed to generate {app_name} config: {e}");
+                }
+            }
         }
 
-        self.copy_dir_recursive(&template_dir, theme_dir, name, description)?;
+        RawFilesGenerator::write_files(theme_dir, &theme.apps).map_err(ThemeError::Io)?;
 
         Ok(())
     }
 
-    /// Recursively copy directory contents and replace placeholders in custom_theme.json
-    fn copy_dir_recursive(
-        &self,
-        src: &Path,
-        dst: &Path,
-        name: &str,
-        description: &str,
-    ) -> Result<(), String> {
-        let entries =
-            fs::read_dir(src).map_err(|e| format!("Failed to read template directory: {e}"))?;
-
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
-            let src_path = entry.path();
-            let dst_path = dst.join(entry.file_name());
+    /// Copy the current custom_theme.json into `backups/<timestamp>.json`, keeping the newest few
+    fn snapshot_theme_metadata(&self, theme_dir: &Path) -> Result<(), ThemeError> {
+        let metadata_path = theme_dir.join("custom_theme.json");
+        if self.max_theme_backups == 0 || !metadata_path.is_file() {
+            return Ok(());
+        }
 
-            if src_path.is_dir() {
-                // Create directory and copy contents recursively
-                fs::create_dir_all(&dst_path)
-                    .map_err(|e| format!("Failed to create directory: {e}"))?;
-                self.copy_dir_recursive(&src_path, &dst_path, name, description)?;
-            } else {
-                // Check if this is the custom_theme.json template
-                if entry.file_name() == "custom_theme.json" {
-                    self.copy_and_process_metadata_template(
-                        &src_path,
-                        &dst_path,
-                        name,
-                        description,
-                    )?;
-                } else {
-                    // Copy file normally
-                    fs::copy(&src_path, &dst_path).map_err(|e| {
-                        format!("Failed to copy file {}: {}", src_path.display(), e)
-                    })?;
-                }
+        let backups_dir = theme_dir.join(BACKUPS_DIR);
+        fs::create_dir_all(&backups_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create backups directory: {e}")))?;
+
+        let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+        // The counter keeps snapshots taken within the same millisecond in order
+        let mut counter = 0;
+        let mut backup_path = backups_dir.join(format!("{timestamp}-{counter:03}.json"));
+        while backup_path.exists() {
+            counter += 1;
+            backup_path = backups_dir.join(format!("{timestamp}-{counter:03}.json"));
+        }
+        fs::copy(&metadata_path, &backup_path)
+            .map_err(|e| ThemeError::Io(format!("Failed to back up theme metadata: {e}")))?;
+
+        let backups = Self::list_backup_files(theme_dir)?;
+        let excess = backups.len().saturating_sub(self.max_theme_backups);
+        for old_backup in &backups[..excess] {
+            if let Err(e) = fs::remove_file(old_backup) {
+                log::warn!("Failed to prune backup {}: {e}", old_backup.display());
             }
         }
 
         Ok(())
     }
 
-    /// Copy and process the custom_theme.json template with placeholder replacement
-    fn copy_and_process_metadata_template(
-        &self,
-        src: &Path,
-        dst: &Path,
-        name: &str,
-        _description: &str,
-    ) -> Result<(), String> {
-        let template_content = fs::read_to_string(src)
-            .map_err(|e| format!("Failed to read metadata template: {e}"))?;
+    /// Metadata snapshots of a theme, oldest first
+    fn list_backup_files(theme_dir: &Path) -> Result<Vec<PathBuf>, ThemeError> {
+        let backups_dir = theme_dir.join(BACKUPS_DIR);
+        if !backups_dir.is_dir() {
+            return Ok(Vec::new());
+        }
 
-        let now = chrono::Utc::now().to_rfc3339();
+        let mut backups: Vec<PathBuf> = fs::read_dir(&backups_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to read backups directory: {e}")))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        backups.sort();
+        Ok(backups)
+    }
 
-        // Replace placeholders (no description)
-        let processed_content = template_content
-            .replace("{{THEME_NAME}}", name)
-            .replace("{{CREATED_AT}}", &now)
-            .replace("{{MODIFIED_AT}}", &now);
+    /// Metadata snapshots of a theme that can be restored, newest first.
+    /// Snapshots that can't be parsed are skipped.
+    pub fn list_theme_backups(&self, name: &str) -> Result<Vec<ThemeBackup>, ThemeError> {
+        let theme_dir = self.themes_dir.join(Self::sanitize_name(name)?);
+        if !theme_dir.exists() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
+        }
 
-        fs::write(dst, processed_content)
-            .map_err(|e| format!("Failed to write processed metadata: {e}"))?;
+        let mut backups = Vec::new();
+        for backup in Self::list_backup_files(&theme_dir)?.into_iter().rev() {
+            let snapshot = fs::read_to_string(&backup)
+                .ok()
+                .and_then(|content| serde_json::from_str::<CustomTheme>(&content).ok());
+            let (Some(snapshot), Some(timestamp)) =
+                (snapshot, backup.file_stem().and_then(|stem| stem.to_str()))
+            else {
+                log::warn!("Skipping unreadable snapshot {}", backup.display());
+                continue;
+            };
+            backups.push(ThemeBackup {
+                timestamp: timestamp.to_string(),
+                modified_at: snapshot.modified_at,
+            });
+        }
 
-        Ok(())
+        Ok(backups)
     }
 
-    /// Load theme metadata from JSON file
-    fn load_theme_metadata(&self, sanitized_name: &str) -> Result<CustomTheme, String> {
-        let theme_dir = self.themes_dir.join(sanitized_name);
-        let metadata_path = theme_dir.join("custom_theme.json");
-
-        let content = fs::read_to_string(&metadata_path)
-            .map_err(|e| format!("Failed to read theme metadata: {e}"))?;
+    /// Roll a theme back to one of its metadata snapshots and regenerate its app configs.
+    /// The state being replaced is backed up first, so a restore can itself be undone.
+    pub fn restore_theme_backup(
+        &self,
+        name: &str,
+        timestamp: &str,
+    ) -> Result<CustomTheme, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if !theme_dir.exists() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
+        }
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{name}' is a read-only system theme"
+            )));
+        }
 
+        // Only snapshots listed in the backups directory are accepted, never arbitrary paths
+        let backup_path = Self::list_backup_files(&theme_dir)?
+            .into_iter()
+            .find(|path| path.file_stem().is_some_and(|stem| stem == timestamp))
+            .ok_or_else(|| {
+                ThemeError::NotFound(format!("Backup '{timestamp}' not found for theme '{name}'"))
+            })?;
+        let content = fs::read_to_string(&backup_path)
+            .map_err(|e| ThemeError::Io(format!("Failed to read theme backup: {e}")))?;
         let mut theme: CustomTheme = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse theme metadata: {e}"))?;
+            .map_err(|e| ThemeError::Parse(format!("Failed to parse theme backup: {e}")))?;
 
-        // If colors are missing (backwards compatibility), extract them now
-        if theme.colors.is_none() {
-            theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
+        // A rename since the snapshot keeps the current name
+        theme.name = self.load_theme_metadata(&sanitized_name)?.name;
+        theme.modified_at = chrono::Utc::now().to_rfc3339();
+        migrate_app_keys(&mut theme.apps);
+        ColorExtractor::trim_color_values(&mut theme.apps);
+        theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
 
-            // Save the updated metadata with colors
-            if let Ok(updated_content) = serde_json::to_string_pretty(&theme) {
-                if let Err(e) = fs::write(&metadata_path, updated_content) {
-                    log::warn!("Failed to update theme metadata with colors: {e}");
-                }
-            }
+        self.regenerate_app_configs(&theme_dir, &theme)?;
+        if let Err(e) = self.snapshot_theme_metadata(&theme_dir) {
+            log::warn!("Failed to snapshot metadata of '{name}': {e}");
         }
+        self.write_theme_metadata(&theme_dir, &theme)?;
 
+        log::info!("Restored theme '{name}' from backup {timestamp}");
         Ok(theme)
     }
-    /// Get list of background images for a theme
-    pub fn get_theme_backgrounds(&self, theme_name: &str) -> Result<Vec<String>, String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
-        let backgrounds_dir = theme_dir.join("backgrounds");
 
-        if !backgrounds_dir.exists() {
-            return Ok(Vec::new());
+    /// Palettes from a theme's metadata snapshots in chronological order.
+    /// Snapshots whose colors can't be recovered are skipped.
+    pub fn get_theme_colors_history(
+        &self,
+        name: &str,
+    ) -> Result<Vec<ThemeColorsSnapshot>, ThemeError> {
+        let theme_dir = self.themes_dir.join(Self::sanitize_name(name)?);
+        if !theme_dir.exists() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
         }
 
-        let mut backgrounds = Vec::new();
-        let entries = fs::read_dir(&backgrounds_dir)
-            .map_err(|e| format!("Failed to read backgrounds directory: {e}"))?;
+        let mut history = Vec::new();
+        for backup in Self::list_backup_files(&theme_dir)? {
+            let snapshot = fs::read_to_string(&backup)
+                .ok()
+                .and_then(|content| serde_json::from_str::<CustomTheme>(&content).ok());
+            let Some(mut snapshot) = snapshot else {
+                log::warn!("Skipping unreadable snapshot {}", backup.display());
+                continue;
+            };
+
+            ColorExtractor::trim_color_values(&mut snapshot.apps);
+            let colors = snapshot
+                .colors
+                .or_else(|| ColorExtractor::extract_from_custom_theme(&snapshot.apps));
+            if let Some(colors) = colors {
+                history.push(ThemeColorsSnapshot {
+                    modified_at: snapshot.modified_at,
+                    colors,
+                });
+            }
+        }
 
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
-            let path = entry.path();
+        Ok(history)
+    }
 
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    let ext = extension.to_string_lossy().to_lowercase();
-                    if matches!(
-                        ext.as_str(),
-                        "jpg" | "jpeg" | "png" | "webp" | "bmp" | "gif"
-                    ) {
-                        if let Some(filename) = path.file_name() {
-                            backgrounds.push(filename.to_string_lossy().to_string());
-                        }
-                    }
-                }
-            }
+    /// Build generator input from a theme's apps, exposing the extracted accent to
+    /// generators that highlight with it
+    fn generator_input(&self, theme: &CustomTheme) -> Value {
+        let mut generator_input = theme.apps.clone();
+        let accent = theme.colors.as_ref().and_then(|c| c.accent.clone());
+        self.add_generator_context(&mut generator_input, accent);
+        generator_input
+    }
+
+    /// Add the accent and the configured fallback palette next to the app configs
+    fn add_generator_context(&self, generator_input: &mut Value, accent: Option<String>) {
+        let Some(apps) = generator_input.as_object_mut() else {
+            return;
+        };
+        if let Some(accent) = accent {
+            apps.entry("accent").or_insert(Value::String(accent));
+        }
+        if let Ok(fallback) = serde_json::to_value(&self.fallback_colors) {
+            apps.entry(FALLBACK_COLORS_KEY).or_insert(fallback);
         }
+    }
 
-        backgrounds.sort();
-        Ok(backgrounds)
+    /// Remove and return the `meta` section of incoming theme data; it describes the theme
+    /// rather than an app, so it never lands in `apps`
+    fn take_theme_meta(theme_data: &mut Value) -> Option<Value> {
+        theme_data.as_object_mut()?.remove("meta")
     }
 
-    /// Add background images to a theme by copying files
-    pub fn add_theme_backgrounds(
-        &self,
-        theme_name: &str,
-        source_paths: Vec<String>,
-    ) -> Result<Vec<String>, String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
+    /// Apply the `tags`, `author`, `version` and `description` keys present in `meta`;
+    /// `null` clears a field
+    fn apply_theme_meta(theme: &mut CustomTheme, meta: &Value) {
+        let text = |value: &Value| {
+            value
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
 
-        if !theme_dir.exists() {
-            return Err(format!("Theme '{theme_name}' not found"));
+        if let Some(tags) = meta.get("tags") {
+            theme.tags = Self::normalize_tags(
+                tags.as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|tag| tag.as_str().map(str::to_string)),
+            );
+        }
+        if let Some(author) = meta.get("author") {
+            theme.author = text(author);
+        }
+        if let Some(version) = meta.get("version") {
+            theme.version = text(version);
         }
+        if let Some(description) = meta.get("description") {
+            theme.description = text(description).unwrap_or_default();
+        }
+    }
 
-        let backgrounds_dir = theme_dir.join("backgrounds");
+    /// Trimmed, non-empty tags with duplicates (ignoring case) dropped, in first-seen order
+    fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                normalized.push(tag.to_string());
+            }
+        }
+        normalized
+    }
 
-        // Create backgrounds directory if it doesn't exist
-        fs::create_dir_all(&backgrounds_dir)
-            .map_err(|e| format!("Failed to create backgrounds directory: {e}"))?;
+    /// Bundle a theme directory into a zip whose top-level folder is the sanitized name.
+    /// `dest_path` may be a directory, in which case `<sanitized>.zip` is created inside it.
+    /// Returns the path of the written archive.
+    pub fn export_theme(&self, name: &str, dest_path: &Path) -> Result<PathBuf, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
 
-        let mut copied_files = Vec::new();
+        if !theme_dir.join("custom_theme.json").is_file() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
+        }
 
-        for source_path in source_paths {
-            let source = Path::new(&source_path);
+        let archive_path = if dest_path.is_dir() {
+            dest_path.join(format!("{sanitized_name}.zip"))
+        } else {
+            dest_path.to_path_buf()
+        };
 
-            if !source.exists() {
-                log::warn!("Source file does not exist: {source_path}");
-                continue;
+        theme_archive::write_theme_archive(&theme_dir, &sanitized_name, &archive_path)
+            .map_err(ThemeError::Io)?;
+
+        log::info!("Exported theme '{name}' to {}", archive_path.display());
+        Ok(archive_path)
+    }
+
+    /// Move `staged` into the place of the existing `theme_dir`. The old directory is moved
+    /// aside first and put back if the new one can't be moved in, so it is never lost.
+    fn replace_theme_dir(&self, staged: &Path, theme_dir: &Path) -> Result<(), ThemeError> {
+        let aside = self
+            .themes_dir
+            .join(format!(".replaced-{}", uuid::Uuid::new_v4()));
+        fs::rename(theme_dir, &aside)
+            .map_err(|e| ThemeError::Io(format!("Failed to replace existing theme: {e}")))?;
+
+        if let Err(e) = fs::rename(staged, theme_dir) {
+            if let Err(restore_err) = fs::rename(&aside, theme_dir) {
+                log::error!(
+                    "Failed to restore theme {} from {}: {restore_err}",
+                    theme_dir.display(),
+                    aside.display()
+                );
             }
+            return Err(ThemeError::Io(format!("Failed to install theme: {e}")));
+        }
+
+        if let Err(e) = fs::remove_dir_all(&aside) {
+            log::error!("Failed to remove replaced theme {}: {e}", aside.display());
+        }
+        Ok(())
+    }
+
+    /// Remove the staging directories an interrupted create, import or overwrite left behind.
+    /// A theme moved aside by an overwrite is moved back instead when nothing took its place.
+    /// Returns how many directories were cleaned up.
+    pub fn clean_up_staging_dirs(&self) -> usize {
+        let Ok(entries) = fs::read_dir(&self.themes_dir) else {
+            return 0;
+        };
 
-            if !source.is_file() {
-                log::warn!("Source path is not a file: {source_path}");
+        let mut cleaned = 0;
+        for entry in entries.flatten() {
+            let dir_name = entry.file_name().to_string_lossy().into_owned();
+            let Some(prefix) = STAGING_DIR_PREFIXES
+                .iter()
+                .find(|prefix| dir_name.starts_with(*prefix))
+            else {
                 continue;
-            }
+            };
+            let path = entry.path();
 
-            // Validate file extension
-            if let Some(extension) = source.extension() {
-                let ext = extension.to_string_lossy().to_lowercase();
-                if !matches!(
-                    ext.as_str(),
-                    "jpg" | "jpeg" | "png" | "webp" | "bmp" | "gif"
-                ) {
-                    log::warn!("Unsupported image format: {source_path}");
+            if *prefix == ".replaced-" {
+                let original = self
+                    .load_theme_metadata(&dir_name)
+                    .ok()
+                    .and_then(|theme| Self::sanitize_name(&theme.name).ok())
+                    .map(|name| self.themes_dir.join(name))
+                    .filter(|original| !original.exists());
+                if let Some(original) = original {
+                    match fs::rename(&path, &original) {
+                        Ok(()) => {
+                            log::warn!("Restored interrupted overwrite of {}", original.display());
+                            cleaned += 1;
+                        },
+                        Err(e) => log::error!("Failed to restore {}: {e}", original.display()),
+                    }
                     continue;
                 }
-            } else {
-                log::warn!("File has no extension: {source_path}");
-                continue;
             }
 
-            // Get filename and create destination path
-            if let Some(filename) = source.file_name() {
-                let dest_path = backgrounds_dir.join(filename);
-
-                // Copy the file
-                match fs::copy(source, &dest_path) {
-                    Ok(_) => {
-                        copied_files.push(filename.to_string_lossy().to_string());
-                        log::debug!(
-                            "Copied background image: {} -> {}",
-                            source_path,
-                            dest_path.display()
-                        );
-                    },
-                    Err(e) => {
-                        log::warn!("Failed to copy {source_path}: {e}");
-                    },
-                }
+            match fs::remove_dir_all(&path) {
+                Ok(()) => {
+                    log::info!("Removed leftover staging directory {}", path.display());
+                    cleaned += 1;
+                },
+                Err(e) => log::error!("Failed to remove {}: {e}", path.display()),
             }
         }
+        cleaned
+    }
 
-        Ok(copied_files)
+    /// Install a theme from a zip archive made by `export_theme` (or laid out the same way).
+    /// An existing theme of the same name is only replaced when `overwrite` is set.
+    pub fn import_theme(
+        &self,
+        archive_path: &Path,
+        overwrite: bool,
+    ) -> Result<CustomTheme, ThemeError> {
+        let staging_dir = self
+            .themes_dir
+            .join(format!(".import-{}", uuid::Uuid::new_v4()));
+        let result = self.install_staged_archive(archive_path, &staging_dir, overwrite);
+        if staging_dir.exists() {
+            let _ = fs::remove_dir_all(&staging_dir);
+        }
+        result
     }
 
-    /// Remove a background image from a theme
-    pub fn remove_theme_background(&self, theme_name: &str, filename: &str) -> Result<(), String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
-        let backgrounds_dir = theme_dir.join("backgrounds");
-        let file_path = backgrounds_dir.join(filename);
+    fn install_staged_archive(
+        &self,
+        archive_path: &Path,
+        staging_dir: &Path,
+        overwrite: bool,
+    ) -> Result<CustomTheme, ThemeError> {
+        theme_archive::extract_theme_archive(archive_path, staging_dir)
+            .map_err(ThemeError::Parse)?;
+
+        let content = fs::read_to_string(staging_dir.join("custom_theme.json"))
+            .map_err(|e| ThemeError::Io(format!("Failed to read theme metadata: {e}")))?;
+        let staged: CustomTheme = serde_json::from_str(&content)
+            .map_err(|e| ThemeError::Parse(format!("Failed to parse theme metadata: {e}")))?;
+        let sanitized_name = Self::sanitize_name(&staged.name)?;
 
-        if !file_path.exists() {
-            return Err(format!("Background image '{filename}' not found"));
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if let Ok(metadata) = fs::symlink_metadata(&theme_dir) {
+            if metadata.file_type().is_symlink() {
+                return Err(ThemeError::AlreadyExists(format!(
+                    "Theme name '{}' is taken by a system theme",
+                    staged.name
+                )));
+            }
+            if !overwrite {
+                return Err(ThemeError::AlreadyExists(format!(
+                    "Theme '{}' already exists",
+                    staged.name
+                )));
+            }
+            self.replace_theme_dir(staging_dir, &theme_dir)?;
+        } else {
+            fs::rename(staging_dir, &theme_dir)
+                .map_err(|e| ThemeError::Io(format!("Failed to install theme: {e}")))?;
         }
 
-        fs::remove_file(&file_path)
-            .map_err(|e| format!("Failed to remove background image: {e}"))?;
+        // Bring the import in line with locally created themes
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+        theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
+        let generator_input = self.generator_input(&theme);
+        for app_name in self.generator_registry.get_all_apps() {
+            let Some(generator) = self.generator_registry.get_generator(app_name) else {
+                continue;
+            };
+            let config_path = theme_dir.join(generator.get_file_name());
+            if theme.apps.get(app_name).is_none() || config_path.exists() {
+                continue;
+            }
+            if let Err(e) = Self::stream_config_to_file(generator, &generator_input, &config_path)?
+            {
+                log::warn!("Failed to generate {app_name} config for imported theme: {e}");
+            }
+        }
+        self.write_theme_metadata(&theme_dir, &theme)?;
 
-        log::debug!("Removed background image: {}", file_path.display());
-        Ok(())
+        log::info!(
+            "Imported theme '{}' from {}",
+            theme.name,
+            archive_path.display()
+        );
+        Ok(theme)
     }
 
-    /// Get background image data as base64 for preview
-    pub fn get_background_image_data(
+    /// Export a custom theme into `output_dir/<theme-dir>` using the upstream Omarchy layout:
+    /// generated configs under their upstream filenames plus `backgrounds/`, without the
+    /// internal `custom_theme.json`. Returns the produced files relative to the export dir.
+    pub fn export_theme_for_omarchy(
         &self,
-        theme_name: &str,
-        filename: &str,
-    ) -> Result<String, String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
+        name: &str,
+        output_dir: &Path,
+    ) -> Result<Vec<String>, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
         let theme_dir = self.themes_dir.join(&sanitized_name);
-        let backgrounds_dir = theme_dir.join("backgrounds");
-        let file_path = backgrounds_dir.join(filename);
 
-        if !file_path.exists() {
-            return Err(format!("Background image '{filename}' not found"));
+        if !theme_dir.exists() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
         }
 
-        // Read the file and convert to base64
-        let image_data =
-            fs::read(&file_path).map_err(|e| format!("Failed to read background image: {e}"))?;
+        let theme = self.load_theme_metadata(&sanitized_name)?;
+        let export_dir = output_dir.join(&sanitized_name);
+        if export_dir.exists() {
+            return Err(ThemeError::AlreadyExists(format!(
+                "Export directory already exists: {}",
+                export_dir.display()
+            )));
+        }
+        fs::create_dir_all(&export_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create export directory: {e}")))?;
 
-        // Determine MIME type based on file extension
-        let mime_type = match file_path.extension().and_then(|ext| ext.to_str()) {
-            Some("jpg") | Some("jpeg") => "image/jpeg",
-            Some("png") => "image/png",
-            Some("webp") => "image/webp",
-            Some("bmp") => "image/bmp",
-            Some("gif") => "image/gif",
-            _ => "image/jpeg", // default fallback
-        };
+        let generator_input = self.generator_input(&theme);
+        let mut apps = self.generator_registry.get_all_apps();
+        apps.sort();
 
-        // Encode as base64 data URL using our optimized implementation
-        let base64_data = Self::base64_encode(&image_data);
-        Ok(format!("data:{mime_type};base64,{base64_data}"))
-    }
+        let mut produced = Vec::new();
+        for app_name in apps {
+            let Some(generator) = self.generator_registry.get_generator(app_name) else {
+                continue;
+            };
+            let file_name = generator.get_file_name();
+            match Self::stream_config_to_file(
+                generator,
+                &generator_input,
+                &export_dir.join(file_name),
+            )? {
+                Ok(()) => produced.push(file_name.to_string()),
+                Err(e) => log::warn!("Skipping {app_name} config in export of '{name}': {e}"),
+            }
+        }
 
-    /// Optimized base64 encoding function
-    fn base64_encode(data: &[u8]) -> String {
-        if data.is_empty() {
-            return String::new();
+        let backgrounds = self.get_theme_backgrounds(name)?;
+        if !backgrounds.is_empty() {
+            let export_backgrounds = export_dir.join("backgrounds");
+            fs::create_dir_all(&export_backgrounds).map_err(|e| {
+                ThemeError::Io(format!("Failed to create backgrounds directory: {e}"))
+            })?;
+            for filename in backgrounds {
+                fs::copy(
+                    theme_dir.join("backgrounds").join(&filename),
+                    export_backgrounds.join(&filename),
+                )
+                .map_err(|e| {
+                    ThemeError::Io(format!("Failed to copy background '{filename}': {e}"))
+                })?;
+                produced.push(format!("backgrounds/{filename}"));
+            }
         }
 
-        const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        log::info!(
+            "Exported theme '{name}' to {} ({} files)",
+            export_dir.display(),
+            produced.len()
+        );
 
-        // Pre-allocate with exact capacity to avoid reallocations
-        let output_len = data.len().div_ceil(3) * 4;
-        let mut result = String::with_capacity(output_len);
+        Ok(produced)
+    }
 
-        for chunk in data.chunks(3) {
-            let mut buf = [0u8; 3];
-            for (i, &byte) in chunk.iter().enumerate() {
-                buf[i] = byte;
-            }
+    /// Set raw text appended after an app's generated config and regenerate the theme
+    pub fn set_app_raw_append(
+        &self,
+        theme_name: &str,
+        app_name: &str,
+        text: String,
+    ) -> Result<CustomTheme, ThemeError> {
+        let generator = self
+            .generator_registry
+            .get_generator(app_name)
+            .ok_or_else(|| ThemeError::NotFound(format!("Unknown app '{app_name}'")))?;
+
+        if !generator.supports_raw_append() {
+            return Err(ThemeError::Validation(format!(
+                "App '{app_name}' does not support raw config text"
+            )));
+        }
 
-            let b = ((buf[0] as u32) << 16) | ((buf[1] as u32) << 8) | (buf[2] as u32);
+        let mut app_data = serde_json::Map::new();
+        app_data.insert(
+            app_name.to_string(),
+            serde_json::json!({ "raw_append": text }),
+        );
+        self.update_theme_advanced(theme_name, Value::Object(app_data), MergeStrategy::Replace)
+    }
 
-            result.push(CHARS[((b >> 18) & 63) as usize] as char);
-            result.push(CHARS[((b >> 12) & 63) as usize] as char);
-            result.push(if chunk.len() > 1 {
-                CHARS[((b >> 6) & 63) as usize] as char
-            } else {
-                '='
-            });
-            result.push(if chunk.len() > 2 {
-                CHARS[(b & 63) as usize] as char
-            } else {
-                '='
-            });
-        }
+    /// Check whether a theme directory is a symlinked system theme without custom metadata
+    fn is_read_only_system_theme(theme_dir: &Path) -> bool {
+        let is_symlink = fs::symlink_metadata(theme_dir)
+            .map(|m| m.file_type().is_symlink())
+            .unwrap_or(false);
 
-        result
+        is_symlink && !theme_dir.join("custom_theme.json").is_file()
     }
-}
 
-// Tauri commands
-#[tauri::command]
-pub async fn create_custom_theme(
-    app_handle: AppHandle,
-    name: String,
-    background: String,
-    foreground: String,
-) -> Result<CustomTheme, String> {
-    log::info!("Creating custom theme '{name}' with colors: bg={background}, fg={foreground}");
-    let service = CustomThemeService::new(&app_handle)?;
-    let result = service.create_theme(name.clone(), background, foreground);
+    /// Fork a read-only system theme into a writable custom theme and return the fork's name.
+    /// Repeated calls reuse the existing fork so later edits land in the same place.
+    pub fn move_theme_to_custom_writable(&self, name: &str) -> Result<String, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let source_dir = self.themes_dir.join(&sanitized_name);
 
-    // Invalidate cache for the created theme
-    if result.is_ok() {
-        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
-            cache.invalidate_theme(&name).await;
-            // Trigger background refresh to include the new theme
-            let _ = cache.trigger_background_refresh().await;
+        if !source_dir.exists() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
         }
-    }
 
-    result
-}
+        if !Self::is_read_only_system_theme(&source_dir) {
+            return Ok(sanitized_name);
+        }
 
-#[tauri::command]
-pub async fn create_custom_theme_advanced(
-    app_handle: AppHandle,
-    name: String,
-    theme_data: Value,
-) -> Result<CustomTheme, String> {
-    log::info!("Creating advanced custom theme '{name}'");
-    let service = CustomThemeService::new(&app_handle)?;
-    let result = service.create_theme_advanced(name.clone(), theme_data);
+        let fork_name = format!("{sanitized_name}-custom");
+        let fork_dir = self.themes_dir.join(&fork_name);
 
-    // Invalidate cache for the created theme
-    if result.is_ok() {
-        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
-            cache.invalidate_theme(&name).await;
-            // Trigger background refresh to include the new theme
-            let _ = cache.trigger_background_refresh().await;
+        if fork_dir.join("custom_theme.json").is_file() {
+            log::debug!("Redirecting edit of system theme '{name}' to fork '{fork_name}'");
+            return Ok(fork_name);
         }
-    }
 
-    result
-}
+        if fork_dir.exists() {
+            return Err(ThemeError::AlreadyExists(format!(
+                "Cannot fork theme '{name}': '{fork_name}' already exists"
+            )));
+        }
 
-#[tauri::command]
-pub async fn update_custom_theme(
-    app_handle: AppHandle,
+        fs::create_dir_all(&fork_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create theme directory: {e}")))?;
+        self.copy_dir_recursive(&source_dir, &fork_dir, &fork_name, "")?;
+
+        // Seed app data from the copied configs so the fork keeps the system theme's look
+        let mut apps = serde_json::Map::new();
+        for app_name in self.generator_registry.get_all_apps() {
+            if let Some(generator) = self.generator_registry.get_generator(app_name) {
+                let config_path = fork_dir.join(generator.get_file_name());
+                let Ok(content) = fs::read_to_string(&config_path) else {
+                    continue;
+                };
+                let parsed = if app_name == "alacritty" {
+                    toml::from_str::<Value>(&content).map_err(|e| e.to_string())
+                } else {
+                    generator.parse_existing_config(&content)
+                };
+                match parsed {
+                    Ok(value) if value.as_object().is_some_and(|o| !o.is_empty()) => {
+                        apps.insert(app_name.to_string(), value);
+                    },
+                    Ok(_) => {},
+                    Err(e) => log::warn!("Failed to parse {app_name} config of '{name}': {e}"),
+                }
+            }
+        }
+        let apps = Value::Object(apps);
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let theme = CustomTheme {
+            name: fork_name.clone(),
+            created_at: now.clone(),
+            modified_at: now,
+            colors: self.extract_theme_colors(&fork_dir, &apps),
+            apps,
+            selected_background: None,
+            tags: Vec::new(),
+            author: None,
+            version: None,
+            description: String::new(),
+            preview: None,
+        };
+
+        self.write_theme_metadata(&fork_dir, &theme)?;
+
+        log::info!("Forked system theme '{name}' into custom theme '{fork_name}'");
+
+        Ok(fork_name)
+    }
+
+    /// Deep-merge JSON values: when both sides are objects, merge keys recursively.
+    /// A `null` in the source removes that key from the target object (at any depth).
+    /// When both sides are arrays, `MergeStrategy::ConcatArrays` appends the source
+    /// elements; `MergeStrategy::Replace` overwrites like any other value.
+    /// Otherwise, overwrite target with source.
+    fn deep_merge(target: &mut Value, src: &Value, strategy: MergeStrategy) {
+        use serde_json::Value::*;
+        match (target, src) {
+            (Object(t_map), Object(s_map)) => {
+                for (k, v) in s_map {
+                    match (t_map.get_mut(k), v) {
+                        (_, Null) => {
+                            t_map.remove(k);
+                        },
+                        (Some(t_child), Object(_) | Array(_)) => {
+                            Self::deep_merge(t_child, v, strategy);
+                        },
+                        (Some(t_child), _) => {
+                            *t_child = v.clone();
+                        },
+                        (None, Object(_)) => {
+                            // Merge into an empty object so nested nulls are dropped too
+                            let mut child = Object(serde_json::Map::new());
+                            Self::deep_merge(&mut child, v, strategy);
+                            t_map.insert(k.clone(), child);
+                        },
+                        (None, _) => {
+                            t_map.insert(k.clone(), v.clone());
+                        },
+                    }
+                }
+            },
+            (Array(t_items), Array(s_items)) if strategy == MergeStrategy::ConcatArrays => {
+                t_items.extend(s_items.iter().cloned());
+            },
+            (t, s) => {
+                *t = s.clone();
+            },
+        }
+    }
+
+    /// Update an existing theme (legacy method for backwards compatibility)
+    pub fn update_theme(
+        &self,
+        name: &str,
+        alacritty_config: AlacrittyConfig,
+    ) -> Result<CustomTheme, ThemeError> {
+        let primary = alacritty_config.colors.primary;
+        let [background, foreground, dim_foreground] = Self::normalize_legacy_colors([
+            ("background", primary.background),
+            ("foreground", primary.foreground),
+            ("dim_foreground", primary.dim_foreground),
+        ])?;
+
+        // Convert legacy config to new format
+        let theme_data = serde_json::json!({
+            "alacritty": {
+                "colors": {
+                    "primary": {
+                        "background": background,
+                        "foreground": foreground,
+                        "dim_foreground": dim_foreground,
+                    }
+                }
+            }
+        });
+
+        self.update_theme_advanced(name, theme_data, MergeStrategy::Replace)
+    }
+
+    /// Validate a theme's generated configs with each app's own checker when it is installed
+    pub fn deep_validate_theme(&self, name: &str) -> Result<Vec<AppValidationResult>, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+
+        if !theme_dir.exists() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
+        }
+
+        let mut apps = self.generator_registry.get_all_apps();
+        apps.sort();
+
+        let mut results = Vec::new();
+        for app_name in apps {
+            let Some(generator) = self.generator_registry.get_generator(app_name) else {
+                continue;
+            };
+            let Some(command) = generator.get_validation_command() else {
+                continue;
+            };
+            let config_path = theme_dir.join(generator.get_file_name());
+            if !config_path.is_file() {
+                continue;
+            }
+
+            let Some((program, args)) = command.split_first() else {
+                continue;
+            };
+            if Self::find_executable(program).is_none() {
+                log::debug!("Skipping {app_name} validation: '{program}' is not installed");
+                continue;
+            }
+
+            let config_arg = config_path.to_string_lossy();
+            let args: Vec<String> = args
+                .iter()
+                .map(|arg| arg.replace("{file}", &config_arg))
+                .collect();
+
+            let result = match std::process::Command::new(program).args(&args).output() {
+                Ok(output) => AppValidationResult {
+                    app: app_name.to_string(),
+                    passed: output.status.success(),
+                    stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                },
+                Err(e) => AppValidationResult {
+                    app: app_name.to_string(),
+                    passed: false,
+                    stderr: format!("Failed to run {program}: {e}"),
+                },
+            };
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Locate an executable by path or on `PATH`
+    fn find_executable(program: &str) -> Option<PathBuf> {
+        if program.contains('/') {
+            let path = PathBuf::from(program);
+            return path.is_file().then_some(path);
+        }
+
+        std::env::var_os("PATH").and_then(|paths| {
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(program))
+                .find(|candidate| candidate.is_file())
+        })
+    }
+
+    /// Get available app schemas for the UI
+    pub fn get_app_schemas(&self) -> Value {
+        let mut schemas = serde_json::Map::new();
+
+        for app_name in self.generator_registry.get_all_apps() {
+            if let Some(schema) = self.generator_registry.get_schema_for_app(app_name) {
+                schemas.insert(app_name.to_string(), schema);
+            }
+        }
+
+        Value::Object(schemas)
+    }
+
+    /// Check each app in `theme_data` against its generator schema. Unknown top-level keys
+    /// are reported as warnings, since they are kept but never generate anything.
+    pub fn validate_theme_data(&self, theme_data: &Value) -> Vec<ValidationError> {
+        let Some(apps) = theme_data.as_object() else {
+            return vec![ValidationError {
+                app: String::new(),
+                field: String::new(),
+                severity: ValidationSeverity::Error,
+                message: "Theme data must be an object".to_string(),
+            }];
+        };
+
+        let mut errors = Vec::new();
+        for (app, data) in apps {
+            if theme_lint::NON_APP_KEYS.contains(&app.as_str()) {
+                continue;
+            }
+            match self.generator_registry.get_schema_for_app(app) {
+                Some(schema) => errors.extend(theme_lint::validate_app_data(app, &schema, data)),
+                None => errors.push(ValidationError {
+                    app: app.clone(),
+                    field: String::new(),
+                    severity: ValidationSeverity::Warning,
+                    message: format!("Unknown app '{app}' will not generate a config"),
+                }),
+            }
+        }
+        errors
+    }
+
+    /// Render the config file an app would get from `theme_data` without writing anything.
+    /// Colors are cleaned up the same way as when saving, so the preview matches the file.
+    pub fn preview_config(&self, app_name: &str, theme_data: &Value) -> Result<String, ThemeError> {
+        let generator = self
+            .generator_registry
+            .get_generator(app_name)
+            .ok_or_else(|| {
+                ThemeError::NotFound(format!("No config generator for app '{app_name}'"))
+            })?;
+
+        let mut generator_input = theme_data.clone();
+        ColorExtractor::trim_color_values(&mut generator_input);
+        color::normalize_color_values(&mut generator_input);
+        let accent = ColorExtractor::extract_from_custom_theme(&generator_input)
+            .and_then(|colors| colors.accent);
+        self.add_generator_context(&mut generator_input, accent);
+        generator
+            .generate_config(&generator_input)
+            .map_err(ThemeError::Generation)
+    }
+
+    /// Get the baseline config for an app so the editor can seed a new tab
+    pub fn get_default_app_config(&self, app_name: &str) -> Result<DefaultAppConfig, ThemeError> {
+        let file_name = self
+            .generator_registry
+            .get_generator(app_name)
+            .ok_or_else(|| ThemeError::NotFound(format!("Unknown app '{app_name}'")))?
+            .get_file_name()
+            .to_string();
+        let (theme_data, content) = self
+            .generator_registry
+            .get_default_config(app_name)
+            .map_err(ThemeError::Generation)?;
+
+        Ok(DefaultAppConfig {
+            app: app_name.to_string(),
+            file_name,
+            theme_data,
+            content,
+        })
+    }
+
+    /// Capabilities of every registered generator, sorted by app name
+    pub fn get_generator_capabilities(&self) -> Vec<GeneratorCapabilities> {
+        let mut apps = self.generator_registry.get_all_apps();
+        apps.sort();
+
+        apps.into_iter()
+            .filter_map(|app_name| self.generator_registry.get_generator(app_name))
+            .map(|generator| GeneratorCapabilities {
+                app: generator.get_app_name().to_string(),
+                can_parse: generator.can_parse(),
+                multi_file: generator.is_multi_file(),
+                supports_raw_append: generator.supports_raw_append(),
+                validates_output: generator.get_validation_command().is_some(),
+                uses_accent: generator.uses_accent(),
+            })
+            .collect()
+    }
+
+    /// Get a theme by name
+    pub fn get_theme(&self, name: &str) -> Result<CustomTheme, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+        self.repair_selected_background_of(&sanitized_name, &mut theme)?;
+        Ok(theme)
+    }
+
+    /// Set (or clear) the background used as the theme's wallpaper
+    pub fn set_selected_background(
+        &self,
+        name: &str,
+        filename: Option<String>,
+    ) -> Result<CustomTheme, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+
+        if let Some(filename) = &filename {
+            if !self.get_theme_backgrounds(name)?.contains(filename) {
+                return Err(ThemeError::NotFound(format!(
+                    "Background image '{filename}' not found"
+                )));
+            }
+        }
+
+        theme.selected_background = filename;
+        self.write_theme_metadata(&theme_dir, &theme)?;
+        Ok(theme)
+    }
+
+    /// Replace a theme's tags without touching its app configs
+    pub fn set_theme_tags(&self, name: &str, tags: Vec<String>) -> Result<CustomTheme, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{name}' is a read-only system theme"
+            )));
+        }
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+
+        theme.tags = Self::normalize_tags(tags);
+        theme.modified_at = chrono::Utc::now().to_rfc3339();
+        self.write_theme_metadata(&theme_dir, &theme)?;
+        Ok(theme)
+    }
+
+    /// Set a theme's description; surrounding whitespace is trimmed
+    pub fn set_theme_description(
+        &self,
+        name: &str,
+        description: &str,
+    ) -> Result<CustomTheme, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{name}' is a read-only system theme"
+            )));
+        }
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+
+        theme.description = description.trim().to_string();
+        theme.modified_at = chrono::Utc::now().to_rfc3339();
+        self.write_theme_metadata(&theme_dir, &theme)?;
+        Ok(theme)
+    }
+
+    /// Choose the image shown as a theme's thumbnail, from the theme directory or its
+    /// `backgrounds/` folder; `None` goes back to picking one by file name
+    pub fn set_theme_preview(
+        &self,
+        name: &str,
+        filename: Option<String>,
+    ) -> Result<CustomTheme, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{name}' is a read-only system theme"
+            )));
+        }
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+
+        if let Some(filename) = &filename {
+            let exists = Self::is_plain_file_name(filename)
+                && (theme_dir.join(filename).is_file()
+                    || theme_dir.join("backgrounds").join(filename).is_file());
+            if !exists {
+                return Err(ThemeError::NotFound(format!(
+                    "Preview image '{filename}' not found"
+                )));
+            }
+        }
+
+        theme.preview = filename;
+        theme.modified_at = chrono::Utc::now().to_rfc3339();
+        self.write_theme_metadata(&theme_dir, &theme)?;
+        Ok(theme)
+    }
+
+    /// Re-point a dangling `selected_background` at the first available background, or
+    /// clear it when none are left. Returns whether a repair happened.
+    pub fn repair_selected_background(&self, name: &str) -> Result<bool, ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+        self.repair_selected_background_of(&sanitized_name, &mut theme)
+    }
+
+    fn repair_selected_background_of(
+        &self,
+        sanitized_name: &str,
+        theme: &mut CustomTheme,
+    ) -> Result<bool, ThemeError> {
+        let theme_dir = self.themes_dir.join(sanitized_name);
+        let Some(selected) = &theme.selected_background else {
+            return Ok(false);
+        };
+        if theme_dir.join("backgrounds").join(selected).is_file() {
+            return Ok(false);
+        }
+
+        let fallback = self
+            .get_theme_backgrounds(sanitized_name)?
+            .into_iter()
+            .next();
+        log::warn!(
+            "Selected background '{selected}' of theme '{}' is missing, using {fallback:?}",
+            theme.name
+        );
+        theme.selected_background = fallback;
+        self.write_theme_metadata(&theme_dir, theme)?;
+        Ok(true)
+    }
+
+    /// List all custom themes (only returns themes with our custom metadata file)
+    pub fn list_themes(&self) -> Result<Vec<CustomTheme>, ThemeError> {
+        let mut themes = Vec::new();
+
+        let entries = fs::read_dir(&self.themes_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to read themes directory: {e}")))?;
+
+        for entry in entries {
+            let entry = entry
+                .map_err(|e| ThemeError::Io(format!("Failed to read directory entry: {e}")))?;
+            let path = entry.path();
+
+            if path.is_dir() {
+                // Dot directories are staging areas of creates, imports and overwrites
+                if let Some(dir_name) = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .filter(|name| !name.starts_with('.'))
+                {
+                    // Only include themes that have our custom metadata file
+                    let metadata_path = path.join("custom_theme.json");
+                    if metadata_path.exists() {
+                        match self.load_theme_metadata(dir_name) {
+                            Ok(theme) => themes.push(theme),
+                            Err(e) => {
+                                log::warn!("Failed to load custom theme '{dir_name}': {e}")
+                            },
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(themes)
+    }
+
+    /// How many custom themes configure each registered app. Themes whose `apps` is not an
+    /// object are skipped, and unregistered app keys are ignored.
+    pub fn get_apps_usage(&self) -> Result<HashMap<String, usize>, ThemeError> {
+        let apps = self.generator_registry.get_all_apps();
+        let mut usage: HashMap<String, usize> =
+            apps.iter().map(|app| (app.to_string(), 0)).collect();
+
+        for theme in self.list_themes()? {
+            let Some(theme_apps) = theme.apps.as_object() else {
+                log::warn!("Skipping theme '{}' with malformed apps", theme.name);
+                continue;
+            };
+            for app in &apps {
+                if theme_apps.get(*app).is_some_and(|config| !config.is_null()) {
+                    *usage.entry(app.to_string()).or_default() += 1;
+                }
+            }
+        }
+        Ok(usage)
+    }
+
+    /// One page of custom themes ordered by name (ignoring case), with the total count
+    pub fn list_themes_paginated(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<CustomTheme>, ThemeError> {
+        let mut themes = self.list_themes()?;
+        themes.sort_by_cached_key(|theme| (theme.name.to_lowercase(), theme.name.clone()));
+        Ok(Page::from_sorted(themes, offset, limit))
+    }
+
+    /// Custom themes whose name contains `query` (ignoring case) and that carry every tag in
+    /// `tags`, best matches first
+    pub fn search_themes(
+        &self,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<CustomTheme>, ThemeError> {
+        Ok(Self::rank_search_results(self.list_themes()?, query, tags))
+    }
+
+    /// Like `search_themes`, but only loading the given theme directories
+    pub fn search_theme_dirs(
+        &self,
+        dirs: &[String],
+        query: &str,
+        tags: &[String],
+    ) -> Vec<CustomTheme> {
+        let themes = dirs
+            .iter()
+            .filter_map(|dir| match self.load_theme_metadata(dir) {
+                Ok(theme) => Some(theme),
+                Err(e) => {
+                    log::warn!("Failed to load custom theme '{dir}': {e}");
+                    None
+                },
+            });
+        Self::rank_search_results(themes, query, tags)
+    }
+
+    /// Keep the themes matching `query` and all `tags`, ordered exact name match first,
+    /// then name prefix, then substring; ties are ordered by name
+    fn rank_search_results(
+        themes: impl IntoIterator<Item = CustomTheme>,
+        query: &str,
+        tags: &[String],
+    ) -> Vec<CustomTheme> {
+        let query = query.trim().to_lowercase();
+        let relevance = |name: &str| {
+            let name = name.to_lowercase();
+            if query.is_empty() || name == query {
+                Some(0)
+            } else if name.starts_with(&query) {
+                Some(1)
+            } else if name.contains(&query) {
+                Some(2)
+            } else {
+                None
+            }
+        };
+        let has_tags = |theme: &CustomTheme| {
+            tags.iter().all(|tag| {
+                theme
+                    .tags
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(tag.trim()))
+            })
+        };
+
+        let mut ranked: Vec<(u8, CustomTheme)> = themes
+            .into_iter()
+            .filter(has_tags)
+            .filter_map(|theme| Some((relevance(&theme.name)?, theme)))
+            .collect();
+        ranked.sort_by(|(a_rank, a), (b_rank, b)| {
+            a_rank
+                .cmp(b_rank)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+        ranked.into_iter().map(|(_, theme)| theme).collect()
+    }
+
+    /// Tally how many custom themes use each color in their app configs.
+    /// Colors within `tolerance` (Euclidean RGB distance) share a bucket; at most `limit`
+    /// entries are returned, most common first.
+    pub fn list_colors_usage(
+        &self,
+        tolerance: f64,
+        limit: usize,
+    ) -> Result<Vec<ColorUsage>, ThemeError> {
+        use std::collections::{BTreeMap, BTreeSet};
+
+        let mut usage: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for theme in self.list_themes()? {
+            let mut colors = BTreeSet::new();
+            Self::collect_colors(&theme.apps, &mut colors);
+            for color in colors {
+                usage.entry(color).or_default().insert(theme.name.clone());
+            }
+        }
+
+        let mut sorted: Vec<(String, BTreeSet<String>)> = usage.into_iter().collect();
+        sorted.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+
+        // Greedily fold each color into the first, more common bucket close enough to it
+        type Bucket = (String, (u8, u8, u8), BTreeSet<String>);
+        let mut buckets: Vec<Bucket> = Vec::new();
+        for (color, themes) in sorted {
+            let Some(rgb) = ColorExtractor::hex_to_rgb(&color) else {
+                continue;
+            };
+            match buckets
+                .iter_mut()
+                .find(|(_, bucket_rgb, _)| Self::rgb_distance(rgb, *bucket_rgb) <= tolerance)
+            {
+                Some((_, _, bucket_themes)) => bucket_themes.extend(themes),
+                None => buckets.push((color, rgb, themes)),
+            }
+        }
+
+        let mut result: Vec<ColorUsage> = buckets
+            .into_iter()
+            .map(|(color, _, themes)| ColorUsage {
+                color,
+                count: themes.len(),
+                themes: themes.into_iter().collect(),
+            })
+            .collect();
+        result.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.color.cmp(&b.color)));
+        result.truncate(limit);
+
+        Ok(result)
+    }
+
+    /// Collect every hex color string found anywhere in a JSON value
+    fn collect_colors(value: &Value, colors: &mut std::collections::BTreeSet<String>) {
+        match value {
+            Value::String(text) => {
+                let trimmed = text.trim();
+                let looks_like_hex = trimmed.starts_with('#')
+                    || (trimmed.len() == 6 && trimmed.chars().all(|c| c.is_ascii_hexdigit()));
+                if looks_like_hex {
+                    if let Some(color) = ColorExtractor::normalize_color(trimmed) {
+                        colors.insert(Self::expand_hex(&color));
+                    }
+                }
+            },
+            Value::Array(items) => items
+                .iter()
+                .for_each(|item| Self::collect_colors(item, colors)),
+            Value::Object(map) => map
+                .values()
+                .for_each(|item| Self::collect_colors(item, colors)),
+            _ => {},
+        }
+    }
+
+    /// Expand `#rgb` shorthand to `#rrggbb` so equal colors tally together
+    fn expand_hex(color: &str) -> String {
+        match ColorExtractor::hex_to_rgb(color) {
+            Some((r, g, b)) => format!("#{r:02x}{g:02x}{b:02x}"),
+            None => color.to_string(),
+        }
+    }
+
+    fn rgb_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+        let dr = a.0 as f64 - b.0 as f64;
+        let dg = a.1 as f64 - b.1 as f64;
+        let db = a.2 as f64 - b.2 as f64;
+        (dr * dr + dg * dg + db * db).sqrt()
+    }
+
+    /// Delete a theme
+    pub fn delete_theme(&self, name: &str) -> Result<(), ThemeError> {
+        let sanitized_name = Self::sanitize_name(name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+
+        if !theme_dir.exists() {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
+        }
+
+        fs::remove_dir_all(&theme_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to delete theme directory: {e}")))?;
+
+        Ok(())
+    }
+
+    /// Rename a theme, moving its directory when the sanitized name changes
+    pub fn rename_theme(&self, old_name: &str, new_name: &str) -> Result<CustomTheme, ThemeError> {
+        let new_name = new_name.trim();
+        let old_sanitized = Self::sanitize_name(old_name)?;
+        let new_sanitized = Self::sanitize_name(new_name)?;
+
+        let old_dir = self.themes_dir.join(&old_sanitized);
+        if !old_dir.exists() {
+            return Err(ThemeError::NotFound(format!(
+                "Theme '{old_name}' not found"
+            )));
+        }
+        if Self::is_read_only_system_theme(&old_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{old_name}' is a read-only system theme"
+            )));
+        }
+
+        let new_dir = self.themes_dir.join(&new_sanitized);
+        if new_sanitized != old_sanitized {
+            // symlink_metadata also sees dangling system theme links
+            if let Ok(metadata) = fs::symlink_metadata(&new_dir) {
+                return Err(ThemeError::AlreadyExists(
+                    if metadata.file_type().is_symlink() {
+                        format!("Theme name '{new_name}' is taken by a system theme")
+                    } else {
+                        format!("Theme '{new_name}' already exists")
+                    },
+                ));
+            }
+
+            fs::rename(&old_dir, &new_dir)
+                .map_err(|e| ThemeError::Io(format!("Failed to rename theme directory: {e}")))?;
+        }
+
+        let mut theme = self.load_theme_metadata(&new_sanitized)?;
+        theme.name = new_name.to_string();
+        theme.modified_at = chrono::Utc::now().to_rfc3339();
+        self.write_theme_metadata(&new_dir, &theme)?;
+
+        log::info!("Renamed theme '{old_name}' to '{new_name}'");
+        Ok(theme)
+    }
+
+    /// Clone a custom theme, including backgrounds and generated configs, under a new name
+    pub fn duplicate_theme(
+        &self,
+        source_name: &str,
+        new_name: &str,
+    ) -> Result<CustomTheme, ThemeError> {
+        let new_name = new_name.trim();
+        let source_sanitized = Self::sanitize_name(source_name)?;
+        let target_sanitized = Self::sanitize_name(new_name)?;
+
+        let mut theme = self.load_theme_metadata(&source_sanitized)?;
+
+        let target_dir = self.themes_dir.join(&target_sanitized);
+        if fs::symlink_metadata(&target_dir).is_ok() {
+            return Err(ThemeError::AlreadyExists(format!(
+                "Theme '{new_name}' already exists"
+            )));
+        }
+
+        fs::create_dir_all(&target_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create theme directory: {e}")))?;
+        let source_dir = self.themes_dir.join(&source_sanitized);
+        if let Err(e) = self.copy_dir_recursive(&source_dir, &target_dir, new_name, "") {
+            let _ = fs::remove_dir_all(&target_dir);
+            return Err(e);
+        }
+
+        // The clone starts its own history
+        let backups_dir = target_dir.join(BACKUPS_DIR);
+        if backups_dir.exists() {
+            if let Err(e) = fs::remove_dir_all(&backups_dir) {
+                log::warn!("Failed to drop copied backups of '{new_name}': {e}");
+            }
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        theme.name = new_name.to_string();
+        theme.created_at = now.clone();
+        theme.modified_at = now;
+        self.write_theme_metadata(&target_dir, &theme)?;
+
+        log::info!("Duplicated theme '{source_name}' as '{new_name}'");
+        Ok(theme)
+    }
+
+    /// Initialize a new custom theme by copying template files
+    pub fn init_theme(&self, name: String, description: String) -> Result<CustomTheme, ThemeError> {
+        let sanitized_name = Self::sanitize_name(&name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+
+        // Check if theme already exists
+        if theme_dir.exists() {
+            return Err(ThemeError::AlreadyExists(format!(
+                "Theme '{name}' already exists"
+            )));
+        }
+
+        // Create theme directory
+        fs::create_dir_all(&theme_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create theme directory: {e}")))?;
+
+        // Copy template files
+        self.copy_template_files(&theme_dir, &name, &description)?;
+
+        // Load the created theme metadata (this will automatically extract colors)
+        let theme = self.load_theme_metadata(&sanitized_name)?;
+
+        log::info!(
+            "Initialized custom theme '{}' in directory: {}",
+            name,
+            theme_dir.display()
+        );
+
+        Ok(theme)
+    }
+
+    /// Copy all template files to the new theme directory
+    fn copy_template_files(
+        &self,
+        theme_dir: &Path,
+        name: &str,
+        description: &str,
+    ) -> Result<(), ThemeError> {
+        // Get template directory path from Tauri resources
+        let app_handle = self
+            .app_handle
+            .as_ref()
+            .ok_or_else(|| ThemeError::Io("Template files require an app handle".to_string()))?;
+        let resource_dir = app_handle
+            .path()
+            .resource_dir()
+            .map_err(|e| ThemeError::Io(format!("Failed to get resource directory: {e}")))?;
+
+        // The resources are copied to target/debug/resources/ in development
+        let template_dir = resource_dir.join("resources").join("template");
+
+        if !template_dir.exists() {
+            return Err(ThemeError::NotFound(format!(
+                "Template directory not found in resources at: {}",
+                template_dir.display()
+            )));
+        }
+
+        self.copy_dir_recursive(&template_dir, theme_dir, name, description)?;
+
+        Ok(())
+    }
+
+    /// Recursively copy directory contents and replace placeholders in custom_theme.json
+    fn copy_dir_recursive(
+        &self,
+        src: &Path,
+        dst: &Path,
+        name: &str,
+        description: &str,
+    ) -> Result<(), ThemeError> {
+        let mut ancestors = std::collections::HashSet::new();
+        self.copy_dir_recursive_guarded(src, dst, name, description, &mut ancestors)
+    }
+
+    /// Recursive copy that skips directories already on the current path (symlink loops)
+    fn copy_dir_recursive_guarded(
+        &self,
+        src: &Path,
+        dst: &Path,
+        name: &str,
+        description: &str,
+        ancestors: &mut std::collections::HashSet<PathBuf>,
+    ) -> Result<(), ThemeError> {
+        let canonical_src = fs::canonicalize(src).map_err(|e| {
+            ThemeError::Io(format!(
+                "Failed to resolve directory {}: {e}",
+                src.display()
+            ))
+        })?;
+        if !ancestors.insert(canonical_src.clone()) {
+            log::warn!(
+                "Skipping directory {} to avoid a symlink loop",
+                src.display()
+            );
+            return Ok(());
+        }
+
+        let result = self.copy_dir_entries(src, dst, name, description, ancestors);
+        ancestors.remove(&canonical_src);
+        result
+    }
+
+    fn copy_dir_entries(
+        &self,
+        src: &Path,
+        dst: &Path,
+        name: &str,
+        description: &str,
+        ancestors: &mut std::collections::HashSet<PathBuf>,
+    ) -> Result<(), ThemeError> {
+        let entries = fs::read_dir(src)
+            .map_err(|e| ThemeError::Io(format!("Failed to read template directory: {e}")))?;
+
+        for entry in entries {
+            let entry = entry
+                .map_err(|e| ThemeError::Io(format!("Failed to read directory entry: {e}")))?;
+            let src_path = entry.path();
+            let dst_path = dst.join(entry.file_name());
+
+            if src_path.is_dir() {
+                // Create directory and copy contents recursively
+                fs::create_dir_all(&dst_path)
+                    .map_err(|e| ThemeError::Io(format!("Failed to create directory: {e}")))?;
+                self.copy_dir_recursive_guarded(
+                    &src_path,
+                    &dst_path,
+                    name,
+                    description,
+                    ancestors,
+                )?;
+            } else {
+                // Check if this is the custom_theme.json template
+                if entry.file_name() == "custom_theme.json" {
+                    self.copy_and_process_metadata_template(
+                        &src_path,
+                        &dst_path,
+                        name,
+                        description,
+                    )?;
+                } else {
+                    // Copy file normally
+                    fs::copy(&src_path, &dst_path).map_err(|e| {
+                        ThemeError::Io(format!("Failed to copy file {}: {}", src_path.display(), e))
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copy and process the custom_theme.json template with placeholder replacement
+    fn copy_and_process_metadata_template(
+        &self,
+        src: &Path,
+        dst: &Path,
+        name: &str,
+        description: &str,
+    ) -> Result<(), ThemeError> {
+        let template_content = fs::read_to_string(src)
+            .map_err(|e| ThemeError::Io(format!("Failed to read metadata template: {e}")))?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+
+        // The description is free text, so escape it for the JSON string it lands in
+        let description = Value::String(description.trim().to_string()).to_string();
+        let processed_content = template_content
+            .replace("{{THEME_NAME}}", name)
+            .replace("{{DESCRIPTION}}", &description[1..description.len() - 1])
+            .replace("{{CREATED_AT}}", &now)
+            .replace("{{MODIFIED_AT}}", &now);
+
+        write_atomic(dst, processed_content)
+            .map_err(|e| ThemeError::Io(format!("Failed to write processed metadata: {e}")))?;
+
+        Ok(())
+    }
+
+    /// Load theme metadata from JSON file
+    fn load_theme_metadata(&self, sanitized_name: &str) -> Result<CustomTheme, ThemeError> {
+        let theme_dir = self.themes_dir.join(sanitized_name);
+        let metadata_path = theme_dir.join("custom_theme.json");
+
+        let content = fs::read_to_string(&metadata_path)
+            .map_err(|e| ThemeError::Io(format!("Failed to read theme metadata: {e}")))?;
+
+        let mut theme: CustomTheme = serde_json::from_str(&content)
+            .map_err(|e| ThemeError::Parse(format!("Failed to parse theme metadata: {e}")))?;
+
+        for (old_key, new_key) in migrate_app_keys(&mut theme.apps) {
+            log::info!(
+                "Migrated app '{old_key}' to '{new_key}' in theme '{}'",
+                theme.name
+            );
+        }
+
+        // Hand-edited metadata may carry padded color strings
+        ColorExtractor::trim_color_values(&mut theme.apps);
+        if let Some(colors) = theme.colors.as_mut() {
+            ColorExtractor::trim_theme_colors(colors);
+        }
+
+        // If colors are missing (backwards compatibility), extract them now
+        if theme.colors.is_none() {
+            theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
+
+            // Save the updated metadata with colors
+            if let Err(e) = self.write_theme_metadata(&theme_dir, &theme) {
+                log::warn!("Failed to update theme metadata with colors: {e}");
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// Write custom_theme.json, compact or pretty-printed depending on configuration
+    fn write_theme_metadata(
+        &self,
+        theme_dir: &Path,
+        theme: &CustomTheme,
+    ) -> Result<(), ThemeError> {
+        let metadata_content = if self.compact_theme_json {
+            serde_json::to_string(theme)
+        } else {
+            serde_json::to_string_pretty(theme)
+        }
+        .map_err(|e| ThemeError::Parse(format!("Failed to serialize theme metadata: {e}")))?;
+
+        write_atomic(&theme_dir.join("custom_theme.json"), metadata_content)
+            .map_err(|e| ThemeError::Io(format!("Failed to write theme metadata: {e}")))
+    }
+    /// Get list of background images for a theme, in the order `order.json` gives them
+    pub fn get_theme_backgrounds(&self, theme_name: &str) -> Result<Vec<String>, ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let backgrounds_dir = theme_dir.join("backgrounds");
+
+        if !backgrounds_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backgrounds = Vec::new();
+        let entries = fs::read_dir(&backgrounds_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to read backgrounds directory: {e}")))?;
+
+        for entry in entries {
+            let entry = entry
+                .map_err(|e| ThemeError::Io(format!("Failed to read directory entry: {e}")))?;
+            let path = entry.path();
+
+            if path.is_file() {
+                if let Some(extension) = path.extension() {
+                    if Self::is_supported_background_extension(&extension.to_string_lossy()) {
+                        // Dot files are downloads that have not been checked yet
+                        let filename = entry.file_name().to_string_lossy().to_string();
+                        if !filename.starts_with('.') {
+                            backgrounds.push(filename);
+                        }
+                    }
+                }
+            }
+        }
+
+        backgrounds.sort();
+        let order = Self::read_background_order(&backgrounds_dir);
+        if !order.is_empty() {
+            // Stable, so unlisted files keep their alphabetical order after the listed ones
+            backgrounds.sort_by_key(|filename| {
+                order
+                    .iter()
+                    .position(|entry| entry == filename)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+        Ok(backgrounds)
+    }
+
+    /// The file names listed in a backgrounds directory's `order.json`, or none when it is
+    /// missing or unreadable
+    fn read_background_order(backgrounds_dir: &Path) -> Vec<String> {
+        let order_path = backgrounds_dir.join(BACKGROUND_ORDER_FILE);
+        let Ok(content) = fs::read_to_string(&order_path) else {
+            return Vec::new();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable background order {order_path:?}: {e}");
+            Vec::new()
+        })
+    }
+
+    /// Add background images to a theme by copying files.
+    /// Files that are missing, not images, or over the size limits are reported as skipped.
+    pub fn add_theme_backgrounds(
+        &self,
+        theme_name: &str,
+        source_paths: Vec<String>,
+    ) -> Result<AddBackgroundsResult, ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+
+        if !theme_dir.exists() {
+            return Err(ThemeError::NotFound(format!(
+                "Theme '{theme_name}' not found"
+            )));
+        }
+
+        let backgrounds_dir = theme_dir.join("backgrounds");
+
+        // Create backgrounds directory if it doesn't exist
+        fs::create_dir_all(&backgrounds_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create backgrounds directory: {e}")))?;
+
+        let mut result = AddBackgroundsResult::default();
+
+        for source_path in source_paths {
+            let source = Path::new(&source_path);
+
+            if let Err(reason) = self.check_background_source(source) {
+                log::warn!("Skipping background {source_path}: {reason}");
+                result.skipped.push((source_path, reason.to_string()));
+                continue;
+            }
+
+            // Get filename and create destination path
+            if let Some(filename) = source.file_name() {
+                let dest_path = backgrounds_dir.join(filename);
+
+                // Copy the file
+                match fs::copy(source, &dest_path) {
+                    Ok(_) => {
+                        result.copied.push(filename.to_string_lossy().to_string());
+                        log::debug!(
+                            "Copied background image: {} -> {}",
+                            source_path,
+                            dest_path.display()
+                        );
+                    },
+                    Err(e) => {
+                        log::warn!("Failed to copy {source_path}: {e}");
+                        result
+                            .skipped
+                            .push((source_path, format!("Failed to copy: {e}")));
+                    },
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Whether a file extension is one of the image formats accepted as a background
+    fn is_supported_background_extension(extension: &str) -> bool {
+        matches!(
+            extension.to_lowercase().as_str(),
+            "jpg" | "jpeg" | "png" | "webp" | "bmp" | "gif"
+        )
+    }
+
+    /// Download an image into a theme's backgrounds, returning the stored file name.
+    /// The name comes from the URL path, or a hash of the URL when it has no image extension.
+    pub fn add_theme_background_from_url(
+        &self,
+        theme_name: &str,
+        url: &str,
+    ) -> Result<String, ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if !theme_dir.exists() {
+            return Err(ThemeError::NotFound(format!(
+                "Theme '{theme_name}' not found"
+            )));
+        }
+
+        let url = url.trim();
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(ThemeError::Validation(format!(
+                "Unsupported URL '{url}': only http and https are allowed"
+            )));
+        }
+
+        let agent = ureq::AgentBuilder::new()
+            .timeout(std::time::Duration::from_secs(
+                BACKGROUND_DOWNLOAD_TIMEOUT_SECS,
+            ))
+            .build();
+        let response = agent
+            .get(url)
+            .call()
+            .map_err(|e| ThemeError::Io(format!("Failed to download background: {e}")))?;
+
+        // Only names URLs without an image file name; the file itself is checked below
+        let content_extension = match response.content_type() {
+            "image/jpeg" | "image/jpg" => "jpg",
+            "image/png" => "png",
+            "image/webp" => "webp",
+            "image/bmp" => "bmp",
+            "image/gif" => "gif",
+            other => other.rsplit('/').next().unwrap_or_default(),
+        };
+
+        let max_bytes = self.max_background_bytes;
+        let declared_length = response
+            .header("Content-Length")
+            .and_then(|length| length.parse::<u64>().ok());
+        if declared_length.is_some_and(|length| length > max_bytes) {
+            return Err(ThemeError::Validation(format!(
+                "Download is larger than the {} MB limit",
+                max_bytes / (1024 * 1024)
+            )));
+        }
+
+        let backgrounds_dir = theme_dir.join("backgrounds");
+        fs::create_dir_all(&backgrounds_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create backgrounds directory: {e}")))?;
+
+        let filename = Self::unique_background_name(
+            &backgrounds_dir,
+            &Self::background_name_from_url(url, content_extension),
+        );
+
+        // Download next to the final file, then check it like any other added background.
+        // One byte past the limit is enough for the size check to reject it.
+        let temp_path =
+            backgrounds_dir.join(format!(".download-{}-{filename}", uuid::Uuid::new_v4()));
+        let downloaded = fs::File::create(&temp_path).and_then(|mut file| {
+            std::io::copy(
+                &mut std::io::Read::take(response.into_reader(), max_bytes + 1),
+                &mut file,
+            )
+        });
+        let checked = downloaded
+            .map_err(|e| ThemeError::Io(format!("Failed to download background: {e}")))
+            .and_then(|_| self.check_background_source(&temp_path))
+            .and_then(|_| {
+                fs::rename(&temp_path, backgrounds_dir.join(&filename))
+                    .map_err(|e| ThemeError::Io(format!("Failed to write background image: {e}")))
+            });
+        if let Err(e) = checked {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+
+        log::debug!("Downloaded background image '{filename}' from {url}");
+        Ok(filename)
+    }
+
+    /// File name for a downloaded background: the last URL path segment when it is a
+    /// plain image file name, otherwise a stable hash of the URL
+    fn background_name_from_url(url: &str, content_extension: &str) -> String {
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+        let segment = path
+            .split_once('/')
+            .and_then(|(_, path)| path.rsplit('/').next())
+            .unwrap_or_default();
+
+        let has_image_extension = Path::new(segment)
+            .extension()
+            .is_some_and(|ext| Self::is_supported_background_extension(&ext.to_string_lossy()));
+        if Self::is_plain_file_name(segment) && has_image_extension {
+            return segment.to_string();
+        }
+
+        // FNV-1a, so the same URL always maps to the same name
+        let hash = url.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+        format!("{hash:016x}.{content_extension}")
+    }
+
+    /// `name`, or `stem-N.ext` for the first N that does not collide in `dir`
+    fn unique_background_name(dir: &Path, name: &str) -> String {
+        if !dir.join(name).exists() {
+            return name.to_string();
+        }
+
+        let path = Path::new(name);
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = path.extension().unwrap_or_default().to_string_lossy();
+        (2..)
+            .map(|n| format!("{stem}-{n}.{extension}"))
+            .find(|candidate| !dir.join(candidate).exists())
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Check that a file can be added as a background, returning why not otherwise
+    fn check_background_source(&self, source: &Path) -> Result<(), ThemeError> {
+        if !source.exists() {
+            return Err(ThemeError::NotFound("File does not exist".to_string()));
+        }
+        if !source.is_file() {
+            return Err(ThemeError::Validation("Not a file".to_string()));
+        }
+
+        // Validate file extension
+        let Some(extension) = source.extension() else {
+            return Err(ThemeError::Validation("File has no extension".to_string()));
+        };
+        let ext = extension.to_string_lossy();
+        if !Self::is_supported_background_extension(&ext) {
+            return Err(ThemeError::Validation(format!(
+                "Unsupported image format '{ext}'"
+            )));
+        }
+
+        let size = fs::metadata(source)
+            .map_err(|e| ThemeError::Io(format!("Failed to read file size: {e}")))?
+            .len();
+        if size > self.max_background_bytes {
+            return Err(ThemeError::Validation(format!(
+                "File is {} MB, larger than the {} MB limit",
+                size.div_ceil(1024 * 1024),
+                self.max_background_bytes / (1024 * 1024)
+            )));
+        }
+
+        let (width, height) = theme_preview::image_dimensions(source).map_err(ThemeError::Parse)?;
+        let (max_width, max_height) = self.max_background_dimensions;
+        if width > max_width || height > max_height {
+            return Err(ThemeError::Validation(format!(
+                "Image is {width}x{height}, larger than the {max_width}x{max_height} limit"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a background image from a theme
+    pub fn remove_theme_background(
+        &self,
+        theme_name: &str,
+        filename: &str,
+    ) -> Result<(), ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let backgrounds_dir = theme_dir.join("backgrounds");
+        let file_path = backgrounds_dir.join(filename);
+
+        if !file_path.exists() {
+            return Err(ThemeError::NotFound(format!(
+                "Background image '{filename}' not found"
+            )));
+        }
+
+        fs::remove_file(&file_path)
+            .map_err(|e| ThemeError::Io(format!("Failed to remove background image: {e}")))?;
+
+        log::debug!("Removed background image: {}", file_path.display());
+        Ok(())
+    }
+
+    /// Background images with their size, dimensions and whether they are animated, in the
+    /// same order as `get_theme_backgrounds`
+    pub fn get_theme_backgrounds_detailed(
+        &self,
+        theme_name: &str,
+    ) -> Result<Vec<BackgroundInfo>, ThemeError> {
+        let backgrounds_dir = self
+            .themes_dir
+            .join(Self::sanitize_name(theme_name)?)
+            .join("backgrounds");
+
+        self.get_theme_backgrounds(theme_name)?
+            .into_iter()
+            .map(|filename| {
+                let path = backgrounds_dir.join(&filename);
+                let size_bytes = fs::metadata(&path)
+                    .map_err(|e| ThemeError::Io(format!("Failed to read background image: {e}")))?
+                    .len();
+                let dimensions = theme_preview::image_dimensions(&path).ok();
+                Ok(BackgroundInfo {
+                    is_animated: theme_preview::is_animated(&path),
+                    width: dimensions.map(|(width, _)| width),
+                    height: dimensions.map(|(_, height)| height),
+                    size_bytes,
+                    filename,
+                })
+            })
+            .collect()
+    }
+
+    /// Rename a background image, keeping `selected_background`, `preview` and `order.json`
+    /// pointing at it
+    pub fn rename_background(
+        &self,
+        theme_name: &str,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<String, ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{theme_name}' is a read-only system theme"
+            )));
+        }
+        let backgrounds_dir = theme_dir.join("backgrounds");
+
+        let old_path = backgrounds_dir.join(old_name);
+        if !Self::is_plain_file_name(old_name) || !old_path.is_file() {
+            return Err(ThemeError::NotFound(format!(
+                "Background image '{old_name}' not found"
+            )));
+        }
+
+        let new_name = new_name.trim();
+        if !Self::is_plain_file_name(new_name) {
+            return Err(ThemeError::Validation(format!(
+                "Invalid background file name '{new_name}'"
+            )));
+        }
+        let supported = Path::new(new_name)
+            .extension()
+            .is_some_and(|ext| Self::is_supported_background_extension(&ext.to_string_lossy()));
+        if !supported {
+            return Err(ThemeError::Validation(format!(
+                "Unsupported image format for '{new_name}'"
+            )));
+        }
+        if new_name == old_name {
+            return Ok(new_name.to_string());
+        }
+
+        let new_path = backgrounds_dir.join(new_name);
+        if new_path.exists() {
+            return Err(ThemeError::AlreadyExists(format!(
+                "Background image '{new_name}' already exists"
+            )));
+        }
+
+        fs::rename(&old_path, &new_path)
+            .map_err(|e| ThemeError::Io(format!("Failed to rename background image: {e}")))?;
+
+        self.replace_background_references(&sanitized_name, old_name, new_name)?;
+
+        log::debug!("Renamed background image '{old_name}' to '{new_name}'");
+        Ok(new_name.to_string())
+    }
+
+    /// Re-encode a background image in another format, returning the new file name.
+    /// With `remove_original`, references to the old file move to the converted one.
+    pub fn convert_background_format(
+        &self,
+        theme_name: &str,
+        filename: &str,
+        target_format: &str,
+        remove_original: bool,
+    ) -> Result<String, ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{theme_name}' is a read-only system theme"
+            )));
+        }
+        let backgrounds_dir = theme_dir.join("backgrounds");
+
+        let source_path = backgrounds_dir.join(filename);
+        if !Self::is_plain_file_name(filename) || !source_path.is_file() {
+            return Err(ThemeError::NotFound(format!(
+                "Background image '{filename}' not found"
+            )));
+        }
+
+        let target_extension = target_format.trim().trim_start_matches('.').to_lowercase();
+        let format = theme_preview::background_format(&target_extension).ok_or_else(|| {
+            ThemeError::Validation(format!("Unsupported target format '{target_format}'"))
+        })?;
+
+        let stem = Path::new(filename)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| filename.to_string());
+        let new_name = format!("{stem}.{target_extension}");
+        let target_path = backgrounds_dir.join(&new_name);
+        if target_path.exists() {
+            return Err(ThemeError::AlreadyExists(format!(
+                "Background image '{new_name}' already exists"
+            )));
+        }
+
+        let image = theme_preview::decode_image(&source_path).map_err(ThemeError::Parse)?;
+        let bytes = theme_preview::encode_image(&image, format).map_err(ThemeError::Generation)?;
+        fs::write(&target_path, bytes)
+            .map_err(|e| ThemeError::Io(format!("Failed to write converted background: {e}")))?;
+
+        if remove_original {
+            fs::remove_file(&source_path).map_err(|e| {
+                ThemeError::Io(format!("Failed to remove original background: {e}"))
+            })?;
+            self.replace_background_references(&sanitized_name, filename, &new_name)?;
+        }
+
+        log::debug!("Converted background image '{filename}' to '{new_name}'");
+        Ok(new_name)
+    }
+
+    /// Point `order.json`, `selected_background` and `preview` entries for `old_name` at
+    /// `new_name`
+    fn replace_background_references(
+        &self,
+        sanitized_name: &str,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<(), ThemeError> {
+        let theme_dir = self.themes_dir.join(sanitized_name);
+        let order_path = theme_dir.join("backgrounds").join(BACKGROUND_ORDER_FILE);
+        if order_path.is_file() {
+            let content = fs::read_to_string(&order_path)
+                .map_err(|e| ThemeError::Io(format!("Failed to read background order: {e}")))?;
+            let mut order: Vec<String> = serde_json::from_str(&content)
+                .map_err(|e| ThemeError::Parse(format!("Failed to parse background order: {e}")))?;
+            for entry in order.iter_mut().filter(|entry| *entry == old_name) {
+                *entry = new_name.to_string();
+            }
+            // `new_name` may already have been listed when two files merge into one
+            let mut seen_new_name = false;
+            order.retain(|entry| entry != new_name || !std::mem::replace(&mut seen_new_name, true));
+            let content = serde_json::to_string_pretty(&order).map_err(|e| {
+                ThemeError::Parse(format!("Failed to serialize background order: {e}"))
+            })?;
+            write_atomic(&order_path, content)
+                .map_err(|e| ThemeError::Io(format!("Failed to write background order: {e}")))?;
+        }
+
+        let mut theme = self.load_theme_metadata(sanitized_name)?;
+        let mut changed = false;
+        if theme.selected_background.as_deref() == Some(old_name) {
+            theme.selected_background = Some(new_name.to_string());
+            changed = true;
+        }
+        // A preview name resolves in the theme directory before `backgrounds/`
+        if theme.preview.as_deref() == Some(old_name) && !theme_dir.join(old_name).is_file() {
+            theme.preview = Some(new_name.to_string());
+            changed = true;
+        }
+        if changed {
+            self.write_theme_metadata(&theme_dir, &theme)?;
+        }
+        Ok(())
+    }
+
+    /// Remove backgrounds that are byte-identical to another one, keeping the alphabetically
+    /// first copy and pointing references to removed files at it. Files that can't be read or
+    /// removed are reported and skipped.
+    pub fn deduplicate_backgrounds(
+        &self,
+        theme_name: &str,
+    ) -> Result<DeduplicatedBackgrounds, ThemeError> {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{theme_name}' is a read-only system theme"
+            )));
+        }
+        let backgrounds_dir = theme_dir.join("backgrounds");
+
+        let mut result = DeduplicatedBackgrounds::default();
+        // Kept files by (size, content hash); a hash match is confirmed byte for byte
+        let mut kept: HashMap<(u64, u64), Vec<String>> = HashMap::new();
+        let mut backgrounds = self.get_theme_backgrounds(theme_name)?;
+        backgrounds.sort();
+        for filename in backgrounds {
+            let path = backgrounds_dir.join(&filename);
+            let bytes = match fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    result
+                        .failed
+                        .push((filename, format!("Failed to read: {e}")));
+                    continue;
+                },
+            };
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            let size = bytes.len() as u64;
+
+            let candidates = kept.entry((size, hasher.finish())).or_default();
+            let survivor = candidates
+                .iter()
+                .find(|kept_name| {
+                    fs::read(backgrounds_dir.join(kept_name)).is_ok_and(|other| other == bytes)
+                })
+                .cloned();
+            let Some(survivor) = survivor else {
+                candidates.push(filename);
+                continue;
+            };
+
+            if let Err(e) = fs::remove_file(&path) {
+                result
+                    .failed
+                    .push((filename, format!("Failed to remove: {e}")));
+                continue;
+            }
+            if let Err(e) =
+                self.replace_background_references(&sanitized_name, &filename, &survivor)
+            {
+                result.failed.push((filename.clone(), e.to_string()));
+            }
+            log::debug!("Removed background '{filename}', a duplicate of '{survivor}'");
+            result.freed_bytes += size;
+            result.removed.push(filename);
+        }
+        Ok(result)
+    }
+
+    /// Set the display order of a theme's backgrounds by writing it to `order.json`.
+    /// Backgrounds missing from `order` follow in their current order. Returns the new order.
+    pub fn reorder_backgrounds(
+        &self,
+        theme_name: &str,
+        order: &[String],
+    ) -> Result<Vec<String>, ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        if Self::is_read_only_system_theme(&theme_dir) {
+            return Err(ThemeError::Validation(format!(
+                "Theme '{theme_name}' is a read-only system theme"
+            )));
+        }
+        let existing = self.get_theme_backgrounds(theme_name)?;
+
+        let mut ordered: Vec<String> = Vec::with_capacity(existing.len());
+        for filename in order {
+            if !existing.contains(filename) {
+                return Err(ThemeError::NotFound(format!(
+                    "Background image '{filename}' not found"
+                )));
+            }
+            if ordered.contains(filename) {
+                return Err(ThemeError::Validation(format!(
+                    "Background image '{filename}' is listed twice"
+                )));
+            }
+            ordered.push(filename.clone());
+        }
+        for filename in existing {
+            if !ordered.contains(&filename) {
+                ordered.push(filename);
+            }
+        }
+
+        let content = serde_json::to_string_pretty(&ordered)
+            .map_err(|e| ThemeError::Parse(format!("Failed to serialize background order: {e}")))?;
+        write_atomic(
+            &theme_dir.join("backgrounds").join(BACKGROUND_ORDER_FILE),
+            content,
+        )
+        .map_err(|e| ThemeError::Io(format!("Failed to write background order: {e}")))?;
+
+        log::debug!("Reordered {} backgrounds of '{theme_name}'", ordered.len());
+        Ok(ordered)
+    }
+
+    /// Whether a name is a single path component that is not hidden or a parent reference
+    fn is_plain_file_name(name: &str) -> bool {
+        !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\', '\0'])
+    }
+
+    /// Get background image data as base64 for preview
+    pub fn get_background_image_data(
+        &self,
+        theme_name: &str,
+        filename: &str,
+    ) -> Result<String, ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let backgrounds_dir = theme_dir.join("backgrounds");
+        let file_path = backgrounds_dir.join(filename);
+
+        if !file_path.exists() {
+            return Err(ThemeError::NotFound(format!(
+                "Background image '{filename}' not found"
+            )));
+        }
+
+        // Read the file and convert to base64; oversized files should use the thumbnail
+        let image_data = theme_preview::read_image_capped(&file_path).map_err(ThemeError::Io)?;
+
+        // Determine MIME type based on file extension
+        let mime_type = match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("png") => "image/png",
+            Some("webp") => "image/webp",
+            Some("bmp") => "image/bmp",
+            Some("gif") => "image/gif",
+            _ => "image/jpeg", // default fallback
+        };
+
+        // Encode as base64 data URL using our optimized implementation
+        let base64_data = base64::encode(&image_data);
+        Ok(format!("data:{mime_type};base64,{base64_data}"))
+    }
+
+    /// Get a downscaled JPEG of a background as a data URL, longest side `max_dimension`.
+    /// Thumbnails are cached in `backgrounds/.thumbnails/`, keyed by the source mtime.
+    pub fn get_background_thumbnail(
+        &self,
+        theme_name: &str,
+        filename: &str,
+        max_dimension: u32,
+    ) -> Result<String, ThemeError> {
+        let sanitized_name = Self::sanitize_name(theme_name)?;
+        let backgrounds_dir = self.themes_dir.join(&sanitized_name).join("backgrounds");
+        let file_path = backgrounds_dir.join(filename);
+
+        if !Self::is_plain_file_name(filename) || !file_path.is_file() {
+            return Err(ThemeError::NotFound(format!(
+                "Background image '{filename}' not found"
+            )));
+        }
+        if max_dimension == 0 {
+            return Err(ThemeError::Validation(
+                "Thumbnail size must be greater than zero".to_string(),
+            ));
+        }
+
+        let source_mtime = fs::metadata(&file_path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| ThemeError::Io(format!("Failed to read background image: {e}")))?
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_nanos())
+            .unwrap_or_default();
+
+        let thumbnails_dir = backgrounds_dir.join(theme_preview::THUMBNAILS_DIR);
+        let key_prefix = format!("{filename}-{max_dimension}-");
+        let thumbnail_path = thumbnails_dir.join(format!("{key_prefix}{source_mtime}.jpg"));
+
+        let thumbnail = match fs::read(&thumbnail_path) {
+            Ok(cached) => cached,
+            Err(_) => {
+                let rendered = theme_preview::render_thumbnail(&file_path, max_dimension)
+                    .map_err(ThemeError::Generation)?;
+                if let Err(e) =
+                    Self::store_thumbnail(&thumbnails_dir, &key_prefix, &thumbnail_path, &rendered)
+                {
+                    log::warn!("Failed to cache thumbnail of '{filename}': {e}");
+                }
+                rendered
+            },
+        };
+
+        Ok(format!(
+            "data:image/jpeg;base64,{}",
+            base64::encode(&thumbnail)
+        ))
+    }
+
+    /// Write a thumbnail, dropping earlier ones of the same source and size
+    fn store_thumbnail(
+        thumbnails_dir: &Path,
+        key_prefix: &str,
+        thumbnail_path: &Path,
+        thumbnail: &[u8],
+    ) -> Result<(), ThemeError> {
+        fs::create_dir_all(thumbnails_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to create thumbnails directory: {e}")))?;
+
+        for entry in fs::read_dir(thumbnails_dir)
+            .map_err(|e| ThemeError::Io(format!("Failed to read thumbnails directory: {e}")))?
+            .flatten()
+        {
+            if entry.file_name().to_string_lossy().starts_with(key_prefix) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+
+        fs::write(thumbnail_path, thumbnail)
+            .map_err(|e| ThemeError::Io(format!("Failed to write thumbnail: {e}")))
+    }
+
+    /// Render the theme's palette over a blurred crop of its selected (or first) background
+    /// as a base64 PNG data URL, or a plain swatch strip when it has no backgrounds
+    pub fn preview_theme_on_wallpaper(&self, name: &str) -> Result<String, ThemeError> {
+        let theme = self.get_theme(name)?;
+        let colors = theme
+            .colors
+            .clone()
+            .unwrap_or_else(|| self.fallback_colors.clone());
+
+        let background = match theme.selected_background {
+            Some(selected) => Some(selected),
+            None => self.get_theme_backgrounds(name)?.into_iter().next(),
+        };
+        let backgrounds_dir = self
+            .themes_dir
+            .join(Self::sanitize_name(name)?)
+            .join("backgrounds");
+        let background_path = background.map(|filename| backgrounds_dir.join(filename));
+
+        let preview = theme_preview::render_wallpaper_preview(background_path.as_deref(), &colors)
+            .map_err(ThemeError::Generation)?;
+        let png = theme_preview::encode_png(&preview).map_err(ThemeError::Generation)?;
+        Ok(format!("data:image/png;base64,{}", base64::encode(&png)))
+    }
+
+    /// Render the theme's palette as a fixed-size swatch strip, as a base64 PNG data URL.
+    /// System themes use the colors extracted from their config files.
+    pub fn render_palette_swatch(&self, name: &str) -> Result<String, ThemeError> {
+        let theme_dir = self.themes_dir.join(Self::sanitize_name(name)?);
+        let colors = if theme_dir.join("custom_theme.json").is_file() {
+            self.get_theme(name)?.colors
+        } else if theme_dir.is_dir() {
+            super::get_sys_themes::extract_theme_colors(&theme_dir, false)
+        } else {
+            return Err(ThemeError::NotFound(format!("Theme '{name}' not found")));
+        };
+        let colors = colors.ok_or_else(|| {
+            ThemeError::Validation(format!("Theme '{name}' has no extracted colors"))
+        })?;
+
+        let strip = theme_preview::render_swatch_strip(
+            &colors,
+            theme_preview::PALETTE_SWATCH_WIDTH,
+            theme_preview::PALETTE_SWATCH_HEIGHT,
+        );
+        let png = theme_preview::encode_png(&strip).map_err(ThemeError::Generation)?;
+        Ok(format!("data:image/png;base64,{}", base64::encode(&png)))
+    }
+}
+
+// Tauri commands
+#[tauri::command]
+pub async fn create_custom_theme(
+    app_handle: AppHandle,
+    name: String,
+    background: String,
+    foreground: String,
+) -> Result<CustomTheme, ThemeError> {
+    log::info!("Creating custom theme '{name}' with colors: bg={background}, fg={foreground}");
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.create_theme(name.clone(), background, foreground);
+
+    // Update the cache entry for the created theme
+    if result.is_ok() {
+        reload_cached_theme(&name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn create_custom_theme_advanced(
+    app_handle: AppHandle,
+    name: String,
+    theme_data: Value,
+) -> Result<CustomTheme, ThemeError> {
+    log::info!("Creating advanced custom theme '{name}'");
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.create_theme_advanced(name.clone(), theme_data);
+
+    // Update the cache entry for the created theme
+    if result.is_ok() {
+        reload_cached_theme(&name).await;
+    }
+
+    result
+}
+
+/// Create many themes (e.g. a folder of palettes), updating only their cache entries.
+/// Names repeated within the batch are reported as failures.
+#[tauri::command]
+pub async fn batch_create_themes(
+    app_handle: AppHandle,
+    items: Vec<(String, Value)>,
+) -> Result<Vec<BatchThemeResult>, ThemeError> {
+    log::info!("Batch creating {} custom themes", items.len());
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let results = service.batch_create_themes(items);
+
+    for result in results.iter().filter(|r| r.theme.is_some()) {
+        reload_cached_theme(&result.name).await;
+    }
+
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn import_base16_scheme(
+    app_handle: AppHandle,
+    name: String,
+    yaml_content: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let theme = service.import_base16(&name, &yaml_content)?;
+
+    reload_cached_theme(&theme.name).await;
+
+    Ok(theme)
+}
+
+#[tauri::command]
+pub async fn export_base16_scheme(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.export_base16(&name)
+}
+
+#[tauri::command]
+pub async fn update_custom_theme(
+    app_handle: AppHandle,
+    name: String,
+    background: String,
+    foreground: String,
+    dim_foreground: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+
+    let alacritty_config = AlacrittyConfig {
+        colors: AlacrittyColors {
+            primary: AlacrittyPrimaryColors {
+                background,
+                foreground,
+                dim_foreground,
+            },
+        },
+    };
+
+    let result = service.update_theme(&name, alacritty_config);
+
+    // Update the cache entry for the updated theme
+    if result.is_ok() {
+        reload_cached_theme(&name).await;
+    }
+
+    result
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn update_custom_theme_advanced(
+    app_handle: AppHandle,
+    name: String,
+    theme_data: Value,
+    strategy: Option<MergeStrategy>,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.update_theme_advanced(&name, theme_data, strategy.unwrap_or_default());
+
+    // Update the cache entries for the theme (and the fork, if the edit was redirected)
+    if let Ok(theme) = &result {
+        reload_cached_theme(&name).await;
+        reload_cached_theme(&theme.name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn recolor_from_accent(
+    app_handle: AppHandle,
+    name: String,
+    accent: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.recolor_from_accent(&name, &accent);
+
+    if let Ok(theme) = &result {
+        reload_cached_theme(&name).await;
+        reload_cached_theme(&theme.name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn derive_light_variant(
+    app_handle: AppHandle,
+    source_name: String,
+    new_name: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.derive_light_variant(&source_name, &new_name);
+
+    if result.is_ok() {
+        reload_cached_theme(&new_name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn convert_background_format(
+    app_handle: AppHandle,
+    theme_name: String,
+    filename: String,
+    target_format: String,
+    remove_original: bool,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let new_name = service.convert_background_format(
+        &theme_name,
+        &filename,
+        &target_format,
+        remove_original,
+    )?;
+
+    reload_cached_theme(&theme_name).await;
+    Ok(new_name)
+}
+
+#[tauri::command]
+pub async fn get_theme_colors_history(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<Vec<ThemeColorsSnapshot>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.get_theme_colors_history(&name)
+}
+
+#[tauri::command]
+pub async fn list_theme_backups(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<Vec<ThemeBackup>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.list_theme_backups(&name)
+}
+
+#[tauri::command]
+pub async fn restore_theme_backup(
+    app_handle: AppHandle,
+    name: String,
+    timestamp: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let theme = service.restore_theme_backup(&name, &timestamp)?;
+
+    reload_cached_theme(&name).await;
+
+    Ok(theme)
+}
+
+#[tauri::command]
+pub async fn lint_theme(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<Vec<LintFinding>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.lint_theme(&name)
+}
+
+#[tauri::command]
+pub async fn read_theme_config(
+    app_handle: AppHandle,
+    name: String,
+    app_name: String,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.read_theme_config(&name, &app_name)
+}
+
+/// Rebuild a theme's config files from its metadata, e.g. after a generator update
+#[tauri::command]
+pub async fn regenerate_configs(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<RegeneratedConfigs, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.regenerate_configs(&name);
+
+    if result.is_ok() {
+        reload_cached_theme(&name).await;
+    }
+
+    result
+}
+
+/// Report whether a theme will apply cleanly, so the UI can block or warn before applying
+#[tauri::command]
+pub async fn preflight_theme(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<PreflightReport, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.preflight_theme(&name)
+}
+
+#[tauri::command]
+pub async fn deep_validate_theme(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<Vec<AppValidationResult>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.deep_validate_theme(&name)
+}
+
+#[tauri::command]
+pub async fn set_app_raw_append(
+    app_handle: AppHandle,
+    theme_name: String,
+    app_name: String,
+    text: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.set_app_raw_append(&theme_name, &app_name, text);
+
+    // Update the cache entry for the updated theme
+    if let Ok(theme) = &result {
+        reload_cached_theme(&theme_name).await;
+        reload_cached_theme(&theme.name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn export_theme_for_omarchy(
+    app_handle: AppHandle,
+    name: String,
+    output_dir: String,
+) -> Result<Vec<String>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.export_theme_for_omarchy(&name, Path::new(&output_dir))
+}
+
+#[tauri::command]
+pub async fn suggest_theme_name(
+    app_handle: AppHandle,
+    base_name: String,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    Ok(service.suggest_theme_name(&base_name))
+}
+
+#[tauri::command]
+pub async fn get_custom_theme(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.get_theme(&name)
+}
+
+#[tauri::command]
+pub async fn set_selected_background(
+    app_handle: AppHandle,
+    name: String,
+    filename: Option<String>,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.set_selected_background(&name, filename)
+}
+
+#[tauri::command]
+pub async fn set_theme_tags(
+    app_handle: AppHandle,
+    name: String,
+    tags: Vec<String>,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.set_theme_tags(&name, tags);
+
+    if result.is_ok() {
+        reload_cached_theme(&name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn set_theme_description(
+    app_handle: AppHandle,
+    name: String,
+    text: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.set_theme_description(&name, &text);
+
+    if result.is_ok() {
+        reload_cached_theme(&name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn set_theme_preview(
+    app_handle: AppHandle,
+    name: String,
+    filename: Option<String>,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.set_theme_preview(&name, filename);
+
+    if result.is_ok() {
+        reload_cached_theme(&name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn repair_selected_background(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<bool, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.repair_selected_background(&name)
+}
+
+#[tauri::command]
+pub async fn list_custom_themes(app_handle: AppHandle) -> Result<Vec<CustomTheme>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.list_themes()
+}
+
+#[tauri::command]
+pub async fn get_apps_usage(app_handle: AppHandle) -> Result<HashMap<String, usize>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.get_apps_usage()
+}
+
+#[tauri::command]
+pub async fn list_themes_paginated(
+    app_handle: AppHandle,
+    offset: usize,
+    limit: usize,
+) -> Result<Page<CustomTheme>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.list_themes_paginated(offset, limit)
+}
+
+#[tauri::command]
+pub async fn search_themes(
+    app_handle: AppHandle,
+    query: String,
+    tags: Vec<String>,
+) -> Result<Vec<CustomTheme>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+
+    // A fresh theme cache narrows the candidates, so only their metadata has to be read
+    if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+        if cache.is_cache_valid().await && !cache.is_empty().await {
+            let needle = query.trim().to_lowercase();
+            let sanitized = CustomThemeService::sanitize_name(&query).unwrap_or_default();
+            let candidates: Vec<String> = cache
+                .get_themes()
+                .await
+                .map_err(ThemeError::Io)?
+                .into_iter()
+                .filter(|theme| theme.is_custom)
+                .filter(|theme| {
+                    theme.title.to_lowercase().contains(&needle) || theme.dir.contains(&sanitized)
+                })
+                .map(|theme| theme.dir)
+                .collect();
+            return Ok(service.search_theme_dirs(&candidates, &query, &tags));
+        }
+    }
+
+    service.search_themes(&query, &tags)
+}
+
+#[tauri::command]
+pub async fn list_colors_usage(
+    app_handle: AppHandle,
+    tolerance: Option<f64>,
+    limit: Option<usize>,
+) -> Result<Vec<ColorUsage>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.list_colors_usage(tolerance.unwrap_or(4.0), limit.unwrap_or(100))
+}
+
+#[tauri::command]
+pub async fn delete_custom_theme(app_handle: AppHandle, name: String) -> Result<(), ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.delete_theme(&name);
+
+    // Invalidate cache for the deleted theme
+    if result.is_ok() {
+        reload_cached_theme(&name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn export_custom_theme(
+    app_handle: AppHandle,
+    name: String,
+    dest_path: String,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let archive_path = service.export_theme(&name, Path::new(&dest_path))?;
+    Ok(archive_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn import_custom_theme(
+    app_handle: AppHandle,
+    archive_path: String,
+    overwrite: bool,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.import_theme(Path::new(&archive_path), overwrite);
+
+    if let Ok(theme) = &result {
+        reload_cached_theme(&theme.name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn rename_custom_theme(
+    app_handle: AppHandle,
+    old_name: String,
+    new_name: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.rename_theme(&old_name, &new_name);
+
+    // Drop cached entries under both names so the old one stops being listed
+    if result.is_ok() {
+        reload_cached_theme(&old_name).await;
+        reload_cached_theme(&new_name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn duplicate_custom_theme(
+    app_handle: AppHandle,
+    source_name: String,
+    new_name: String,
+) -> Result<CustomTheme, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.duplicate_theme(&source_name, &new_name);
+
+    // Refresh so the clone shows up immediately
+    if result.is_ok() {
+        reload_cached_theme(&new_name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn init_custom_theme(
+    app_handle: AppHandle,
+    name: String,
+    description: Option<String>,
+) -> Result<CustomTheme, ThemeError> {
+    log::info!("Initializing custom theme '{name}'");
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.init_theme(name, description.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_app_schemas(app_handle: AppHandle) -> Result<Value, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    Ok(service.get_app_schemas())
+}
+
+#[tauri::command]
+pub async fn preview_config(
+    app_handle: AppHandle,
+    app_name: String,
+    theme_data: Value,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.preview_config(&app_name, &theme_data)
+}
+
+#[tauri::command]
+pub async fn validate_theme_data(
+    app_handle: AppHandle,
+    theme_data: Value,
+) -> Result<Vec<ValidationError>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    Ok(service.validate_theme_data(&theme_data))
+}
+
+#[tauri::command]
+pub async fn get_generator_capabilities(
+    app_handle: AppHandle,
+) -> Result<Vec<GeneratorCapabilities>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    Ok(service.get_generator_capabilities())
+}
+
+#[tauri::command]
+pub async fn get_default_app_config(
+    app_handle: AppHandle,
+    app_name: String,
+) -> Result<DefaultAppConfig, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.get_default_app_config(&app_name)
+}
+
+#[tauri::command]
+pub async fn get_theme_backgrounds(
+    app_handle: AppHandle,
+    theme_name: String,
+) -> Result<Vec<String>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.get_theme_backgrounds(&theme_name)
+}
+
+#[tauri::command]
+pub async fn add_theme_backgrounds(
+    app_handle: AppHandle,
+    theme_name: String,
+    source_paths: Vec<String>,
+) -> Result<AddBackgroundsResult, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.add_theme_backgrounds(&theme_name, source_paths)
+}
+
+#[tauri::command]
+pub async fn remove_theme_background(
+    app_handle: AppHandle,
+    theme_name: String,
+    filename: String,
+) -> Result<(), ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.remove_theme_background(&theme_name, &filename)
+}
+
+#[tauri::command]
+pub async fn get_theme_backgrounds_detailed(
+    app_handle: AppHandle,
+    theme_name: String,
+) -> Result<Vec<BackgroundInfo>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.get_theme_backgrounds_detailed(&theme_name)
+}
+
+#[tauri::command]
+pub async fn rename_background(
+    app_handle: AppHandle,
+    theme_name: String,
+    old_name: String,
+    new_name: String,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.rename_background(&theme_name, &old_name, &new_name);
+
+    if result.is_ok() {
+        reload_cached_theme(&theme_name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn deduplicate_backgrounds(
+    app_handle: AppHandle,
+    theme_name: String,
+) -> Result<DeduplicatedBackgrounds, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.deduplicate_backgrounds(&theme_name);
+
+    if result.is_ok() {
+        reload_cached_theme(&theme_name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn reorder_backgrounds(
+    app_handle: AppHandle,
+    theme_name: String,
+    order: Vec<String>,
+) -> Result<Vec<String>, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    let result = service.reorder_backgrounds(&theme_name, &order);
+
+    if result.is_ok() {
+        reload_cached_theme(&theme_name).await;
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn get_background_image_data(
+    app_handle: AppHandle,
+    theme_name: String,
+    filename: String,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.get_background_image_data(&theme_name, &filename)
+}
+
+#[tauri::command]
+pub async fn add_theme_background_from_url(
+    app_handle: AppHandle,
+    theme_name: String,
+    url: String,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    // The download blocks, so keep it off the async runtime
+    tokio::task::spawn_blocking(move || service.add_theme_background_from_url(&theme_name, &url))
+        .await
+        .map_err(|e| ThemeError::Io(format!("Background download task failed: {e}")))?
+}
+
+#[tauri::command]
+pub async fn get_background_thumbnail(
+    app_handle: AppHandle,
+    theme_name: String,
+    filename: String,
+    max_dimension: u32,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.get_background_thumbnail(&theme_name, &filename, max_dimension)
+}
+
+#[tauri::command]
+pub async fn preview_theme_on_wallpaper(
+    app_handle: AppHandle,
     name: String,
-    background: String,
-    foreground: String,
-    dim_foreground: String,
-) -> Result<CustomTheme, String> {
-    let service = CustomThemeService::new(&app_handle)?;
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.preview_theme_on_wallpaper(&name)
+}
+
+#[tauri::command]
+pub async fn render_palette_swatch(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<String, ThemeError> {
+    let service = CustomThemeService::with_settings(&app_handle).await?;
+    service.render_palette_swatch(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(
+            CustomThemeService::sanitize_name("My Cool Theme").unwrap(),
+            "my-cool-theme"
+        );
+        assert_eq!(
+            CustomThemeService::sanitize_name("Test_Theme-123").unwrap(),
+            "test_theme-123"
+        );
+        assert_eq!(
+            CustomThemeService::sanitize_name("Special@#$%Theme").unwrap(),
+            "specialtheme"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_name_rejects_names_without_usable_characters() {
+        for name in ["@#$%", "", "   ", "\t\n", " - _ ", ".", "..", "../.."] {
+            assert!(
+                CustomThemeService::sanitize_name(name).is_err(),
+                "{name:?} should be rejected"
+            );
+        }
+        // Surrounding spaces are kept as dashes once there is something to name
+        assert_eq!(CustomThemeService::sanitize_name(" a ").unwrap(), "-a-");
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        assert!(service
+            .create_theme_advanced("@#$%".to_string(), serde_json::json!({}))
+            .is_err());
+        assert!(!temp.path().join("custom_theme.json").exists());
+        assert!(service.delete_theme("@#$%").is_err());
+        assert!(temp.path().exists());
+    }
+
+    #[test]
+    fn test_theme_creation() {
+        // Skip this test since it requires a real AppHandle
+        // which is not available in unit tests
+    }
+
+    struct MockValidatedGenerator {
+        app: &'static str,
+        command: Vec<&'static str>,
+    }
+
+    impl crate::services::config::generators::ConfigGenerator for MockValidatedGenerator {
+        fn get_app_name(&self) -> &'static str {
+            self.app
+        }
+
+        fn get_file_name(&self) -> &'static str {
+            "mock.conf"
+        }
+
+        fn generate_config(&self, _theme_data: &Value) -> Result<String, String> {
+            Ok("mock = true\n".to_string())
+        }
+
+        fn get_config_schema(&self) -> Value {
+            serde_json::json!({})
+        }
+
+        fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
+            Ok(serde_json::json!({}))
+        }
+
+        fn get_validation_command(&self) -> Option<Vec<&'static str>> {
+            Some(self.command.clone())
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_deep_validate_theme_with_mock_command() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .generator_registry
+            .register(Box::new(MockValidatedGenerator {
+                app: "failing",
+                command: vec!["sh", "-c", "echo \"bad config: $0\" >&2; exit 1", "{file}"],
+            }));
+        service
+            .generator_registry
+            .register(Box::new(MockValidatedGenerator {
+                app: "missing",
+                command: vec!["omarchist-no-such-validator", "{file}"],
+            }));
+
+        fs::create_dir_all(temp.path().join("mock")).unwrap();
+        fs::write(temp.path().join("mock").join("mock.conf"), "mock = true\n").unwrap();
+
+        let results = service.deep_validate_theme("mock").unwrap();
+
+        // The missing binary is skipped, the failing one reports its stderr
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].app, "failing");
+        assert!(!results[0].passed);
+        assert!(results[0].stderr.contains("bad config"));
+        assert!(results[0].stderr.contains("mock.conf"));
+    }
+
+    #[test]
+    fn test_compact_theme_json_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service.set_compact_theme_json(true);
+
+        let theme_data = serde_json::json!({
+            "alacritty": { "colors": { "primary": { "background": "#101010" } } }
+        });
+        let created = service
+            .create_theme_advanced("Compact".to_string(), theme_data)
+            .unwrap();
+
+        let raw = fs::read_to_string(temp.path().join("compact/custom_theme.json")).unwrap();
+        assert!(!raw.contains('\n'));
+
+        // Reading works regardless of the flag
+        service.set_compact_theme_json(false);
+        let loaded = service.get_theme("Compact").unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&created).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tag_edit_keeps_compact_theme_json() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service.set_compact_theme_json(true);
+        service
+            .create_theme_advanced("Compact".to_string(), serde_json::json!({}))
+            .unwrap();
+
+        service
+            .set_theme_tags("Compact", vec!["dark".to_string()])
+            .unwrap();
+
+        let raw = fs::read_to_string(temp.path().join("compact/custom_theme.json")).unwrap();
+        assert!(!raw.contains('\n'));
+        assert!(raw.contains("\"dark\""));
+    }
+
+    #[test]
+    fn test_set_app_raw_append_writes_text_verbatim() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Raw".to_string(), serde_json::json!({}))
+            .unwrap();
+
+        let raw = "bind = SUPER, T, exec, kitty";
+        service
+            .set_app_raw_append("Raw", "hyprland", raw.to_string())
+            .unwrap();
+
+        let config = fs::read_to_string(temp.path().join("raw/hyprland.conf")).unwrap();
+        assert!(config.contains(raw));
+        assert!(service
+            .set_app_raw_append("Raw", "chromium", "x".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn test_list_colors_usage_counts_shared_color() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+
+        service
+            .create_theme_advanced(
+                "One".to_string(),
+                serde_json::json!({"waybar": {"colors": {"main": {"background": "#123456"}}}}),
+            )
+            .unwrap();
+        service
+            .create_theme_advanced(
+                "Two".to_string(),
+                serde_json::json!({"mako": {"colors": {"border": "#123457", "text": "#ABCDEF"}}}),
+            )
+            .unwrap();
+
+        let usage = service.list_colors_usage(4.0, 100).unwrap();
+        let shared = usage
+            .iter()
+            .find(|u| u.color == "#123456" || u.color == "#123457");
+        assert_eq!(shared.map(|u| u.count), Some(2));
+        assert!(usage.iter().any(|u| u.color == "#abcdef" && u.count == 1));
+
+        // Without tolerance the near-identical colors stay apart; the cap is honored
+        let exact = service.list_colors_usage(0.0, 100).unwrap();
+        assert!(exact.iter().any(|u| u.color == "#123456" && u.count == 1));
+        assert_eq!(service.list_colors_usage(0.0, 1).unwrap().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_dir_recursive_terminates_on_symlink_loop() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let src = temp.path().join("src");
+        fs::create_dir_all(src.join("nested")).unwrap();
+        fs::write(src.join("nested").join("file.txt"), "data").unwrap();
+        std::os::unix::fs::symlink(&src, src.join("nested").join("loop")).unwrap();
+
+        let dst = temp.path().join("dst");
+        fs::create_dir_all(&dst).unwrap();
+
+        let service = CustomThemeService::with_themes_dir(temp.path().join("themes")).unwrap();
+        service.copy_dir_recursive(&src, &dst, "loop", "").unwrap();
+
+        assert!(dst.join("nested").join("file.txt").is_file());
+        // The looping link becomes an empty directory instead of recursing forever
+        assert!(dst.join("nested").join("loop").is_dir());
+        assert!(!dst.join("nested").join("loop").join("nested").exists());
+    }
+
+    #[test]
+    fn test_export_theme_for_omarchy_omits_metadata() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let themes_dir = temp.path().join("themes");
+        let service = CustomThemeService::with_themes_dir(themes_dir.clone()).unwrap();
+
+        let theme_data = serde_json::json!({
+            "alacritty": { "colors": { "primary": { "background": "#101010" } } }
+        });
+        service
+            .create_theme_advanced("Shared".to_string(), theme_data)
+            .unwrap();
+        fs::write(themes_dir.join("shared/backgrounds/wall.png"), b"png").unwrap();
+
+        let output = temp.path().join("out");
+        let produced = service.export_theme_for_omarchy("Shared", &output).unwrap();
+
+        let export_dir = output.join("shared");
+        assert!(!export_dir.join("custom_theme.json").exists());
+        assert!(export_dir.join("alacritty.toml").is_file());
+        assert!(export_dir.join("hyprland.conf").is_file());
+        assert!(export_dir.join("backgrounds/wall.png").is_file());
+        assert!(produced.contains(&"alacritty.toml".to_string()));
+        assert!(produced.contains(&"backgrounds/wall.png".to_string()));
+    }
+
+    #[test]
+    fn test_missing_selected_background_is_repointed_then_cleared() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Walls".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("walls/backgrounds");
+        fs::write(backgrounds.join("a.png"), b"a").unwrap();
+        fs::write(backgrounds.join("b.png"), b"b").unwrap();
+
+        service
+            .set_selected_background("Walls", Some("b.png".to_string()))
+            .unwrap();
+        assert!(!service.repair_selected_background("Walls").unwrap());
+
+        fs::remove_file(backgrounds.join("b.png")).unwrap();
+        let theme = service.get_theme("Walls").unwrap();
+        assert_eq!(theme.selected_background.as_deref(), Some("a.png"));
+
+        fs::remove_file(backgrounds.join("a.png")).unwrap();
+        assert!(service.repair_selected_background("Walls").unwrap());
+        assert_eq!(
+            service.get_theme("Walls").unwrap().selected_background,
+            None
+        );
+    }
+
+    #[test]
+    fn test_preview_theme_on_wallpaper_composites_png() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Pictured".to_string(), serde_json::json!({}))
+            .unwrap();
+
+        // Swatch-only fallback without a background
+        let plain = service.preview_theme_on_wallpaper("Pictured").unwrap();
+        assert!(plain.starts_with("data:image/png;base64,"));
+
+        let wallpaper = image::RgbaImage::from_fn(64, 48, |x, y| {
+            image::Rgba([(x * 4) as u8, (y * 5) as u8, 128, 255])
+        });
+        wallpaper
+            .save(temp.path().join("pictured/backgrounds/wall.png"))
+            .unwrap();
+
+        let preview = service.preview_theme_on_wallpaper("Pictured").unwrap();
+        let encoded = preview.strip_prefix("data:image/png;base64,").unwrap();
+        assert_ne!(preview, plain);
+
+        let png = base64::decode(encoded).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert_eq!(decoded.width(), theme_preview::PREVIEW_WIDTH);
+        assert_eq!(decoded.height(), theme_preview::PREVIEW_HEIGHT);
+        assert!(png.len() > 1000);
+    }
+
+    #[test]
+    fn test_render_palette_swatch_draws_fixed_size_strip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced(
+                "Swatched".to_string(),
+                serde_json::json!({
+                    "alacritty": {
+                        "colors": {
+                            "primary": { "background": "#101010", "foreground": "#f0f0f0" },
+                            "normal": { "red": "#ff0000", "cyan": "#00ffff" }
+                        }
+                    }
+                }),
+            )
+            .unwrap();
+
+        let swatch = service.render_palette_swatch("Swatched").unwrap();
+        let encoded = swatch.strip_prefix("data:image/png;base64,").unwrap();
+        let decoded = image::load_from_memory(&base64::decode(encoded).unwrap())
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(decoded.width(), theme_preview::PALETTE_SWATCH_WIDTH);
+        assert_eq!(decoded.height(), theme_preview::PALETTE_SWATCH_HEIGHT);
+        // Background on the left edge, then foreground
+        let colors = service.get_theme("Swatched").unwrap().colors.unwrap();
+        let pixel = |hex: &str| {
+            let (r, g, b) = ColorExtractor::hex_to_rgb(hex).unwrap();
+            [r, g, b, 255]
+        };
+        assert_eq!(decoded.get_pixel(0, 0).0, pixel(&colors.primary.background));
+        assert_eq!(
+            decoded.get_pixel(30, 20).0,
+            pixel(&colors.primary.foreground)
+        );
+
+        // System themes without any colors to extract are rejected
+        fs::create_dir(temp.path().join("bare")).unwrap();
+        fs::write(temp.path().join("bare/hyprland.conf"), "general {}\n").unwrap();
+        assert!(matches!(
+            service.render_palette_swatch("Bare"),
+            Err(ThemeError::Validation(_))
+        ));
+        assert!(matches!(
+            service.render_palette_swatch("Missing"),
+            Err(ThemeError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_oversized_background_data_is_refused() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Pictured".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds_dir = temp.path().join("pictured/backgrounds");
+        fs::write(backgrounds_dir.join("small.jpg"), b"small").unwrap();
+        let huge = fs::File::create(backgrounds_dir.join("huge.jpg")).unwrap();
+        huge.set_len(theme_preview::MAX_DATA_URL_IMAGE_BYTES + 1)
+            .unwrap();
+
+        assert!(service
+            .get_background_image_data("Pictured", "small.jpg")
+            .unwrap()
+            .starts_with("data:image/jpeg;base64,"));
+        let err = service
+            .get_background_image_data("Pictured", "huge.jpg")
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("get_background_thumbnail"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_recolor_from_accent_keeps_backgrounds() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let theme_data = serde_json::json!({
+            "alacritty": {
+                "colors": {
+                    "primary": { "background": "#101010", "foreground": "#e0e0e0" },
+                    "normal": {
+                        "red": "#cc0000", "green": "#00cc00", "yellow": "#cccc00",
+                        "blue": "#0000cc", "magenta": "#cc00cc", "cyan": "#00cccc"
+                    }
+                }
+            }
+        });
+        let before = service
+            .create_theme_advanced("Reskin".to_string(), theme_data)
+            .unwrap();
+        let wallpaper = temp.path().join("reskin/backgrounds/wall.png");
+        fs::write(&wallpaper, b"wallpaper bytes").unwrap();
+        service
+            .set_selected_background("Reskin", Some("wall.png".to_string()))
+            .unwrap();
+
+        assert!(service.recolor_from_accent("Reskin", "nope").is_err());
+        let after = service.recolor_from_accent("Reskin", "#FF8800").unwrap();
+
+        let (before_colors, after_colors) = (before.colors.unwrap(), after.colors.unwrap());
+        assert_ne!(before_colors.terminal.red, after_colors.terminal.red);
+        assert_ne!(before_colors.terminal.blue, after_colors.terminal.blue);
+        assert_eq!(after_colors.accent.as_deref(), Some("#ff8800"));
+        assert_eq!(after_colors.primary.background, "#101010");
+        assert_eq!(fs::read(&wallpaper).unwrap(), b"wallpaper bytes");
+        assert_eq!(after.selected_background.as_deref(), Some("wall.png"));
+    }
+
+    #[test]
+    fn test_legacy_methods_normalize_and_reject_colors() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let primary = |theme: &CustomTheme| theme.apps["alacritty"]["colors"]["primary"].clone();
+
+        let theme = service
+            .create_theme("Named".to_string(), "Navy".to_string(), "#abc".to_string())
+            .unwrap();
+        assert_eq!(primary(&theme)["background"], "#000080");
+        assert_eq!(primary(&theme)["foreground"], "#aabbcc");
+
+        let err = service
+            .create_theme(
+                "Broken".to_string(),
+                "#GGG".to_string(),
+                "white".to_string(),
+            )
+            .unwrap_err();
+        assert!(matches!(&err, ThemeError::Validation(message)
+            if message.contains("background '#GGG'") && !message.contains("foreground")));
+        assert!(!temp.path().join("broken").exists());
+
+        let config = |background: &str, dim_foreground: &str| AlacrittyConfig {
+            colors: AlacrittyColors {
+                primary: AlacrittyPrimaryColors {
+                    background: background.to_string(),
+                    foreground: "#eeeeee".to_string(),
+                    dim_foreground: dim_foreground.to_string(),
+                },
+            },
+        };
+        let theme = service
+            .update_theme("Named", config("black", "#888"))
+            .unwrap();
+        assert_eq!(primary(&theme)["background"], "#000000");
+        assert_eq!(primary(&theme)["dim_foreground"], "#888888");
+
+        let err = service
+            .update_theme("Named", config("red2", "dim"))
+            .unwrap_err();
+        assert!(matches!(&err, ThemeError::Validation(message)
+            if message.contains("background 'red2'") && message.contains("dim_foreground 'dim'")));
+        let theme = service.get_theme("Named").unwrap();
+        assert_eq!(primary(&theme)["background"], "#000000");
+    }
+
+    #[test]
+    fn test_renamed_app_key_is_migrated_on_load() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Legacy".to_string(), serde_json::json!({}))
+            .unwrap();
+
+        let metadata_path = temp.path().join("legacy/custom_theme.json");
+        let mut raw: Value =
+            serde_json::from_str(&fs::read_to_string(&metadata_path).unwrap()).unwrap();
+        raw["apps"] = serde_json::json!({ "nvim": { "colorscheme": "tokyonight" } });
+        fs::write(&metadata_path, raw.to_string()).unwrap();
+
+        let theme = service.get_theme("Legacy").unwrap();
+        assert_eq!(theme.apps["neovim"]["colorscheme"], "tokyonight");
+        assert!(theme.apps.get("nvim").is_none());
+
+        // The migrated key is what gets saved on the next write
+        service
+            .update_theme_advanced("Legacy", serde_json::json!({}), MergeStrategy::Replace)
+            .unwrap();
+        let saved = fs::read_to_string(&metadata_path).unwrap();
+        assert!(saved.contains("\"neovim\""));
+        assert!(!saved.contains("\"nvim\""));
+    }
+
+    #[test]
+    fn test_rename_selected_background_updates_selection() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Tidy".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("tidy/backgrounds");
+        fs::write(backgrounds.join("IMG_0001.png"), b"a").unwrap();
+        fs::write(backgrounds.join("taken.png"), b"b").unwrap();
+        fs::write(
+            backgrounds.join(BACKGROUND_ORDER_FILE),
+            r#"["taken.png", "IMG_0001.png"]"#,
+        )
+        .unwrap();
+        service
+            .set_selected_background("Tidy", Some("IMG_0001.png".to_string()))
+            .unwrap();
+
+        assert!(service
+            .rename_background("Tidy", "IMG_0001.png", "taken.png")
+            .is_err());
+        assert!(service
+            .rename_background("Tidy", "IMG_0001.png", "../escape.png")
+            .is_err());
+        assert!(service
+            .rename_background("Tidy", "IMG_0001.png", "notes.txt")
+            .is_err());
+
+        let renamed = service
+            .rename_background("Tidy", "IMG_0001.png", " mountains.png ")
+            .unwrap();
+        assert_eq!(renamed, "mountains.png");
+        assert!(backgrounds.join("mountains.png").is_file());
+        assert!(!backgrounds.join("IMG_0001.png").exists());
+
+        let theme = service.get_theme("Tidy").unwrap();
+        assert_eq!(theme.selected_background.as_deref(), Some("mountains.png"));
+        let order: Vec<String> = serde_json::from_str(
+            &fs::read_to_string(backgrounds.join(BACKGROUND_ORDER_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(order, vec!["taken.png", "mountains.png"]);
+    }
+
+    #[test]
+    fn test_backgrounds_detailed_flags_animated_images() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Frame};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Moving".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("moving/backgrounds");
+
+        let write_gif = |filename: &str, frame_count: u8| {
+            let file = fs::File::create(backgrounds.join(filename)).unwrap();
+            let frames = (0..frame_count).map(|i| {
+                let buffer = image::RgbaImage::from_pixel(6, 4, image::Rgba([i * 80, 0, 0, 255]));
+                Frame::from_parts(buffer, 0, 0, Delay::from_numer_denom_ms(100, 1))
+            });
+            GifEncoder::new(file).encode_frames(frames).unwrap();
+        };
+        write_gif("looping.gif", 3);
+        write_gif("still.gif", 1);
+        image::RgbaImage::new(8, 5)
+            .save(backgrounds.join("plain.png"))
+            .unwrap();
+        // Minimal extended WebP header with the animation flag set
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0\x02".to_vec();
+        webp.extend_from_slice(&[0; 9]);
+        fs::write(backgrounds.join("spinner.webp"), &webp).unwrap();
+
+        let details = service.get_theme_backgrounds_detailed("Moving").unwrap();
+        let summary: Vec<(&str, bool)> = details
+            .iter()
+            .map(|info| (info.filename.as_str(), info.is_animated))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("looping.gif", true),
+                ("plain.png", false),
+                ("spinner.webp", true),
+                ("still.gif", false)
+            ]
+        );
+        assert_eq!((details[0].width, details[0].height), (Some(6), Some(4)));
+        assert_eq!((details[1].width, details[1].height), (Some(8), Some(5)));
+        assert_eq!(
+            details[1].size_bytes,
+            fs::metadata(backgrounds.join("plain.png")).unwrap().len()
+        );
+        assert_eq!(
+            service.get_theme_backgrounds("Moving").unwrap(),
+            ["looping.gif", "plain.png", "spinner.webp", "still.gif"]
+        );
+    }
+
+    #[test]
+    fn test_background_order_file_sets_display_order() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Sorted".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("sorted/backgrounds");
+        for filename in ["beach.png", "city.jpg", "dunes.png", "forest.png"] {
+            fs::write(backgrounds.join(filename), filename).unwrap();
+        }
+        assert_eq!(
+            service.get_theme_backgrounds("Sorted").unwrap(),
+            ["beach.png", "city.jpg", "dunes.png", "forest.png"]
+        );
+
+        // Listed files come first; stale entries are ignored and the rest stay alphabetical
+        fs::write(
+            backgrounds.join(BACKGROUND_ORDER_FILE),
+            r#"["forest.png", "gone.png", "city.jpg"]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            service.get_theme_backgrounds("Sorted").unwrap(),
+            ["forest.png", "city.jpg", "beach.png", "dunes.png"]
+        );
+
+        fs::write(backgrounds.join(BACKGROUND_ORDER_FILE), "not json").unwrap();
+        assert_eq!(
+            service.get_theme_backgrounds("Sorted").unwrap(),
+            ["beach.png", "city.jpg", "dunes.png", "forest.png"]
+        );
+    }
+
+    #[test]
+    fn test_deduplicate_backgrounds_keeps_first_copy() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Doubled".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("doubled/backgrounds");
+        fs::write(backgrounds.join("b-copy.png"), b"same pixels").unwrap();
+        fs::write(backgrounds.join("a-original.png"), b"same pixels").unwrap();
+        fs::write(backgrounds.join("c-copy.png"), b"same pixels").unwrap();
+        fs::write(backgrounds.join("d-other.png"), b"other pixels").unwrap();
+        fs::write(
+            backgrounds.join(BACKGROUND_ORDER_FILE),
+            r#"["c-copy.png", "d-other.png", "a-original.png"]"#,
+        )
+        .unwrap();
+        service
+            .set_theme_preview("Doubled", Some("b-copy.png".to_string()))
+            .unwrap();
+        service
+            .set_selected_background("Doubled", Some("c-copy.png".to_string()))
+            .unwrap();
+
+        let result = service.deduplicate_backgrounds("Doubled").unwrap();
+        assert_eq!(result.removed, ["b-copy.png", "c-copy.png"]);
+        assert_eq!(result.freed_bytes, 2 * b"same pixels".len() as u64);
+        assert!(result.failed.is_empty());
+        assert_eq!(
+            service.get_theme_backgrounds("Doubled").unwrap(),
+            ["a-original.png", "d-other.png"]
+        );
+
+        let theme = service.get_theme("Doubled").unwrap();
+        assert_eq!(theme.preview.as_deref(), Some("a-original.png"));
+        assert_eq!(theme.selected_background.as_deref(), Some("a-original.png"));
+        let order: Vec<String> = serde_json::from_str(
+            &fs::read_to_string(backgrounds.join(BACKGROUND_ORDER_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(order, ["a-original.png", "d-other.png"]);
+
+        let again = service.deduplicate_backgrounds("Doubled").unwrap();
+        assert_eq!(again, DeduplicatedBackgrounds::default());
+    }
+
+    #[test]
+    fn test_reorder_backgrounds_writes_order_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Tidy".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("tidy/backgrounds");
+        for filename in ["beach.png", "city.jpg", "forest.png"] {
+            fs::write(backgrounds.join(filename), filename).unwrap();
+        }
+
+        let order = vec!["forest.png".to_string(), "city.jpg".to_string()];
+        let ordered = service.reorder_backgrounds("Tidy", &order).unwrap();
+        assert_eq!(ordered, ["forest.png", "city.jpg", "beach.png"]);
+        assert_eq!(service.get_theme_backgrounds("Tidy").unwrap(), ordered);
+        // Files keep their names
+        for filename in ["beach.png", "city.jpg", "forest.png"] {
+            assert!(backgrounds.join(filename).is_file());
+        }
+
+        // Renaming a background keeps its place
+        service
+            .rename_background("Tidy", "city.jpg", "town.jpg")
+            .unwrap();
+        assert_eq!(
+            service.get_theme_backgrounds("Tidy").unwrap(),
+            ["forest.png", "town.jpg", "beach.png"]
+        );
+
+        for bad_order in [vec!["nope.png"], vec!["forest.png", "forest.png"]] {
+            let bad_order: Vec<String> = bad_order.into_iter().map(String::from).collect();
+            assert!(service.reorder_backgrounds("Tidy", &bad_order).is_err());
+        }
+        assert_eq!(
+            service.get_theme_backgrounds("Tidy").unwrap(),
+            ["forest.png", "town.jpg", "beach.png"]
+        );
+
+        // Read-only system themes are left alone
+        let system_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir(system_dir.path().join("backgrounds")).unwrap();
+        fs::write(system_dir.path().join("backgrounds/wall.png"), b"wall").unwrap();
+        std::os::unix::fs::symlink(system_dir.path(), temp.path().join("nord")).unwrap();
+        let err = service.reorder_backgrounds("Nord", &[]).unwrap_err();
+        assert!(err.message().contains("system theme"), "{err}");
+        assert!(!system_dir
+            .path()
+            .join("backgrounds")
+            .join(BACKGROUND_ORDER_FILE)
+            .exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rename_background_refuses_system_theme() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let system_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir(system_dir.path().join("backgrounds")).unwrap();
+        fs::write(system_dir.path().join("backgrounds/wall.png"), b"wall").unwrap();
+        std::os::unix::fs::symlink(system_dir.path(), temp.path().join("nord")).unwrap();
+
+        let err = service
+            .rename_background("Nord", "wall.png", "peak.png")
+            .unwrap_err();
+        assert!(err.message().contains("system theme"), "{err}");
+        assert!(system_dir.path().join("backgrounds/wall.png").is_file());
+    }
+
+    #[test]
+    fn test_rename_theme_moves_directory_and_updates_name() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Draft Theme".to_string(), serde_json::json!({}))
+            .unwrap();
+        let before = service.get_theme("Draft Theme").unwrap();
+
+        let renamed = service.rename_theme("Draft Theme", "Final Theme").unwrap();
+        assert_eq!(renamed.name, "Final Theme");
+        assert_ne!(renamed.modified_at, before.modified_at);
+        assert!(!temp.path().join("draft-theme").exists());
+        assert_eq!(
+            service.get_theme("Final Theme").unwrap().name,
+            "Final Theme"
+        );
+
+        // A case-only change keeps the directory and just updates the display name
+        let recased = service.rename_theme("Final Theme", "FINAL THEME").unwrap();
+        assert_eq!(recased.name, "FINAL THEME");
+        assert!(temp.path().join("final-theme").is_dir());
+    }
+
+    #[test]
+    fn test_rename_theme_rejects_taken_targets() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Mine".to_string(), serde_json::json!({}))
+            .unwrap();
+        service
+            .create_theme_advanced("Other".to_string(), serde_json::json!({}))
+            .unwrap();
+        let system_dir = tempfile::TempDir::new().unwrap();
+        std::os::unix::fs::symlink(system_dir.path(), temp.path().join("nord")).unwrap();
+
+        assert!(matches!(
+            service.rename_theme("Mine", "Other"),
+            Err(ThemeError::AlreadyExists(_))
+        ));
+        let err = service.rename_theme("Mine", "Nord").unwrap_err();
+        assert!(err.message().contains("system theme"), "{err}");
+        assert!(matches!(
+            service.rename_theme("Missing", "Anything"),
+            Err(ThemeError::NotFound(_))
+        ));
+        assert!(temp.path().join("mine").is_dir());
+    }
+
+    #[test]
+    fn test_duplicate_theme_copies_backgrounds_and_configs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced(
+                "Original".to_string(),
+                serde_json::json!({
+                    "alacritty": {"colors": {"primary": {"background": "#101010"}}}
+                }),
+            )
+            .unwrap();
+        fs::write(temp.path().join("original/backgrounds/hill.png"), b"png").unwrap();
+        service
+            .set_selected_background("Original", Some("hill.png".to_string()))
+            .unwrap();
+
+        let copy = service
+            .duplicate_theme("Original", "Original Copy")
+            .unwrap();
+        assert_eq!(copy.name, "Original Copy");
+        assert_eq!(copy.selected_background.as_deref(), Some("hill.png"));
+
+        let copy_dir = temp.path().join("original-copy");
+        assert!(copy_dir.join("backgrounds/hill.png").is_file());
+        assert_eq!(
+            fs::read_to_string(copy_dir.join("alacritty.toml")).unwrap(),
+            fs::read_to_string(temp.path().join("original/alacritty.toml")).unwrap()
+        );
+        assert_eq!(
+            service.get_theme("Original Copy").unwrap().name,
+            "Original Copy"
+        );
+        assert_eq!(service.get_theme("Original").unwrap().name, "Original");
+
+        assert!(service
+            .duplicate_theme("Original", "original copy")
+            .is_err());
+        assert!(service.duplicate_theme("Missing", "Elsewhere").is_err());
+    }
+
+    #[test]
+    fn test_export_theme_writes_zip_under_sanitized_folder() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().join("themes")).unwrap();
+        service
+            .create_theme_advanced(
+                "Shared Theme".to_string(),
+                serde_json::json!({
+                    "alacritty": {"colors": {"primary": {"background": "#101010"}}}
+                }),
+            )
+            .unwrap();
+        let wallpaper = vec![7u8; 4096];
+        fs::write(
+            temp.path().join("themes/shared-theme/backgrounds/wall.png"),
+            &wallpaper,
+        )
+        .unwrap();
+
+        let archive_path = service.export_theme("Shared Theme", temp.path()).unwrap();
+        assert_eq!(archive_path, temp.path().join("shared-theme.zip"));
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&archive_path).unwrap()).unwrap();
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        assert!(names.contains(&"shared-theme/custom_theme.json".to_string()));
+        assert!(names.contains(&"shared-theme/alacritty.toml".to_string()));
+
+        let background = archive
+            .by_name("shared-theme/backgrounds/wall.png")
+            .unwrap();
+        assert_eq!(background.compression(), zip::CompressionMethod::Stored);
+        assert_eq!(background.size(), wallpaper.len() as u64);
+    }
+
+    #[test]
+    fn test_import_theme_round_trips_export() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().join("themes")).unwrap();
+        service
+            .create_theme_advanced(
+                "Round Trip".to_string(),
+                serde_json::json!({
+                    "alacritty": {"colors": {"primary": {"background": "#202020"}}}
+                }),
+            )
+            .unwrap();
+        let theme_dir = temp.path().join("themes/round-trip");
+        fs::write(theme_dir.join("backgrounds/dunes.jpg"), b"jpeg bytes").unwrap();
+        let original = service.get_theme("Round Trip").unwrap();
+        let original_config = fs::read_to_string(theme_dir.join("alacritty.toml")).unwrap();
+
+        let archive = service.export_theme("Round Trip", temp.path()).unwrap();
+        assert!(service.import_theme(&archive, false).is_err());
+
+        service.delete_theme("Round Trip").unwrap();
+        let imported = service.import_theme(&archive, false).unwrap();
+        assert_eq!(imported.name, original.name);
+        assert_eq!(imported.apps, original.apps);
+        assert_eq!(
+            fs::read_to_string(theme_dir.join("alacritty.toml")).unwrap(),
+            original_config
+        );
+        assert_eq!(
+            fs::read(theme_dir.join("backgrounds/dunes.jpg")).unwrap(),
+            b"jpeg bytes"
+        );
+
+        assert!(service.import_theme(&archive, true).is_ok());
+        let leftovers: Vec<_> = fs::read_dir(temp.path().join("themes"))
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name())
+            .collect();
+        assert_eq!(leftovers, vec![std::ffi::OsString::from("round-trip")]);
+    }
+
+    #[test]
+    fn test_failed_replace_keeps_existing_theme() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Keeper".to_string(), serde_json::json!({}))
+            .unwrap();
+        let theme_dir = temp.path().join("keeper");
+
+        // A staged directory that can't be moved in leaves the old theme where it was
+        let missing = temp.path().join(".import-missing");
+        assert!(service.replace_theme_dir(&missing, &theme_dir).is_err());
+        assert!(theme_dir.join("custom_theme.json").is_file());
+        let entries: Vec<_> = fs::read_dir(temp.path())
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("keeper")]);
+    }
+
+    #[test]
+    fn test_import_theme_rejects_zip_slip_entries() {
+        use std::io::Write;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().join("themes")).unwrap();
+        let archive_path = temp.path().join("evil.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer
+            .start_file("evil/custom_theme.json", options)
+            .unwrap();
+        writer
+            .write_all(br#"{"name":"Evil","created_at":"","modified_at":"","apps":{}}"#)
+            .unwrap();
+        writer
+            .start_file("evil/../../escaped.txt", options)
+            .unwrap();
+        writer.write_all(b"pwned").unwrap();
+        writer.finish().unwrap();
+
+        let err = service.import_theme(&archive_path, false).unwrap_err();
+        assert_eq!(err.kind(), "parse");
+        assert!(err.message().contains("escapes"), "{err}");
+        assert!(!temp.path().join("escaped.txt").exists());
+        assert!(!temp.path().join("themes/evil").exists());
+    }
+
+    #[test]
+    fn test_background_thumbnail_is_downscaled_and_cached() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Thumbs".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("thumbs/backgrounds");
+        let source = backgrounds.join("wide.png");
+        image::RgbaImage::from_pixel(400, 200, image::Rgba([200, 80, 40, 255]))
+            .save(&source)
+            .unwrap();
+
+        let thumbnail = service
+            .get_background_thumbnail("Thumbs", "wide.png", 100)
+            .unwrap();
+        let encoded = thumbnail.strip_prefix("data:image/jpeg;base64,").unwrap();
+        let decoded = image::load_from_memory(&base64::decode(encoded).unwrap()).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (100, 50));
+
+        let cached = || -> Vec<_> {
+            fs::read_dir(backgrounds.join(theme_preview::THUMBNAILS_DIR))
+                .unwrap()
+                .flatten()
+                .map(|entry| entry.path())
+                .collect()
+        };
+        let first_cache = cached();
+        assert_eq!(first_cache.len(), 1);
+        assert_eq!(
+            service
+                .get_background_thumbnail("Thumbs", "wide.png", 100)
+                .unwrap(),
+            thumbnail
+        );
+        assert!(!service
+            .get_theme_backgrounds("Thumbs")
+            .unwrap()
+            .iter()
+            .any(|name| name.starts_with('.')));
+
+        // A newer source replaces the stale thumbnail
+        fs::File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        service
+            .get_background_thumbnail("Thumbs", "wide.png", 100)
+            .unwrap();
+        let second_cache = cached();
+        assert_eq!(second_cache.len(), 1);
+        assert_ne!(second_cache, first_cache);
+    }
+
+    #[test]
+    fn test_add_backgrounds_reports_skipped_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut service = CustomThemeService::with_themes_dir(temp.path().join("themes")).unwrap();
+        service.set_background_limits((64, 64), 1024 * 1024);
+        service
+            .create_theme_advanced("Limits".to_string(), serde_json::json!({}))
+            .unwrap();
+
+        let small = temp.path().join("small.png");
+        image::RgbaImage::new(16, 16).save(&small).unwrap();
+        let wide = temp.path().join("wide.png");
+        image::RgbaImage::new(100, 10).save(&wide).unwrap();
+        let notes = temp.path().join("notes.txt");
+        fs::write(&notes, "not an image").unwrap();
+        let missing = temp.path().join("missing.png");
+
+        let paths = [&small, &wide, &notes, &missing]
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        let result = service.add_theme_backgrounds("Limits", paths).unwrap();
+
+        assert_eq!(result.copied, vec!["small.png".to_string()]);
+        let skipped: Vec<&str> = result
+            .skipped
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                wide.to_str().unwrap(),
+                notes.to_str().unwrap(),
+                missing.to_str().unwrap()
+            ]
+        );
+        assert!(result.skipped[0].1.contains("100x10"));
+        assert!(!temp
+            .path()
+            .join("themes/limits/backgrounds/wide.png")
+            .exists());
+    }
 
-    let alacritty_config = AlacrittyConfig {
-        colors: AlacrittyColors {
-            primary: AlacrittyPrimaryColors {
-                background,
-                foreground,
-                dim_foreground,
-            },
-        },
-    };
+    /// Serve a single canned HTTP response on localhost and return its base URL
+    fn serve_once(content_type: &'static str, body: Vec<u8>) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        });
+        format!("http://{address}")
+    }
 
-    let result = service.update_theme(&name, alacritty_config);
+    /// Encode a blank `width`x`height` image in the given format
+    fn encoded_image(width: u32, height: u32, format: image::ImageFormat) -> Vec<u8> {
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(width, height)
+            .write_to(&mut bytes, format)
+            .unwrap();
+        bytes.into_inner()
+    }
 
-    // Invalidate cache for the updated theme
-    if result.is_ok() {
-        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
-            cache.invalidate_theme(&name).await;
-            // Trigger background refresh to update the theme
-            let _ = cache.trigger_background_refresh().await;
-        }
+    #[test]
+    fn test_add_background_from_url_names_and_validates_download() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Remote".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("remote/backgrounds");
+
+        let png = encoded_image(16, 16, image::ImageFormat::Png);
+        let url = serve_once("image/png", png.clone());
+        let stored = service
+            .add_theme_background_from_url("Remote", &format!("{url}/walls/peak.png?size=large"))
+            .unwrap();
+        assert_eq!(stored, "peak.png");
+        assert_eq!(fs::read(backgrounds.join("peak.png")).unwrap(), png);
+
+        let jpeg = encoded_image(16, 16, image::ImageFormat::Jpeg);
+        let url = serve_once("image/jpeg; charset=binary", jpeg);
+        let hashed = service
+            .add_theme_background_from_url("Remote", &format!("{url}/download"))
+            .unwrap();
+        assert!(hashed.ends_with(".jpg"), "{hashed}");
+
+        // The content is checked, not the Content-Type or the URL's extension
+        let url = serve_once("image/png", b"<html></html>".to_vec());
+        assert!(service
+            .add_theme_background_from_url("Remote", &format!("{url}/page.png"))
+            .is_err());
+        let url = serve_once("text/html", b"<html></html>".to_vec());
+        assert!(service
+            .add_theme_background_from_url("Remote", &format!("{url}/page"))
+            .is_err());
+
+        service.set_background_limits((8, 8), 1024 * 1024);
+        let url = serve_once("image/png", png.clone());
+        let err = service
+            .add_theme_background_from_url("Remote", &format!("{url}/wide.png"))
+            .unwrap_err();
+        assert!(err.message().contains("16x16"), "{}", err.message());
+        assert!(!backgrounds.join("wide.png").exists());
+
+        service.set_background_limits((8000, 8000), 4);
+        let url = serve_once("image/png", png);
+        assert!(service
+            .add_theme_background_from_url("Remote", &format!("{url}/big.png"))
+            .is_err());
+        assert!(!backgrounds.join("big.png").exists());
+
+        assert!(service
+            .add_theme_background_from_url("Remote", "file:///etc/passwd")
+            .is_err());
+
+        // Rejected downloads leave nothing behind
+        let mut names: Vec<String> = fs::read_dir(&backgrounds)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec![hashed, "peak.png".to_string()]);
     }
 
-    result
-}
+    #[test]
+    fn test_background_name_from_url() {
+        assert_eq!(
+            CustomThemeService::background_name_from_url(
+                "https://cdn.example.com/a/b/Sky.JPG",
+                "jpg"
+            ),
+            "Sky.JPG"
+        );
+        let hashed = CustomThemeService::background_name_from_url("https://example.com/", "webp");
+        assert_eq!(hashed.len(), "0123456789abcdef.webp".len());
+        assert_eq!(
+            hashed,
+            CustomThemeService::background_name_from_url("https://example.com/", "webp")
+        );
+    }
 
-#[tauri::command(rename_all = "snake_case")]
-pub async fn update_custom_theme_advanced(
-    app_handle: AppHandle,
-    name: String,
-    theme_data: Value,
-) -> Result<CustomTheme, String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    let result = service.update_theme_advanced(&name, theme_data);
+    #[test]
+    fn test_alacritty_generator_capabilities() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let capabilities = service.get_generator_capabilities();
+
+        let alacritty = capabilities
+            .iter()
+            .find(|c| c.app == "alacritty")
+            .expect("alacritty should be registered");
+        assert_eq!(
+            alacritty,
+            &GeneratorCapabilities {
+                app: "alacritty".to_string(),
+                can_parse: false,
+                multi_file: false,
+                supports_raw_append: true,
+                validates_output: true,
+                uses_accent: false,
+            }
+        );
+        assert!(capabilities
+            .iter()
+            .any(|c| c.app == "waybar" && c.uses_accent));
+    }
 
-    // Invalidate cache for the updated theme
-    if result.is_ok() {
-        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
-            cache.invalidate_theme(&name).await;
-            // Trigger background refresh to update the theme
-            let _ = cache.trigger_background_refresh().await;
+    #[test]
+    fn test_convert_png_background_to_webp() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Formats".to_string(), serde_json::json!({}))
+            .unwrap();
+        let backgrounds = temp.path().join("formats/backgrounds");
+        image::RgbaImage::from_pixel(8, 4, image::Rgba([10, 120, 200, 255]))
+            .save(backgrounds.join("sky.png"))
+            .unwrap();
+        service
+            .set_selected_background("Formats", Some("sky.png".to_string()))
+            .unwrap();
+
+        assert!(service
+            .convert_background_format("Formats", "sky.png", "tiff", false)
+            .is_err());
+
+        let converted = service
+            .convert_background_format("Formats", "sky.png", "webp", true)
+            .unwrap();
+        assert_eq!(converted, "sky.webp");
+        assert!(!backgrounds.join("sky.png").exists());
+
+        let decoded = theme_preview::decode_image(&backgrounds.join("sky.webp")).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (8, 4));
+        let theme = service.get_theme("Formats").unwrap();
+        assert_eq!(theme.selected_background.as_deref(), Some("sky.webp"));
+
+        // Read-only system themes are left alone
+        let system_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir(system_dir.path().join("backgrounds")).unwrap();
+        image::RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 0, 255]))
+            .save(system_dir.path().join("backgrounds/wall.png"))
+            .unwrap();
+        std::os::unix::fs::symlink(system_dir.path(), temp.path().join("nord")).unwrap();
+        let err = service
+            .convert_background_format("Nord", "wall.png", "webp", true)
+            .unwrap_err();
+        assert!(err.message().contains("system theme"), "{err}");
+        assert!(system_dir.path().join("backgrounds/wall.png").is_file());
+        assert!(!system_dir.path().join("backgrounds/wall.webp").exists());
+    }
+
+    #[test]
+    fn test_colors_history_lists_earlier_palettes_in_order() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let palette = |background: &str| {
+            serde_json::json!({
+                "alacritty": {
+                    "colors": {
+                        "primary": { "background": background, "foreground": "#eeeeee" },
+                        "normal": {
+                            "red": "#cc0000", "green": "#00cc00", "yellow": "#cccc00",
+                            "blue": "#0000cc", "magenta": "#cc00cc", "cyan": "#00cccc"
+                        }
+                    }
+                }
+            })
+        };
+
+        service
+            .create_theme_advanced("Evolving".to_string(), palette("#111111"))
+            .unwrap();
+        assert!(service
+            .get_theme_colors_history("Evolving")
+            .unwrap()
+            .is_empty());
+
+        service
+            .update_theme_advanced("Evolving", palette("#222222"), MergeStrategy::Replace)
+            .unwrap();
+        service
+            .update_theme_advanced("Evolving", palette("#333333"), MergeStrategy::Replace)
+            .unwrap();
+
+        let history = service.get_theme_colors_history("Evolving").unwrap();
+        let backgrounds: Vec<&str> = history
+            .iter()
+            .map(|snapshot| snapshot.colors.primary.background.as_str())
+            .collect();
+        assert_eq!(backgrounds, vec!["#111111", "#222222"]);
+    }
+
+    #[test]
+    fn test_suggest_theme_name_skips_existing_copies() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+
+        assert_eq!(service.suggest_theme_name("foo"), "foo (copy)");
+
+        fs::create_dir_all(
+            temp.path()
+                .join(CustomThemeService::sanitize_name("foo (copy)").unwrap()),
+        )
+        .unwrap();
+        assert_eq!(service.suggest_theme_name("foo"), "foo (copy 2)");
+    }
+
+    #[test]
+    fn test_batch_create_themes_continues_past_failures() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+
+        let results = service.batch_create_themes(vec![
+            ("First Theme".to_string(), serde_json::json!({})),
+            ("@@@".to_string(), serde_json::json!({})),
+            ("Second Theme".to_string(), serde_json::json!({})),
+        ]);
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].theme.is_some());
+        assert!(results[1].theme.is_none());
+        assert!(results[1].error.is_some());
+        assert!(results[2].theme.is_some());
+        assert!(temp.path().join("first-theme/custom_theme.json").is_file());
+        assert!(temp.path().join("second-theme/custom_theme.json").is_file());
+
+        // Names that collide within one batch are rejected without touching the first
+        let results = service.batch_create_themes(vec![
+            ("Base16 Ocean".to_string(), serde_json::json!({})),
+            ("base16-ocean".to_string(), serde_json::json!({})),
+        ]);
+        assert!(results[0].theme.is_some());
+        assert!(results[1]
+            .error
+            .as_deref()
+            .is_some_and(|e| e.contains("more than once")));
+    }
+
+    #[test]
+    fn test_configured_fallback_colors_used_for_colorless_theme() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+
+        let mut fallback = ColorExtractor::get_fallback_colors();
+        fallback.primary.background = "#102030".to_string();
+        service.set_fallback_colors(fallback);
+
+        let theme = service
+            .create_theme_advanced("Colorless".to_string(), serde_json::json!({ "dunst": {} }))
+            .unwrap();
+
+        assert_eq!(theme.colors.unwrap().primary.background, "#102030");
+        // Generators that follow the palette get the configured fallback too
+        let dunstrc = fs::read_to_string(temp.path().join("colorless").join("dunstrc")).unwrap();
+        assert!(dunstrc.contains("background = \"#102030\""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_edit_system_theme_creates_custom_fork() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let system_dir = temp.path().join("system").join("nord");
+        fs::create_dir_all(&system_dir).unwrap();
+        fs::write(
+            system_dir.join("alacritty.toml"),
+            "[colors.primary]\nbackground = \"#2e3440\"\nforeground = \"#d8dee9\"\n",
+        )
+        .unwrap();
+
+        let themes_dir = temp.path().join("themes");
+        fs::create_dir_all(&themes_dir).unwrap();
+        std::os::unix::fs::symlink(&system_dir, themes_dir.join("nord")).unwrap();
+
+        let service = CustomThemeService::with_themes_dir(themes_dir.clone()).unwrap();
+        let theme_data = serde_json::json!({
+            "alacritty": { "colors": { "primary": { "background": "#000000" } } }
+        });
+        let updated = service
+            .update_theme_advanced("nord", theme_data, MergeStrategy::Replace)
+            .unwrap();
+
+        assert_eq!(updated.name, "nord-custom");
+        assert!(themes_dir
+            .join("nord-custom")
+            .join("custom_theme.json")
+            .is_file());
+        assert_eq!(
+            updated.apps["alacritty"]["colors"]["primary"]["foreground"],
+            "#d8dee9"
+        );
+        assert_eq!(
+            updated.apps["alacritty"]["colors"]["primary"]["background"],
+            "#000000"
+        );
+
+        // The system theme itself is untouched
+        assert!(!system_dir.join("custom_theme.json").exists());
+        let original = fs::read_to_string(system_dir.join("alacritty.toml")).unwrap();
+        assert!(original.contains("#2e3440"));
+
+        // Subsequent edits are redirected to the same fork
+        let again = service
+            .update_theme_advanced("nord", serde_json::json!({}), MergeStrategy::Replace)
+            .unwrap();
+        assert_eq!(again.name, "nord-custom");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_edit_system_theme_without_fork_fails() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let system_dir = temp.path().join("system").join("nord");
+        fs::create_dir_all(&system_dir).unwrap();
+
+        let themes_dir = temp.path().join("themes");
+        fs::create_dir_all(&themes_dir).unwrap();
+        std::os::unix::fs::symlink(&system_dir, themes_dir.join("nord")).unwrap();
+
+        let mut service = CustomThemeService::with_themes_dir(themes_dir.clone()).unwrap();
+        service.set_fork_system_theme_on_edit(false);
+
+        assert!(service
+            .update_theme_advanced("nord", serde_json::json!({}), MergeStrategy::Replace)
+            .is_err());
+        assert!(!themes_dir.join("nord-custom").exists());
+    }
+
+    #[test]
+    fn test_derive_light_variant_inverts_palette_and_keeps_hues() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let theme_data = serde_json::json!({
+            "alacritty": {
+                "colors": {
+                    "primary": { "background": "#1a1b26", "foreground": "#c0caf5" },
+                    "normal": {
+                        "red": "#f7768e", "green": "#9ece6a", "yellow": "#e0af68",
+                        "blue": "#7aa2f7", "magenta": "#bb9af7", "cyan": "#7dcfff"
+                    }
+                }
+            }
+        });
+        service
+            .create_theme_advanced("Night".to_string(), theme_data)
+            .unwrap();
+        fs::write(temp.path().join("night/backgrounds/wall.png"), b"wall").unwrap();
+
+        let light = service.derive_light_variant("Night", "Day").unwrap();
+        let colors = light.colors.unwrap();
+        let (background, foreground) = (&colors.primary.background, &colors.primary.foreground);
+        assert!(ColorExtractor::relative_luminance(background).unwrap() > 0.7);
+        assert!(ColorExtractor::contrast_ratio(foreground, background).unwrap() >= 4.5);
+
+        let hue = |color: &str| ColorExtractor::hex_to_hsl(color).unwrap().0;
+        assert!((hue(&colors.terminal.blue) - hue("#7aa2f7")).abs() < 5.0);
+        assert!((hue(&colors.terminal.red) - hue("#f7768e")).abs() < 5.0);
+        assert!(
+            ColorExtractor::contrast_ratio(&colors.terminal.yellow, background).unwrap() >= 3.0
+        );
+
+        let day_dir = temp.path().join("day");
+        assert!(day_dir.join("backgrounds/wall.png").exists());
+        let alacritty = fs::read_to_string(day_dir.join("alacritty.toml")).unwrap();
+        assert!(alacritty.contains(background.as_str()));
+
+        // Source is untouched and a light theme can't be inverted again
+        let night = service.get_theme("Night").unwrap();
+        assert_eq!(night.colors.unwrap().primary.background, "#1a1b26");
+        assert!(service.derive_light_variant("Day", "Day 2").is_err());
+        assert!(!temp.path().join("day-2").exists());
+    }
+
+    #[test]
+    fn test_theme_meta_is_stored_and_survives_color_backfill() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let theme_data = serde_json::json!({
+            "meta": { "tags": ["dark", " Warm ", "DARK", ""], "author": "Ada", "version": "1.0" },
+            "alacritty": {}
+        });
+        let created = service
+            .create_theme_advanced("Tagged".to_string(), theme_data)
+            .unwrap();
+        assert_eq!(created.tags, ["dark", "Warm"]);
+        assert_eq!(created.author.as_deref(), Some("Ada"));
+        assert!(created.apps.get("meta").is_none());
+
+        // Only the keys present in `meta` change; null clears
+        let updated = service
+            .update_theme_advanced(
+                "Tagged",
+                serde_json::json!({ "meta": { "version": "1.1", "author": null } }),
+                MergeStrategy::Replace,
+            )
+            .unwrap();
+        assert_eq!(updated.tags, ["dark", "Warm"]);
+        assert_eq!(updated.version.as_deref(), Some("1.1"));
+        assert!(updated.author.is_none());
+
+        let tagged = service
+            .set_theme_tags("Tagged", vec!["light".to_string()])
+            .unwrap();
+        assert_eq!(tagged.tags, ["light"]);
+
+        // Dropping the palette forces the backwards-compat rewrite on load
+        let metadata_path = temp.path().join("tagged/custom_theme.json");
+        let mut metadata: Value =
+            serde_json::from_str(&fs::read_to_string(&metadata_path).unwrap()).unwrap();
+        metadata.as_object_mut().unwrap().remove("colors");
+        fs::write(&metadata_path, metadata.to_string()).unwrap();
+
+        let reloaded = service.get_theme("Tagged").unwrap();
+        assert!(reloaded.colors.is_some());
+        let rewritten: Value =
+            serde_json::from_str(&fs::read_to_string(&metadata_path).unwrap()).unwrap();
+        assert_eq!(rewritten["tags"], serde_json::json!(["light"]));
+        assert_eq!(rewritten["version"], "1.1");
+    }
+
+    #[test]
+    fn test_theme_description_from_meta_template_and_setter() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let created = service
+            .create_theme_advanced(
+                "Described".to_string(),
+                serde_json::json!({ "meta": { "description": " Warm dusk " }, "alacritty": {} }),
+            )
+            .unwrap();
+        assert_eq!(created.description, "Warm dusk");
+
+        let updated = service
+            .set_theme_description("Described", "Cool \"dawn\"")
+            .unwrap();
+        assert_eq!(updated.description, "Cool \"dawn\"");
+        assert_eq!(
+            service.get_theme("Described").unwrap().description,
+            "Cool \"dawn\""
+        );
+
+        // Metadata written before descriptions existed loads with an empty one
+        let metadata_path = temp.path().join("described/custom_theme.json");
+        let mut metadata: Value =
+            serde_json::from_str(&fs::read_to_string(&metadata_path).unwrap()).unwrap();
+        metadata.as_object_mut().unwrap().remove("description");
+        fs::write(&metadata_path, metadata.to_string()).unwrap();
+        assert_eq!(service.get_theme("Described").unwrap().description, "");
+
+        // The init template's placeholder takes the description, escaped for JSON
+        let template =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/template/custom_theme.json");
+        let dst = temp.path().join("from-template.json");
+        service
+            .copy_and_process_metadata_template(&template, &dst, "Templated", "Line \"one\"\n")
+            .unwrap();
+        let theme: CustomTheme = serde_json::from_str(&fs::read_to_string(&dst).unwrap()).unwrap();
+        assert_eq!(theme.name, "Templated");
+        assert_eq!(theme.description, "Line \"one\"");
+    }
+
+    #[test]
+    fn test_search_themes_ranks_matches_and_requires_tags() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        for (name, tags) in [
+            ("Ocean Night", vec!["dark"]),
+            ("Night", vec!["dark", "warm"]),
+            ("Midnight", vec!["dark"]),
+            ("Daylight", vec!["light"]),
+        ] {
+            let theme_data = serde_json::json!({ "meta": { "tags": tags } });
+            service
+                .create_theme_advanced(name.to_string(), theme_data)
+                .unwrap();
         }
+
+        let names = |themes: Vec<CustomTheme>| -> Vec<String> {
+            themes.into_iter().map(|t| t.name).collect()
+        };
+        assert_eq!(
+            names(service.search_themes("NIGHT", &[]).unwrap()),
+            ["Night", "Midnight", "Ocean Night"]
+        );
+        assert_eq!(
+            names(
+                service
+                    .search_themes("night", &["Warm".to_string()])
+                    .unwrap()
+            ),
+            ["Night"]
+        );
+        assert_eq!(
+            names(service.search_themes("", &["dark".to_string()]).unwrap()),
+            ["Midnight", "Night", "Ocean Night"]
+        );
+        assert_eq!(
+            names(service.search_theme_dirs(&["daylight".to_string()], "light", &[])),
+            ["Daylight"]
+        );
     }
 
-    result
-}
+    #[test]
+    fn test_apps_usage_counts_registered_apps() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced(
+                "First".to_string(),
+                serde_json::json!({ "alacritty": {}, "waybar": {}, "not-an-app": {} }),
+            )
+            .unwrap();
+        service
+            .create_theme_advanced("Second".to_string(), serde_json::json!({ "alacritty": {} }))
+            .unwrap();
+        fs::create_dir(temp.path().join("broken")).unwrap();
+        fs::write(
+            temp.path().join("broken/custom_theme.json"),
+            r#"{"name": "Broken", "created_at": "", "modified_at": "", "apps": ["alacritty"]}"#,
+        )
+        .unwrap();
+
+        let usage = service.get_apps_usage().unwrap();
+        assert_eq!(usage["alacritty"], 2);
+        assert_eq!(usage["waybar"], 1);
+        assert_eq!(usage["btop"], 0);
+        assert!(!usage.contains_key("not-an-app"));
+        assert_eq!(usage.len(), service.generator_registry.get_all_apps().len());
+    }
 
-#[tauri::command]
-pub async fn get_custom_theme(app_handle: AppHandle, name: String) -> Result<CustomTheme, String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    service.get_theme(&name)
-}
+    #[test]
+    fn test_list_themes_paginated_is_sorted_and_counts_all() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        for name in ["Nord", "ash", "Catppuccin", "Dracula", "everforest"] {
+            service
+                .create_theme_advanced(name.to_string(), serde_json::json!({}))
+                .unwrap();
+        }
 
-#[tauri::command]
-pub async fn list_custom_themes(app_handle: AppHandle) -> Result<Vec<CustomTheme>, String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    service.list_themes()
-}
+        let page = service.list_themes_paginated(1, 2).unwrap();
+        assert_eq!(page.total, 5);
+        let names: Vec<String> = page.items.into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["Catppuccin", "Dracula"]);
+
+        let last = service.list_themes_paginated(4, 10).unwrap();
+        assert_eq!(last.items.len(), 1);
+        assert_eq!(last.items[0].name, "Nord");
+        assert!(service
+            .list_themes_paginated(9, 10)
+            .unwrap()
+            .items
+            .is_empty());
+    }
 
-#[tauri::command]
-pub async fn delete_custom_theme(app_handle: AppHandle, name: String) -> Result<(), String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    let result = service.delete_theme(&name);
+    #[test]
+    fn test_list_themes_skips_leftover_staging_dirs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Kept".to_string(), serde_json::json!({}))
+            .unwrap();
+
+        // What a crash during creation leaves behind
+        let staging = temp.path().join(".create-1234");
+        fs::create_dir(&staging).unwrap();
+        fs::copy(
+            temp.path().join("kept/custom_theme.json"),
+            staging.join("custom_theme.json"),
+        )
+        .unwrap();
+
+        let names: Vec<String> = service
+            .list_themes()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, ["Kept"]);
+    }
 
-    // Invalidate cache for the deleted theme
-    if result.is_ok() {
-        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
-            cache.invalidate_theme(&name).await;
-            // Trigger background refresh to remove the theme from cache
-            let _ = cache.trigger_background_refresh().await;
+    #[test]
+    fn test_clean_up_staging_dirs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        for name in ["Kept", "Moved"] {
+            service
+                .create_theme_advanced(name.to_string(), serde_json::json!({}))
+                .unwrap();
+        }
+
+        // An overwrite of "kept" that finished, and one of "moved" that did not
+        let copy_metadata = |theme: &str, staging: &str| {
+            fs::create_dir(temp.path().join(staging)).unwrap();
+            fs::copy(
+                temp.path().join(theme).join("custom_theme.json"),
+                temp.path().join(staging).join("custom_theme.json"),
+            )
+            .unwrap();
+        };
+        copy_metadata("kept", ".replaced-1");
+        fs::rename(temp.path().join("moved"), temp.path().join(".replaced-2")).unwrap();
+        copy_metadata("kept", ".create-3");
+        fs::create_dir(temp.path().join(".import-4")).unwrap();
+        fs::create_dir(temp.path().join(".config")).unwrap();
+
+        assert_eq!(service.clean_up_staging_dirs(), 4);
+
+        let mut entries: Vec<String> = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, [".config", "kept", "moved"]);
+    }
+
+    #[test]
+    fn test_restore_theme_backup_rolls_back_and_prunes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service.set_max_theme_backups(2);
+        let palette = |background: &str| {
+            serde_json::json!({
+                "alacritty": { "colors": { "primary": { "background": background } } }
+            })
+        };
+
+        service
+            .create_theme_advanced("Rollback".to_string(), palette("#111111"))
+            .unwrap();
+        for background in ["#222222", "#333333", "#444444"] {
+            service
+                .update_theme_advanced("Rollback", palette(background), MergeStrategy::Replace)
+                .unwrap();
         }
+
+        // Only the newest two earlier versions survive, newest first
+        let backups = service.list_theme_backups("Rollback").unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(backups[0].timestamp > backups[1].timestamp);
+
+        let restored = service
+            .restore_theme_backup("Rollback", &backups[1].timestamp)
+            .unwrap();
+        let background = |theme: &CustomTheme| {
+            theme.apps["alacritty"]["colors"]["primary"]["background"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(background(&restored), "#222222");
+        assert_eq!(
+            background(&service.get_theme("Rollback").unwrap()),
+            "#222222"
+        );
+        let alacritty = fs::read_to_string(temp.path().join("rollback/alacritty.toml")).unwrap();
+        assert!(alacritty.contains("#222222"));
+
+        // The replaced state was backed up, so the restore can be undone
+        let latest = &service.list_theme_backups("Rollback").unwrap()[0];
+        service
+            .restore_theme_backup("Rollback", &latest.timestamp)
+            .unwrap();
+        assert_eq!(
+            background(&service.get_theme("Rollback").unwrap()),
+            "#444444"
+        );
+
+        assert!(service
+            .restore_theme_backup("Rollback", "../custom_theme")
+            .is_err());
     }
 
-    result
-}
+    #[test]
+    fn test_create_theme_io_error_leaves_no_partial_theme() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let theme_data = serde_json::json!({
+            "alacritty": { "colors": { "primary": { "background": "#101010" } } },
+            "waybar": {}
+        });
 
-#[tauri::command]
-pub async fn init_custom_theme(app_handle: AppHandle, name: String) -> Result<CustomTheme, String> {
-    log::info!("Initializing custom theme '{name}'");
-    let service = CustomThemeService::new(&app_handle)?;
-    service.init_theme(name, String::new())
-}
+        // A directory where alacritty.toml should go makes that write fail
+        let staging_dir = temp.path().join(".create-test");
+        fs::create_dir_all(staging_dir.join("alacritty.toml")).unwrap();
+        assert!(service
+            .create_theme_via_staging(&staging_dir, "Broken".to_string(), theme_data.clone())
+            .is_err());
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 0);
+
+        let theme = service
+            .create_theme_advanced("Broken".to_string(), theme_data)
+            .unwrap();
+        assert_eq!(theme.name, "Broken");
+        assert!(temp.path().join("broken/alacritty.toml").is_file());
+        assert!(temp.path().join("broken/custom_theme.json").is_file());
+        let entries: Vec<_> = fs::read_dir(temp.path()).unwrap().flatten().collect();
+        assert_eq!(entries.len(), 1);
+    }
 
-#[tauri::command]
-pub async fn get_app_schemas(app_handle: AppHandle) -> Result<Value, String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    Ok(service.get_app_schemas())
-}
+    #[test]
+    fn test_deep_merge_adds_overwrites_and_deletes_with_null() {
+        let mut target = serde_json::json!({
+            "alacritty": { "colors": { "primary": { "background": "#000000", "foreground": "#ffffff" } } },
+            "waybar": { "colors": { "background": "#111111" } },
+            "opacity": 0.9
+        });
 
-#[tauri::command]
-pub async fn get_theme_backgrounds(
-    app_handle: AppHandle,
-    theme_name: String,
-) -> Result<Vec<String>, String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    service.get_theme_backgrounds(&theme_name)
-}
+        CustomThemeService::deep_merge(
+            &mut target,
+            &serde_json::json!({
+                "alacritty": { "colors": { "primary": { "background": "#222222", "foreground": null } } },
+                "waybar": null,
+                "mako": { "colors": { "normal": { "text_color": "#eeeeee", "border_color": null } } },
+                "opacity": 1.0,
+                "missing": null
+            }),
+            MergeStrategy::Replace,
+        );
 
-#[tauri::command]
-pub async fn add_theme_backgrounds(
-    app_handle: AppHandle,
-    theme_name: String,
-    source_paths: Vec<String>,
-) -> Result<Vec<String>, String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    service.add_theme_backgrounds(&theme_name, source_paths)
-}
+        assert_eq!(
+            target,
+            serde_json::json!({
+                "alacritty": { "colors": { "primary": { "background": "#222222" } } },
+                "mako": { "colors": { "normal": { "text_color": "#eeeeee" } } },
+                "opacity": 1.0
+            })
+        );
+    }
 
-#[tauri::command]
-pub async fn remove_theme_background(
-    app_handle: AppHandle,
-    theme_name: String,
-    filename: String,
-) -> Result<(), String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    service.remove_theme_background(&theme_name, &filename)
-}
+    #[test]
+    fn test_deep_merge_array_strategies() {
+        let target = serde_json::json!({
+            "hyprland": { "gradient": ["#111111", "#222222"], "angle": 45 },
+            "tags": ["dark"]
+        });
+        let update = serde_json::json!({
+            "hyprland": { "gradient": ["#333333"] },
+            "tags": ["warm"]
+        });
 
-#[tauri::command]
-pub async fn get_background_image_data(
-    app_handle: AppHandle,
-    theme_name: String,
-    filename: String,
-) -> Result<String, String> {
-    let service = CustomThemeService::new(&app_handle)?;
-    service.get_background_image_data(&theme_name, &filename)
-}
+        let mut replaced = target.clone();
+        CustomThemeService::deep_merge(&mut replaced, &update, MergeStrategy::Replace);
+        assert_eq!(
+            replaced,
+            serde_json::json!({
+                "hyprland": { "gradient": ["#333333"], "angle": 45 },
+                "tags": ["warm"]
+            })
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let mut concatenated = target;
+        CustomThemeService::deep_merge(&mut concatenated, &update, MergeStrategy::ConcatArrays);
+        assert_eq!(
+            concatenated,
+            serde_json::json!({
+                "hyprland": { "gradient": ["#111111", "#222222", "#333333"], "angle": 45 },
+                "tags": ["dark", "warm"]
+            })
+        );
+    }
 
     #[test]
-    fn test_sanitize_name() {
+    fn test_create_generates_configs_from_the_whole_theme() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced(
+                "Fresh".to_string(),
+                serde_json::json!({
+                    "alacritty": {
+                        "colors": {
+                            "primary": { "background": "#101820", "foreground": "#e0e0e0" },
+                            "normal": {
+                                "red": "#cc3333", "green": "#33cc33", "yellow": "#cccc33",
+                                "blue": "#3333cc", "magenta": "#cc33cc", "cyan": "#33cccc"
+                            }
+                        }
+                    },
+                    "hyprland": {}
+                }),
+            )
+            .unwrap();
+
+        let theme_dir = temp.path().join("fresh");
+        let alacritty = fs::read_to_string(theme_dir.join("alacritty.toml")).unwrap();
+        assert!(alacritty.contains("background = \"#101820\""));
+        assert!(alacritty.contains("foreground = \"#e0e0e0\""));
+        // Hyprland has no colors of its own and falls back to the alacritty palette
+        let hyprland = fs::read_to_string(theme_dir.join("hyprland.conf")).unwrap();
+        assert!(hyprland.contains("col.inactive_border = rgb(101820)"));
+    }
+
+    #[test]
+    fn test_create_passes_extracted_accent_to_generators() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let theme = service
+            .create_theme_advanced(
+                "Accented".to_string(),
+                serde_json::json!({
+                    "alacritty": {
+                        "colors": {
+                            "primary": { "background": "#101010", "foreground": "#e0e0e0" },
+                            "normal": {
+                                "red": "#cc0000", "green": "#00cc00", "yellow": "#cccc00",
+                                "blue": "#0000cc", "magenta": "#cc00cc", "cyan": "#00cccc"
+                            },
+                            "accent": "#ff8800"
+                        }
+                    },
+                    "waybar": {},
+                    "hyprland": {}
+                }),
+            )
+            .unwrap();
         assert_eq!(
-            CustomThemeService::sanitize_name("My Cool Theme"),
-            "my-cool-theme"
+            theme.colors.and_then(|c| c.accent).as_deref(),
+            Some("#ff8800")
         );
+
+        let theme_dir = temp.path().join("accented");
+        let waybar = fs::read_to_string(theme_dir.join("waybar.css")).unwrap();
+        assert!(waybar.contains("@define-color accent #ff8800;"));
+        let hyprland = fs::read_to_string(theme_dir.join("hyprland.conf")).unwrap();
+        assert!(hyprland.contains("col.active_border = rgb(ff8800)"));
+    }
+
+    #[test]
+    fn test_raw_files_written_on_create_and_update() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced(
+                "Raw".to_string(),
+                serde_json::json!({ "files": { "starship.toml": "format = \"$all\"\n" } }),
+            )
+            .unwrap();
+        assert!(temp.path().join("raw/starship.toml").is_file());
+
+        service
+            .update_theme_advanced(
+                "Raw",
+                serde_json::json!({ "files": { "zathura/zathurarc": "set recolor true\n" } }),
+                MergeStrategy::Replace,
+            )
+            .unwrap();
         assert_eq!(
-            CustomThemeService::sanitize_name("Test_Theme-123"),
-            "test_theme-123"
+            fs::read_to_string(temp.path().join("raw/zathura/zathurarc")).unwrap(),
+            "set recolor true\n"
+        );
+
+        // An escaping path rejects the whole update and leaves the metadata alone
+        assert!(service
+            .update_theme_advanced(
+                "Raw",
+                serde_json::json!({ "files": { "../outside": "x" } }),
+                MergeStrategy::Replace,
+            )
+            .is_err());
+        assert!(!temp.path().join("outside").exists());
+        assert!(service.get_theme("Raw").unwrap().apps["files"]
+            .get("../outside")
+            .is_none());
+    }
+
+    #[test]
+    fn test_validate_theme_data_checks_known_apps_and_warns_on_unknown() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+
+        let errors = service.validate_theme_data(&serde_json::json!({
+            "alacritty": { "colors": { "primary": { "background": "not-a-color" } } },
+            "not_an_app": {},
+            "meta": { "tags": ["dark"] }
+        }));
+
+        let alacritty: Vec<&ValidationError> =
+            errors.iter().filter(|e| e.app == "alacritty").collect();
+        assert_eq!(alacritty.len(), 1);
+        assert_eq!(alacritty[0].field, "colors.primary.background");
+        assert_eq!(alacritty[0].severity, ValidationSeverity::Error);
+
+        let unknown: Vec<&ValidationError> =
+            errors.iter().filter(|e| e.app == "not_an_app").collect();
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].severity, ValidationSeverity::Warning);
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_preview_config_renders_without_writing() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+
+        let theme_data = serde_json::json!({
+            "alacritty": { "colors": { "primary": { "background": "rgb(30, 30, 46)" } } }
+        });
+        let content = service.preview_config("alacritty", &theme_data).unwrap();
+        assert!(content.contains("#1e1e2e"));
+
+        // Apps without data in the theme preview their defaults
+        assert!(service.preview_config("waybar", &theme_data).is_ok());
+        assert!(service.preview_config("not_an_app", &theme_data).is_err());
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_import_base16_creates_theme_with_mapped_palette() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        let yaml = r#"
+scheme: "Tomorrow Night"
+author: "Chris Kempson"
+base00: "1d1f21"
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666"
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"#;
+
+        let theme = service.import_base16("", yaml).unwrap();
+        assert_eq!(theme.name, "Tomorrow Night");
+        assert_eq!(theme.author.as_deref(), Some("Chris Kempson"));
+        let colors = theme.colors.unwrap();
+        assert_eq!(colors.primary.background, "#1d1f21");
+        assert_eq!(colors.primary.foreground, "#c5c8c6");
+        assert_eq!(colors.terminal.red, "#cc6666");
+        assert_eq!(colors.terminal.blue, "#81a2be");
+        assert!(temp.path().join("tomorrow-night/alacritty.toml").is_file());
+
+        let exported = service.export_base16("Tomorrow Night").unwrap();
+        assert!(exported.contains("scheme: \"Tomorrow Night\""));
+        assert!(exported.contains("author: \"Chris Kempson\""));
+        assert!(exported.contains("base08: \"cc6666\""));
+
+        assert!(matches!(
+            service.import_base16("Broken", "base00: 1d1f21"),
+            Err(ThemeError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_preflight_theme_reports_missing_pieces() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced(
+                "Preflight".to_string(),
+                serde_json::json!({
+                    "alacritty": {
+                        "colors": { "primary": { "background": "#101010", "foreground": "#e0e0e0" } }
+                    },
+                    "hyprland": {},
+                    "waybar": {},
+                    "gtk": {}
+                }),
+            )
+            .unwrap();
+        let status = |report: &PreflightReport, check: &str| {
+            report
+                .items
+                .iter()
+                .find(|item| item.check == check)
+                .map(|item| item.status)
+        };
+
+        let report = service.preflight_theme("Preflight").unwrap();
+        assert!(report.ok, "{:?}", report.items);
+        assert_eq!(report.theme, "preflight");
+        assert_eq!(
+            status(&report, "config:hyprland"),
+            Some(PreflightStatus::Pass)
         );
+        assert_eq!(status(&report, "images"), Some(PreflightStatus::Warn));
+
+        fs::remove_file(temp.path().join("preflight/gtk.css")).unwrap();
+        fs::remove_file(temp.path().join("preflight/hyprland.conf")).unwrap();
+        fs::create_dir_all(temp.path().join("preflight/backgrounds")).unwrap();
+        fs::write(temp.path().join("preflight/backgrounds/wall.png"), b"png").unwrap();
+        let report = service.preflight_theme("preflight").unwrap();
+        assert!(!report.ok);
+        assert_eq!(status(&report, "config:gtk"), Some(PreflightStatus::Warn));
         assert_eq!(
-            CustomThemeService::sanitize_name("Special@#$%Theme"),
-            "specialtheme"
+            status(&report, "config:hyprland"),
+            Some(PreflightStatus::Fail)
         );
+        assert_eq!(status(&report, "images"), Some(PreflightStatus::Pass));
+        assert_eq!(status(&report, "colors"), Some(PreflightStatus::Pass));
+
+        fs::write(temp.path().join("preflight/custom_theme.json"), "{").unwrap();
+        let report = service.preflight_theme("preflight").unwrap();
+        assert_eq!(status(&report, "metadata"), Some(PreflightStatus::Fail));
+
+        let report = service.preflight_theme("Missing").unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.items.len(), 1);
+        assert_eq!(status(&report, "directory"), Some(PreflightStatus::Fail));
     }
 
     #[test]
-    fn test_theme_creation() {
-        // Skip this test since it requires a real AppHandle
-        // which is not available in unit tests
+    fn test_read_theme_config_returns_generated_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced(
+                "Source".to_string(),
+                serde_json::json!({
+                    "alacritty": { "colors": { "primary": { "background": "#101010" } } },
+                    "waybar": {}
+                }),
+            )
+            .unwrap();
+
+        for app_name in ["alacritty", "waybar"] {
+            let generator = service.generator_registry.get_generator(app_name).unwrap();
+            let on_disk =
+                fs::read_to_string(temp.path().join("source").join(generator.get_file_name()))
+                    .unwrap();
+            assert_eq!(
+                service.read_theme_config("Source", app_name).unwrap(),
+                on_disk
+            );
+        }
+
+        for (name, app_name) in [
+            ("Source", "btop"),
+            ("Source", "nope"),
+            ("Missing", "alacritty"),
+        ] {
+            let err = service.read_theme_config(name, app_name).unwrap_err();
+            assert!(
+                matches!(err, ThemeError::NotFound(_)),
+                "{name}/{app_name}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_regenerate_configs_rebuilds_deleted_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced(
+                "Rebuild".to_string(),
+                serde_json::json!({
+                    "alacritty": { "colors": { "primary": { "background": "#101010" } } },
+                    "waybar": {}
+                }),
+            )
+            .unwrap();
+        let theme_dir = temp.path().join("rebuild");
+        fs::remove_file(theme_dir.join("alacritty.toml")).unwrap();
+        fs::remove_file(theme_dir.join("waybar.css")).unwrap();
+        let metadata_before = fs::read_to_string(theme_dir.join("custom_theme.json")).unwrap();
+
+        let report = service.regenerate_configs("Rebuild").unwrap();
+        assert_eq!(report.regenerated, ["alacritty", "waybar"]);
+        assert!(report.skipped.contains(&"btop".to_string()));
+        assert!(report.failed.is_empty());
+        assert!(!theme_dir.join("btop.theme").exists());
+
+        let alacritty = fs::read_to_string(theme_dir.join("alacritty.toml")).unwrap();
+        assert!(alacritty.contains("#101010"));
+        assert!(theme_dir.join("waybar.css").is_file());
+        assert_eq!(
+            fs::read_to_string(theme_dir.join("custom_theme.json")).unwrap(),
+            metadata_before
+        );
+
+        assert!(matches!(
+            service.regenerate_configs("Missing"),
+            Err(ThemeError::Io(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_set_theme_preview_overrides_name_order() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp.path().to_path_buf()).unwrap();
+        service
+            .create_theme_advanced("Pictured".to_string(), serde_json::json!({}))
+            .unwrap();
+        let theme_dir = temp.path().join("pictured");
+        fs::write(theme_dir.join("preview.png"), b"preview").unwrap();
+        fs::create_dir_all(theme_dir.join("backgrounds")).unwrap();
+        fs::write(theme_dir.join("backgrounds").join("wall.jpg"), b"wall").unwrap();
+
+        let loader = crate::services::themes::optimized_theme_loader::OptimizedThemeLoader::new();
+        let image_name = |theme: &crate::services::themes::get_sys_themes::SysTheme| {
+            Path::new(theme.image_path.as_deref().unwrap())
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        };
+        let before = loader.load_theme(&theme_dir).await.unwrap();
+        assert_eq!(image_name(&before), "preview.png");
+
+        let theme = service
+            .set_theme_preview("Pictured", Some("wall.jpg".to_string()))
+            .unwrap();
+        assert_eq!(theme.preview.as_deref(), Some("wall.jpg"));
+        let after = loader.load_theme(&theme_dir).await.unwrap();
+        assert_eq!(image_name(&after), "wall.jpg");
+        let raw = super::super::get_sys_themes::read_theme_image(&theme_dir, None).unwrap();
+        assert_eq!(raw.bytes, b"wall");
+
+        for missing in ["nope.png", "../preview.png", ""] {
+            assert!(matches!(
+                service.set_theme_preview("Pictured", Some(missing.to_string())),
+                Err(ThemeError::NotFound(_))
+            ));
+        }
+
+        service.set_theme_preview("Pictured", None).unwrap();
+        let reset = loader.load_theme(&theme_dir).await.unwrap();
+        assert_eq!(image_name(&reset), "preview.png");
     }
 }
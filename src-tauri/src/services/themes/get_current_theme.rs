@@ -1,6 +1,6 @@
 use regex::Regex;
 use serde::Serialize;
-use std::{fs, io, path::PathBuf};
+use std::{fs, io};
 
 #[derive(Debug, Serialize)]
 pub struct SystemColors {
@@ -10,8 +10,7 @@ pub struct SystemColors {
 
 #[tauri::command]
 pub fn get_system_theme_colors() -> Result<Option<SystemColors>, String> {
-    let home = std::env::var("HOME").map_err(|e| format!("HOME not set: {e}"))?;
-    let path = PathBuf::from(home).join(".config/omarchy/current/theme/waybar.css");
+    let path = super::themes_dir::config_dir()?.join("omarchy/current/theme/waybar.css");
 
     let content = match fs::read_to_string(&path) {
         Ok(s) => s,
@@ -1,11 +1,13 @@
 use super::color_extraction::ColorExtractor;
+use super::custom_themes::CustomThemeService;
 use super::optimized_theme_loader::{OptimizedThemeLoader, ThemeMetadata};
+use super::theme_cache::ThemeCache;
+use super::themes_dir::themes_dir;
 use crate::services::cache::cache_manager::get_theme_cache;
-use crate::types::ThemeColors;
-use dirs;
+use crate::types::{Page, RawImage, ThemeColors};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -14,19 +16,115 @@ pub struct SysTheme {
     pub title: String, // Display name for the theme
     pub description: String,
     pub image: String,
+    #[serde(default)]
+    pub image_path: Option<String>, // Absolute path of the file behind `image`
     pub is_system: bool,             // Indicates if this is a system theme
     pub is_custom: bool,             // Indicates if this is a custom theme
     pub colors: Option<ThemeColors>, // Extracted color palette from theme configuration
+    #[serde(default)]
+    pub is_dark: Option<bool>, // Whether the background is dark; None without colors
+    #[serde(default = "default_symlink_ok")]
+    pub symlink_ok: bool, // False when the theme directory is a symlink to a missing target
+    #[serde(default)]
+    pub modified_at: Option<String>, // RFC 3339 time of the last change, if known
+}
+
+fn default_symlink_ok() -> bool {
+    true
+}
+
+/// Orders offered by `get_sys_themes_sorted`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// Title, ignoring case
+    #[default]
+    Title,
+    /// Most recently modified first; themes without a timestamp go last
+    ModifiedDesc,
+    /// Dark themes, then light ones, then themes without colors
+    Mode,
+}
+
+/// A theme directory entry that is a symlink to a missing target
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrokenThemeLink {
+    pub dir: String,
+    pub target: String,
+    pub removed: bool,
 }
 
 /// Global instance of the optimized theme loader
 static THEME_LOADER: OnceLock<OptimizedThemeLoader> = OnceLock::new();
 
-/// Get or initialize the global theme loader instance
-fn get_theme_loader() -> &'static OptimizedThemeLoader {
+/// Get or initialize the global theme loader instance, whose color and image caches are
+/// shared by every scan
+pub(crate) fn get_theme_loader() -> &'static OptimizedThemeLoader {
     THEME_LOADER.get_or_init(OptimizedThemeLoader::new)
 }
 
+/// Derive the cache key for a theme (its directory name under `themes_dir`) from a name or dir
+pub fn theme_cache_key_in(themes_dir: &Path, name: &str) -> String {
+    if !name.is_empty() && !name.contains(['/', '\\']) && themes_dir.join(name).is_dir() {
+        return name.to_string();
+    }
+    CustomThemeService::sanitize_name(name).unwrap_or_else(|_| name.to_string())
+}
+
+/// Derive the cache key for a theme in the themes directory from a name or dir
+pub fn theme_cache_key(name: &str) -> String {
+    match themes_dir() {
+        Ok(themes_dir) => theme_cache_key_in(&themes_dir, name),
+        Err(_) => CustomThemeService::sanitize_name(name).unwrap_or_else(|_| name.to_string()),
+    }
+}
+
+/// Persist the global loader's extracted colors to `path`, or stop persisting with `None`
+pub async fn set_color_cache_persistence(path: Option<PathBuf>) {
+    get_theme_loader().set_color_cache_persistence(path).await;
+}
+
+/// Bound the global loader's in-memory color cache to `capacity` theme directories
+pub async fn set_color_cache_capacity(capacity: usize) {
+    get_theme_loader().set_color_cache_capacity(capacity).await;
+}
+
+/// Evict a theme's extracted colors from the global loader's color cache
+pub async fn invalidate_theme_colors(dir: &str) {
+    get_theme_loader().invalidate_theme_colors(dir).await;
+}
+
+/// Bring one theme's cache entry in line with disk without rescanning the others: the
+/// theme is reloaded when its directory exists and dropped when it is gone
+pub async fn reload_cached_theme(name: &str) {
+    reload_cached_theme_dir(&theme_cache_key(name)).await;
+}
+
+/// `reload_cached_theme` for an exact directory name in the themes directory, which may
+/// already be gone
+pub async fn reload_cached_theme_dir(key: &str) {
+    let Ok(cache) = get_theme_cache().await else {
+        return;
+    };
+    invalidate_theme_colors(key).await;
+
+    let theme_dir = themes_dir().ok().map(|themes_dir| themes_dir.join(key));
+    match theme_dir.filter(|dir| fs::symlink_metadata(dir).is_ok()) {
+        Some(theme_dir) => match get_theme_loader().load_theme(&theme_dir).await {
+            Ok(theme) => cache.update_cached_theme(theme).await,
+            Err(e) => {
+                log::warn!("Failed to reload theme '{key}': {e}");
+                cache.invalidate_theme(key).await;
+            },
+        },
+        None => cache.invalidate_theme(key).await,
+    }
+
+    if let Err(e) = cache.save_snapshot().await {
+        log::warn!("Failed to persist theme cache: {e}");
+    }
+}
+
 /// Scans the system themes directory and returns a list of themes with their info
 /// Includes color extraction for each discovered theme directory with performance optimizations
 /// This function now uses cache-first strategy with fallback to direct filesystem scanning
@@ -34,14 +132,12 @@ fn get_theme_loader() -> &'static OptimizedThemeLoader {
 pub async fn get_sys_themes() -> Result<Vec<SysTheme>, String> {
     // Try cache first if available
     if let Ok(cache) = get_theme_cache().await {
-        if cache.is_cache_valid().await && !cache.is_empty().await {
-            if let Ok(cached_themes) = cache.get_themes().await {
-                log::info!(
-                    "Returning {} themes from cache (get_sys_themes)",
-                    cached_themes.len()
-                );
-                return Ok(cached_themes);
-            }
+        if let Some(cached_themes) = cache.get_valid_themes().await {
+            log::info!(
+                "Returning {} themes from cache (get_sys_themes)",
+                cached_themes.len()
+            );
+            return Ok(cached_themes);
         }
     }
 
@@ -76,9 +172,18 @@ async fn get_sys_themes_direct() -> Result<Vec<SysTheme>, String> {
     Ok(themes)
 }
 
+/// Files `extract_theme_colors` reads colors from
+pub(crate) const COLOR_SOURCE_FILES: [&str; 5] = [
+    "custom_theme.json",
+    "alacritty.toml",
+    "kitty.conf",
+    "wezterm.lua",
+    "colors.lua",
+];
+
 /// Extract colors from theme configuration files with comprehensive error handling
 /// Returns None if no extractable colors are found, allowing graceful degradation
-fn extract_theme_colors(theme_dir: &Path, is_custom: bool) -> Option<ThemeColors> {
+pub(crate) fn extract_theme_colors(theme_dir: &Path, is_custom: bool) -> Option<ThemeColors> {
     // Performance optimization: Check file existence before attempting to read
     if is_custom {
         // For custom themes, try to extract from custom_theme.json
@@ -122,6 +227,32 @@ fn extract_theme_colors(theme_dir: &Path, is_custom: bool) -> Option<ThemeColors
         }
     }
 
+    // Then a kitty config, for themes that don't ship an Alacritty one
+    let kitty_config_path = theme_dir.join("kitty.conf");
+    if kitty_config_path.exists() {
+        match ColorExtractor::extract_from_kitty_config(&kitty_config_path) {
+            Some(colors) => return Some(colors),
+            None => {
+                log::warn!("Failed to extract colors from kitty config at {kitty_config_path:?}");
+            },
+        }
+    }
+
+    // Then a WezTerm color table
+    for file_name in ["wezterm.lua", "colors.lua"] {
+        let wezterm_config_path = theme_dir.join(file_name);
+        if wezterm_config_path.exists() {
+            match ColorExtractor::extract_from_wezterm_config(&wezterm_config_path) {
+                Some(colors) => return Some(colors),
+                None => {
+                    log::warn!(
+                        "Failed to extract colors from WezTerm config at {wezterm_config_path:?}"
+                    );
+                },
+            }
+        }
+    }
+
     // No extractable colors found - this is handled gracefully by returning None
     None
 }
@@ -158,32 +289,19 @@ fn generate_theme_from_directory(theme_dir: &Path) -> Result<SysTheme, String> {
     };
 
     // Look for any image files with common extensions
-    let mut image_path = String::new();
-
-    // Read all files in the theme directory and look for image files
-    if let Ok(entries) = fs::read_dir(theme_dir) {
-        for entry in entries.flatten() {
-            let file_path = entry.path();
-            // Check if it's a file (not a directory) and has an image extension
-            if file_path.is_file() {
-                if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
-                    let ext_lower = extension.to_lowercase();
-                    if matches!(
-                        ext_lower.as_str(),
-                        "png" | "jpg" | "jpeg" | "webp" | "gif" | "svg"
-                    ) {
-                        match convert_image_to_data_url(&file_path) {
-                            Ok(data_url) => {
-                                image_path = data_url;
-                                break;
-                            },
-                            Err(e) => {
-                                log::warn!("Failed to load image {file_path:?}: {e}");
-                            },
-                        }
-                    }
-                }
-            }
+    let mut image_data_url = String::new();
+    let mut image_path = None;
+
+    for file_path in preview_image_candidates(theme_dir) {
+        match convert_image_to_data_url(&file_path) {
+            Ok(data_url) => {
+                image_data_url = data_url;
+                image_path = Some(absolute_image_path(&file_path));
+                break;
+            },
+            Err(e) => {
+                log::warn!("Failed to load image {file_path:?}: {e}");
+            },
         }
     }
 
@@ -198,84 +316,220 @@ fn generate_theme_from_directory(theme_dir: &Path) -> Result<SysTheme, String> {
             .unwrap_or(false)
     };
 
+    // Following the link fails when a system theme's target has gone missing
+    let symlink_ok = fs::metadata(theme_dir).is_ok();
+
     // Extract colors from theme configuration
     let colors = extract_theme_colors(theme_dir, is_custom);
+    let is_dark = colors.as_ref().and_then(ColorExtractor::is_dark);
 
     Ok(SysTheme {
         dir: dir_name.to_string(),
         title,
         description: format!("Auto-generated theme from {dir_name}"),
-        image: image_path,
+        image: image_data_url,
+        image_path,
         is_system,
         is_custom,
         colors,
+        is_dark,
+        symlink_ok,
+        modified_at: theme_modified_at(theme_dir),
     })
 }
 
-/// Convert a local image file to a base64 data URL
-fn convert_image_to_data_url(image_path: &Path) -> Result<String, String> {
-    if !image_path.exists() {
-        return Err(format!("Image file does not exist: {image_path:?}"));
+/// Image files directly inside a theme directory in preview order: `preview.*`, then
+/// `background.*`, then the rest alphabetically
+pub(crate) fn preview_image_candidates(theme_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(theme_dir) else {
+        return Vec::new();
+    };
+    let chosen = chosen_preview_image(theme_dir);
+    let mut images: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        matches!(
+                            ext.to_lowercase().as_str(),
+                            "png" | "jpg" | "jpeg" | "webp" | "gif" | "svg"
+                        )
+                    })
+        })
+        .collect();
+
+    let rank = |path: &Path| {
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(str::to_lowercase);
+        match stem.as_deref() {
+            Some("preview") => 0,
+            Some("background") => 1,
+            _ => 2,
+        }
+    };
+    images.sort_by(|a, b| {
+        rank(a)
+            .cmp(&rank(b))
+            .then_with(|| a.file_name().cmp(&b.file_name()))
+    });
+
+    // An explicitly chosen preview goes ahead of the name-order heuristic
+    if let Some(chosen) = chosen {
+        images.retain(|path| *path != chosen);
+        images.insert(0, chosen);
     }
+    images
+}
 
-    let image_data = fs::read(image_path).map_err(|e| format!("Failed to read image file: {e}"))?;
+/// The image named by the `preview` field of a custom theme's metadata, looked up in the
+/// theme directory first and then in `backgrounds/`
+fn chosen_preview_image(theme_dir: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(theme_dir.join("custom_theme.json")).ok()?;
+    let metadata: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let filename = metadata.get("preview")?.as_str()?;
+    if filename.is_empty() || filename.contains(['/', '\\']) || filename == ".." {
+        return None;
+    }
+    [
+        theme_dir.join(filename),
+        theme_dir.join("backgrounds").join(filename),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+}
+
+/// Resolve an image file to an absolute path (following symlinks when possible)
+pub(crate) fn absolute_image_path(path: &Path) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
 
-    // Determine MIME type based on file extension
-    let mime_type = match image_path.extension().and_then(|ext| ext.to_str()) {
+/// MIME type of an image file, based on its extension
+fn image_mime_type(image_path: &Path) -> &'static str {
+    let extension = image_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    match extension.as_deref() {
         Some("png") => "image/png",
         Some("jpg") | Some("jpeg") => "image/jpeg",
         Some("gif") => "image/gif",
         Some("webp") => "image/webp",
+        Some("bmp") => "image/bmp",
         Some("svg") => "image/svg+xml",
         _ => "image/png", // Default to PNG
+    }
+}
+
+/// Read a theme's preview image, or `backgrounds/<background>` when given, as raw bytes
+pub fn read_theme_image(theme_dir: &Path, background: Option<&str>) -> Result<RawImage, String> {
+    let image_path = match background {
+        Some(filename) => {
+            if filename.is_empty() || filename.contains(['/', '\\']) || filename == ".." {
+                return Err(format!("Invalid background file name '{filename}'"));
+            }
+            theme_dir.join("backgrounds").join(filename)
+        },
+        None => preview_image_candidates(theme_dir)
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("Theme has no preview image: {theme_dir:?}"))?,
     };
 
-    let base64_data = base64_encode(&image_data);
-    Ok(format!("data:{mime_type};base64,{base64_data}"))
+    let bytes = fs::read(&image_path)
+        .map_err(|e| format!("Failed to read image file {}: {e}", image_path.display()))?;
+    Ok(RawImage {
+        bytes,
+        mime_type: image_mime_type(&image_path).to_string(),
+    })
 }
 
-/// Optimized base64 encoding function with pre-allocated capacity
-fn base64_encode(data: &[u8]) -> String {
-    if data.is_empty() {
-        return String::new();
+/// Convert a local image file to a base64 data URL
+fn convert_image_to_data_url(image_path: &Path) -> Result<String, String> {
+    if !image_path.exists() {
+        return Err(format!("Image file does not exist: {image_path:?}"));
     }
 
-    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-
-    // Pre-allocate with exact capacity to avoid reallocations
-    let output_len = data.len().div_ceil(3) * 4;
-    let mut result = String::with_capacity(output_len);
+    let mime_type = image_mime_type(image_path);
+    // SVG is text, so it embeds smaller URL-encoded than as base64
+    if mime_type == "image/svg+xml" {
+        return super::theme_preview::svg_data_url(image_path);
+    }
+    super::theme_preview::preview_data_url(image_path, mime_type)
+}
 
-    for chunk in data.chunks(3) {
-        let mut buf = [0u8; 3];
-        for (i, &byte) in chunk.iter().enumerate() {
-            buf[i] = byte;
-        }
+/// Order themes by title (ignoring case), then directory
+pub fn sort_by_title(themes: &mut [SysTheme]) {
+    themes.sort_by_cached_key(|theme| (theme.title.to_lowercase(), theme.dir.clone()));
+}
 
-        let b = ((buf[0] as u32) << 16) | ((buf[1] as u32) << 8) | (buf[2] as u32);
+/// When a theme was last modified, as RFC 3339: `modified_at` from `custom_theme.json`
+/// for custom themes, the directory's mtime otherwise
+pub(crate) fn theme_modified_at(theme_dir: &Path) -> Option<String> {
+    let recorded = fs::read_to_string(theme_dir.join("custom_theme.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|theme| Some(theme.get("modified_at")?.as_str()?.to_string()))
+        .filter(|modified_at| !modified_at.is_empty());
+    recorded.or_else(|| {
+        let modified = fs::metadata(theme_dir).and_then(|m| m.modified()).ok()?;
+        Some(chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339())
+    })
+}
 
-        result.push(CHARS[((b >> 18) & 63) as usize] as char);
-        result.push(CHARS[((b >> 12) & 63) as usize] as char);
-        result.push(if chunk.len() > 1 {
-            CHARS[((b >> 6) & 63) as usize] as char
-        } else {
-            '='
-        });
-        result.push(if chunk.len() > 2 {
-            CHARS[(b & 63) as usize] as char
-        } else {
-            '='
-        });
+/// Sort themes by `sort_by`, falling back to title order for ties
+pub fn sort_sys_themes(themes: &mut [SysTheme], sort_by: SortKey) {
+    sort_by_title(themes);
+    match sort_by {
+        SortKey::Title => {},
+        SortKey::ModifiedDesc => themes.sort_by_cached_key(|theme| {
+            std::cmp::Reverse(
+                theme
+                    .modified_at
+                    .as_deref()
+                    .and_then(|modified_at| chrono::DateTime::parse_from_rfc3339(modified_at).ok()),
+            )
+        }),
+        SortKey::Mode => themes.sort_by_key(|theme| match theme.is_dark {
+            Some(true) => 0,
+            Some(false) => 1,
+            None => 2,
+        }),
     }
+}
+
+/// Order themes by title and take one page
+fn paginate_sys_themes(mut themes: Vec<SysTheme>, offset: usize, limit: usize) -> Page<SysTheme> {
+    sort_by_title(&mut themes);
+    Page::from_sorted(themes, offset, limit)
+}
 
-    result
+/// Get all themes in the requested order
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_sys_themes_sorted(sort_by: SortKey) -> Result<Vec<SysTheme>, String> {
+    let mut themes = get_sys_themes().await?;
+    sort_sys_themes(&mut themes, sort_by);
+    Ok(themes)
+}
+
+/// Get one page of themes in a stable order, served from the cache when it is valid
+#[tauri::command]
+pub async fn get_sys_themes_paged(offset: usize, limit: usize) -> Result<Page<SysTheme>, String> {
+    Ok(paginate_sys_themes(get_sys_themes().await?, offset, limit))
 }
 
 /// Get a specific system theme by folder name
 #[tauri::command]
 pub async fn get_sys_theme_by_name(theme_name: String) -> Result<Option<SysTheme>, String> {
-    let home_dir = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
-    let theme_path = home_dir.join(".config/omarchy/themes").join(&theme_name);
+    let theme_path = themes_dir()?.join(&theme_name);
 
     if !theme_path.exists() || !theme_path.is_dir() {
         return Ok(None);
@@ -287,25 +541,112 @@ pub async fn get_sys_theme_by_name(theme_name: String) -> Result<Option<SysTheme
     }
 }
 
+/// Pick the theme a loose query refers to: an exact directory match, then a directory
+/// matching the sanitized query, then a case-insensitive title match
+fn match_theme(themes: Vec<SysTheme>, query: &str) -> Option<SysTheme> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+    let sanitized = CustomThemeService::sanitize_name(query).ok();
+    let title = query.to_lowercase();
+
+    let mut by_sanitized = None;
+    let mut by_title = None;
+    for theme in themes {
+        if theme.dir == query {
+            return Some(theme);
+        }
+        if by_sanitized.is_none() && sanitized.as_deref() == Some(theme.dir.as_str()) {
+            by_sanitized = Some(theme);
+        } else if by_title.is_none() && theme.title.trim().to_lowercase() == title {
+            by_title = Some(theme);
+        }
+    }
+    by_sanitized.or(by_title)
+}
+
+/// Get a theme by folder name, sanitized name or title, unlike the exact
+/// `get_sys_theme_by_name`
+#[tauri::command]
+pub async fn find_theme(query: String) -> Result<Option<SysTheme>, String> {
+    Ok(match_theme(get_sys_themes().await?, &query))
+}
+
+/// Find entries in `themes_dir` that are symlinks to missing targets, removing them when
+/// `remove` is set
+pub fn find_broken_theme_links(themes_dir: &Path, remove: bool) -> Vec<BrokenThemeLink> {
+    let Ok(entries) = fs::read_dir(themes_dir) else {
+        return Vec::new();
+    };
+
+    let mut broken: Vec<BrokenThemeLink> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_symlink() && fs::metadata(entry.path()).is_err())
+        .map(|entry| {
+            let path = entry.path();
+            let removed = remove
+                && fs::remove_file(&path)
+                    .inspect_err(|e| log::warn!("Failed to remove broken link {path:?}: {e}"))
+                    .is_ok();
+            BrokenThemeLink {
+                dir: entry.file_name().to_string_lossy().into_owned(),
+                target: fs::read_link(&path)
+                    .map(|target| target.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                removed,
+            }
+        })
+        .collect();
+    broken.sort_by(|a, b| a.dir.cmp(&b.dir));
+    broken
+}
+
+/// Report theme symlinks whose targets are missing; with `remove`, also delete those links
+#[tauri::command]
+pub async fn repair_theme_symlinks(remove: Option<bool>) -> Result<Vec<BrokenThemeLink>, String> {
+    let themes_dir = themes_dir()?;
+    let broken = find_broken_theme_links(&themes_dir, remove.unwrap_or(false));
+
+    if broken.iter().any(|link| link.removed) {
+        for link in broken.iter().filter(|link| link.removed) {
+            reload_cached_theme(&link.dir).await;
+        }
+    }
+
+    Ok(broken)
+}
+
+/// Scan an additional directory for themes without importing them
+#[tauri::command]
+pub async fn scan_external_themes_dir(path: String) -> Result<Vec<SysTheme>, String> {
+    let root = Path::new(&path);
+
+    if !root.is_absolute() {
+        return Err(format!("Themes directory must be an absolute path: {path}"));
+    }
+    if !root.is_dir() {
+        return Err(format!("Themes directory does not exist: {path}"));
+    }
+
+    // Use a separate loader so external themes never share the main color cache
+    let loader = OptimizedThemeLoader::new();
+    let themes = loader.load_themes_from_dir(root).await?;
+
+    log::info!("Found {} themes in external directory {path}", themes.len());
+    Ok(themes)
+}
+
 /// Get system themes using cache-first strategy with fallback to direct loading
 #[tauri::command]
 pub async fn get_themes_cached() -> Result<Vec<SysTheme>, String> {
     // Try to get themes from cache first
     match get_theme_cache().await {
         Ok(cache) => {
-            // Check if cache is valid and has themes
-            if cache.is_cache_valid().await && !cache.is_empty().await {
-                // Return cached themes
-                match cache.get_themes().await {
-                    Ok(themes) => {
-                        log::info!("Returning {} themes from cache", themes.len());
-                        return Ok(themes);
-                    },
-                    Err(e) => {
-                        log::warn!("Failed to get themes from cache: {e}");
-                        // Continue to fallback
-                    },
-                }
+            // Return cached themes if the cache is valid and has themes
+            if let Some(themes) = cache.get_valid_themes().await {
+                log::info!("Returning {} themes from cache", themes.len());
+                return Ok(themes);
             }
 
             // Cache is invalid or empty, load themes and cache them
@@ -420,13 +761,79 @@ pub async fn refresh_theme_cache() -> Result<Vec<SysTheme>, String> {
     }
 }
 
+/// Clear color cache, invalidate the theme cache and rescan a themes directory, in that order
+async fn reindex_themes_dir(
+    loader: &OptimizedThemeLoader,
+    cache: Option<&ThemeCache>,
+    themes_dir: &Path,
+) -> Result<Vec<SysTheme>, String> {
+    loader.clear_cache().await;
+
+    if let Some(cache) = cache {
+        cache.invalidate().await;
+    }
+
+    let themes = loader.load_themes_from_dir(themes_dir).await?;
+
+    if let Some(cache) = cache {
+        cache.cache_themes(themes.clone(), false).await?;
+    }
+
+    log::info!("Reindexed {} themes from {themes_dir:?}", themes.len());
+    Ok(themes)
+}
+
+/// Catch up with theme changes made on disk outside the app
+#[tauri::command]
+pub async fn reindex_cache() -> Result<Vec<SysTheme>, String> {
+    log::info!("Reindexing theme cache");
+
+    let themes_dir = themes_dir()?;
+    if !themes_dir.exists() {
+        return Err(format!("Themes directory does not exist: {themes_dir:?}"));
+    }
+
+    let cache = get_theme_cache().await.ok();
+    reindex_themes_dir(get_theme_loader(), cache.as_deref(), &themes_dir).await
+}
+
+/// Get a theme's preview image (or one of its backgrounds) as raw bytes with its MIME type
+#[tauri::command]
+pub async fn get_theme_image_raw(
+    dir: String,
+    background: Option<String>,
+) -> Result<RawImage, String> {
+    let themes_dir = themes_dir()?;
+    let dir = theme_cache_key_in(&themes_dir, &dir);
+    if dir.is_empty() || dir.contains(['/', '\\']) {
+        return Err(format!("Invalid theme directory '{dir}'"));
+    }
+
+    read_theme_image(&themes_dir.join(dir), background.as_deref())
+}
+
 /// Get lightweight theme metadata for faster initial responses
 #[tauri::command]
 pub async fn get_theme_metadata() -> Result<Vec<ThemeMetadata>, String> {
     log::info!("Loading theme metadata");
 
+    let cache = get_theme_cache().await.ok();
+    if let Some(cache) = &cache {
+        if let Some(metadata) = cache.get_metadata().await {
+            log::debug!(
+                "Serving {} theme metadata entries from cache",
+                metadata.len()
+            );
+            return Ok(metadata);
+        }
+    }
+
     let theme_loader = get_theme_loader();
-    theme_loader.load_theme_metadata_only().await
+    let metadata = theme_loader.load_theme_metadata_only().await?;
+    if let Some(cache) = cache {
+        cache.cache_metadata(metadata.clone()).await;
+    }
+    Ok(metadata)
 }
 
 /// Clear color extraction cache
@@ -444,13 +851,22 @@ pub async fn clear_color_cache() -> Result<(), String> {
 #[tauri::command]
 pub async fn get_cache_stats() -> Result<serde_json::Value, String> {
     let theme_loader = get_theme_loader();
-    let (color_cache_size,) = theme_loader.get_cache_stats().await;
+    let (color_cache_size, color_cache_hits, color_cache_misses) =
+        theme_loader.get_cache_stats().await;
 
     let mut stats = serde_json::Map::new();
     stats.insert(
         "color_cache_size".to_string(),
         serde_json::Value::Number(color_cache_size.into()),
     );
+    stats.insert(
+        "color_cache_hits".to_string(),
+        serde_json::Value::Number(color_cache_hits.into()),
+    );
+    stats.insert(
+        "color_cache_misses".to_string(),
+        serde_json::Value::Number(color_cache_misses.into()),
+    );
 
     // Add theme cache stats if available
     if let Ok(cache) = get_theme_cache().await {
@@ -464,11 +880,30 @@ pub async fn get_cache_stats() -> Result<serde_json::Value, String> {
             "theme_cache_valid".to_string(),
             serde_json::Value::Bool(is_valid),
         );
+        let (theme_cache_hits, theme_cache_misses) = cache.hit_counts();
+        stats.insert(
+            "theme_cache_hits".to_string(),
+            serde_json::Value::Number(theme_cache_hits.into()),
+        );
+        stats.insert(
+            "theme_cache_misses".to_string(),
+            serde_json::Value::Number(theme_cache_misses.into()),
+        );
     }
 
     Ok(serde_json::Value::Object(stats))
 }
 
+/// Zero the theme and color cache hit/miss counters reported by `get_cache_stats`
+#[tauri::command]
+pub async fn reset_cache_stats() -> Result<(), String> {
+    get_theme_loader().reset_cache_stats();
+    if let Ok(cache) = get_theme_cache().await {
+        cache.reset_hit_counts();
+    }
+    Ok(())
+}
+
 /// Invalidate cache for a specific theme
 #[tauri::command]
 pub async fn invalidate_theme_cache(theme_dir: String) -> Result<(), String> {
@@ -528,10 +963,175 @@ pub async fn invalidate_and_refresh_cache() -> Result<Vec<SysTheme>, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::util::base64;
     use serde_json::json;
     use std::fs;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn test_image_path_points_at_real_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let theme_dir = temp_dir.path().join("pictured");
+        fs::create_dir(&theme_dir).unwrap();
+        let image_file = theme_dir.join("preview.png");
+        fs::write(&image_file, b"fake png data").unwrap();
+
+        let expected = fs::canonicalize(&image_file).unwrap();
+
+        let theme = generate_theme_from_directory(&theme_dir).unwrap();
+        assert!(theme.image.starts_with("data:image/png;base64,"));
+        assert_eq!(
+            theme.image_path.as_deref().map(Path::new),
+            Some(expected.as_path())
+        );
+
+        let loader = OptimizedThemeLoader::new();
+        let themes = loader.load_themes_from_dir(temp_dir.path()).await.unwrap();
+        assert_eq!(
+            themes[0].image_path.as_deref().map(Path::new),
+            Some(expected.as_path())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preview_image_prefers_conventional_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let theme_dir = temp_dir.path().join("pictured");
+        fs::create_dir(&theme_dir).unwrap();
+        fs::write(theme_dir.join("aaa.jpg"), b"aaa").unwrap();
+        fs::write(theme_dir.join("background.webp"), b"background").unwrap();
+        fs::write(theme_dir.join("preview.png"), b"preview").unwrap();
+
+        let names = |dir: &Path| -> Vec<String> {
+            preview_image_candidates(dir)
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+                .collect()
+        };
+        assert_eq!(
+            names(&theme_dir),
+            ["preview.png", "background.webp", "aaa.jpg"]
+        );
+
+        let expected = fs::canonicalize(theme_dir.join("preview.png")).unwrap();
+        let theme = generate_theme_from_directory(&theme_dir).unwrap();
+        assert_eq!(
+            theme.image_path.as_deref().map(Path::new),
+            Some(expected.as_path())
+        );
+        let loader = OptimizedThemeLoader::new();
+        let loaded = loader.load_theme(&theme_dir).await.unwrap();
+        assert_eq!(loaded.image_path, theme.image_path);
+        assert_eq!(loaded.image, theme.image);
+
+        fs::remove_file(theme_dir.join("preview.png")).unwrap();
+        assert_eq!(names(&theme_dir), ["background.webp", "aaa.jpg"]);
+    }
+
+    #[tokio::test]
+    async fn test_svg_preview_is_url_encoded_without_scripts() {
+        let temp_dir = TempDir::new().unwrap();
+        let theme_dir = temp_dir.path().join("vector");
+        fs::create_dir(&theme_dir).unwrap();
+        fs::write(
+            theme_dir.join("preview.svg"),
+            r##"<svg xmlns="http://www.w3.org/2000/svg"><script>alert(1)</script><rect fill="#1e1e2e" width="10" height="10"/><SCRIPT src="x.js"/></svg>"##,
+        )
+        .unwrap();
+
+        let theme = generate_theme_from_directory(&theme_dir).unwrap();
+        let encoded = theme.image.strip_prefix("data:image/svg+xml,").unwrap();
+        assert!(!theme.image.contains(";base64,"));
+        assert!(encoded.contains("fill=%22%231e1e2e%22"));
+        assert!(!encoded.to_lowercase().contains("script"));
+        assert!(!encoded.contains(['<', '>', '"', '#', ' ']));
+
+        let loader = OptimizedThemeLoader::new();
+        let loaded = loader.load_theme(&theme_dir).await.unwrap();
+        assert_eq!(loaded.image, theme.image);
+    }
+
+    #[test]
+    fn test_oversized_preview_image_is_downscaled() {
+        let temp_dir = TempDir::new().unwrap();
+        let theme_dir = temp_dir.path().join("huge");
+        fs::create_dir(&theme_dir).unwrap();
+        // An uncompressed bitmap just over the inline size limit; decoding sniffs the
+        // format from the content, so the extension only has to mark it as an image
+        let image_file = theme_dir.join("preview.jpg");
+        image::RgbImage::from_pixel(1700, 1700, image::Rgb([200, 40, 90]))
+            .save_with_format(&image_file, image::ImageFormat::Bmp)
+            .unwrap();
+        assert!(
+            fs::metadata(&image_file).unwrap().len()
+                > super::super::theme_preview::MAX_DATA_URL_IMAGE_BYTES
+        );
+
+        let theme = generate_theme_from_directory(&theme_dir).unwrap();
+        let encoded = theme.image.strip_prefix("data:image/jpeg;base64,").unwrap();
+        let decoded = image::load_from_memory(&base64::decode(encoded).unwrap()).unwrap();
+        assert!(decoded.width() <= 1280 && decoded.height() <= 1280);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_picks_up_new_theme_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("first")).unwrap();
+
+        let loader = OptimizedThemeLoader::new();
+        let cache = ThemeCache::new();
+
+        let themes = reindex_themes_dir(&loader, Some(&cache), temp_dir.path())
+            .await
+            .unwrap();
+        assert_eq!(themes.len(), 1);
+
+        // A theme added on disk shows up after reindexing
+        fs::create_dir(temp_dir.path().join("second")).unwrap();
+        let themes = reindex_themes_dir(&loader, Some(&cache), temp_dir.path())
+            .await
+            .unwrap();
+        assert_eq!(themes.len(), 2);
+        assert!(cache.get_theme("second").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_scan_external_themes_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["ocean-blue", "forest"] {
+            let theme_dir = temp_dir.path().join(name);
+            fs::create_dir(&theme_dir).unwrap();
+            fs::write(
+                theme_dir.join("alacritty.toml"),
+                "[colors.primary]\nbackground = \"#000000\"\nforeground = \"#ffffff\"\n",
+            )
+            .unwrap();
+        }
+        fs::write(temp_dir.path().join("README.md"), "not a theme").unwrap();
+
+        let mut themes = scan_external_themes_dir(temp_dir.path().to_string_lossy().to_string())
+            .await
+            .unwrap();
+        themes.sort_by(|a, b| a.dir.cmp(&b.dir));
+
+        assert_eq!(themes.len(), 2);
+        assert_eq!(themes[0].dir, "forest");
+        assert_eq!(themes[1].title, "Ocean Blue");
+        assert!(!themes[1].is_custom);
+    }
+
+    #[tokio::test]
+    async fn test_scan_external_themes_dir_rejects_invalid_paths() {
+        assert!(scan_external_themes_dir("relative/themes".to_string())
+            .await
+            .is_err());
+        assert!(
+            scan_external_themes_dir("/nonexistent/omarchist/themes".to_string())
+                .await
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_generate_theme_from_directory_with_custom_theme() {
         // Create a temporary directory structure
@@ -734,4 +1334,290 @@ mod tests {
         let colors = extract_theme_colors(&non_existent_dir, true);
         assert!(colors.is_none());
     }
+
+    #[test]
+    fn test_read_theme_image_returns_source_bytes() {
+        let temp_dir = TempDir::new().unwrap();
+        let theme_dir = temp_dir.path().join("raw");
+        fs::create_dir_all(theme_dir.join("backgrounds")).unwrap();
+        fs::write(theme_dir.join("preview.png"), b"png bytes").unwrap();
+        fs::write(theme_dir.join("backgrounds/wall.JPG"), b"jpeg bytes").unwrap();
+
+        let preview = read_theme_image(&theme_dir, None).unwrap();
+        assert_eq!(preview.bytes, b"png bytes");
+        assert_eq!(preview.mime_type, "image/png");
+
+        let background = read_theme_image(&theme_dir, Some("wall.JPG")).unwrap();
+        assert_eq!(background.bytes, b"jpeg bytes");
+        assert_eq!(background.mime_type, "image/jpeg");
+
+        assert!(read_theme_image(&theme_dir, Some("../preview.png")).is_err());
+    }
+
+    #[test]
+    fn test_theme_cache_key_uses_dir_name() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("Tokyo-Night")).unwrap();
+
+        assert_eq!(
+            theme_cache_key_in(temp_dir.path(), "Tokyo-Night"),
+            "Tokyo-Night"
+        );
+        assert_eq!(theme_cache_key_in(temp_dir.path(), "My Theme!"), "my-theme");
+    }
+
+    #[tokio::test]
+    async fn test_update_evicts_color_cache_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = CustomThemeService::with_themes_dir(temp_dir.path().to_path_buf()).unwrap();
+        let theme_data = json!({
+            "alacritty": {
+                "colors": {
+                    "primary": { "background": "#101010", "foreground": "#f0f0f0" }
+                }
+            }
+        });
+        service
+            .create_theme_advanced("Evict Me Theme".to_string(), theme_data.clone())
+            .unwrap();
+
+        let loader = get_theme_loader();
+        loader.load_themes_from_dir(temp_dir.path()).await.unwrap();
+        assert!(loader.has_cached_colors("evict-me-theme").await);
+
+        service
+            .update_theme_advanced(
+                "Evict Me Theme",
+                theme_data,
+                crate::types::MergeStrategy::Replace,
+            )
+            .unwrap();
+        ThemeCache::new().invalidate_theme("Evict Me Theme").await;
+
+        assert!(!loader.has_cached_colors("evict-me-theme").await);
+    }
+
+    #[tokio::test]
+    async fn test_is_dark_follows_background_luminance() {
+        let temp_dir = TempDir::new().unwrap();
+        let palette = |background: &str| {
+            format!(
+                r##"[colors.primary]
+background = "{background}"
+foreground = "#808080"
+
+[colors.normal]
+red = "#cc0000"
+green = "#00cc00"
+yellow = "#cccc00"
+blue = "#0000cc"
+magenta = "#cc00cc"
+cyan = "#00cccc"
+"##
+            )
+        };
+        for (dir, background) in [("night", "#0a0a0c"), ("paper", "#fafaf7")] {
+            let theme_dir = temp_dir.path().join(dir);
+            fs::create_dir(&theme_dir).unwrap();
+            fs::write(theme_dir.join("alacritty.toml"), palette(background)).unwrap();
+        }
+        fs::create_dir(temp_dir.path().join("colorless")).unwrap();
+
+        let expected = [
+            ("colorless", None),
+            ("night", Some(true)),
+            ("paper", Some(false)),
+        ];
+        for (dir, is_dark) in expected {
+            let theme = generate_theme_from_directory(&temp_dir.path().join(dir)).unwrap();
+            assert_eq!(theme.is_dark, is_dark, "{dir}");
+        }
+
+        let loader = OptimizedThemeLoader::new();
+        let mut themes = loader.load_themes_from_dir(temp_dir.path()).await.unwrap();
+        themes.sort_by(|a, b| a.dir.cmp(&b.dir));
+        let loaded: Vec<(&str, Option<bool>)> =
+            themes.iter().map(|t| (t.dir.as_str(), t.is_dark)).collect();
+        assert_eq!(loaded, expected);
+    }
+
+    #[tokio::test]
+    async fn test_modified_at_matches_between_loaders() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("custom")).unwrap();
+        fs::write(
+            temp_dir.path().join("custom/custom_theme.json"),
+            r#"{"name": "Custom", "modified_at": "2024-05-01T12:00:00+00:00"}"#,
+        )
+        .unwrap();
+        fs::create_dir(temp_dir.path().join("plain")).unwrap();
+
+        let custom = generate_theme_from_directory(&temp_dir.path().join("custom")).unwrap();
+        assert_eq!(
+            custom.modified_at.as_deref(),
+            Some("2024-05-01T12:00:00+00:00")
+        );
+        let plain = generate_theme_from_directory(&temp_dir.path().join("plain")).unwrap();
+        let plain_modified = plain.modified_at.expect("directory mtime");
+        assert!(chrono::DateTime::parse_from_rfc3339(&plain_modified).is_ok());
+
+        let loader = OptimizedThemeLoader::new();
+        let themes = loader.load_themes_from_dir(temp_dir.path()).await.unwrap();
+        let loaded: Vec<(&str, Option<&str>)> = themes
+            .iter()
+            .map(|t| (t.dir.as_str(), t.modified_at.as_deref()))
+            .collect();
+        assert_eq!(
+            loaded,
+            [
+                ("custom", Some("2024-05-01T12:00:00+00:00")),
+                ("plain", Some(plain_modified.as_str()))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dangling_theme_links_are_flagged_and_removable() {
+        let temp_dir = TempDir::new().unwrap();
+        let install_dir = temp_dir.path().join("install");
+        let themes_dir = temp_dir.path().join("themes");
+        fs::create_dir_all(install_dir.join("nord")).unwrap();
+        fs::create_dir_all(&themes_dir).unwrap();
+        std::os::unix::fs::symlink(install_dir.join("nord"), themes_dir.join("nord")).unwrap();
+        std::os::unix::fs::symlink(install_dir.join("gone"), themes_dir.join("gone")).unwrap();
+
+        let gone = generate_theme_from_directory(&themes_dir.join("gone")).unwrap();
+        assert!(gone.is_system && !gone.symlink_ok && gone.colors.is_none());
+        assert!(
+            generate_theme_from_directory(&themes_dir.join("nord"))
+                .unwrap()
+                .symlink_ok
+        );
+
+        let loader = OptimizedThemeLoader::new();
+        let mut themes = loader.load_themes_from_dir(&themes_dir).await.unwrap();
+        themes.sort_by(|a, b| a.dir.cmp(&b.dir));
+        let flags: Vec<(&str, bool)> = themes
+            .iter()
+            .map(|t| (t.dir.as_str(), t.symlink_ok))
+            .collect();
+        assert_eq!(flags, [("gone", false), ("nord", true)]);
+
+        let reported = find_broken_theme_links(&themes_dir, false);
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].dir, "gone");
+        assert!(!reported[0].removed);
+        assert!(themes_dir.join("gone").is_symlink());
+
+        assert!(find_broken_theme_links(&themes_dir, true)[0].removed);
+        assert!(!themes_dir.join("gone").is_symlink());
+        assert!(find_broken_theme_links(&themes_dir, false).is_empty());
+    }
+
+    #[test]
+    fn test_paginate_sys_themes_orders_by_title() {
+        let theme = |dir: &str, title: &str| SysTheme {
+            dir: dir.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            image: String::new(),
+            image_path: None,
+            is_system: false,
+            is_custom: false,
+            colors: None,
+            is_dark: None,
+            symlink_ok: true,
+            modified_at: None,
+        };
+        let themes = vec![
+            theme("nord", "Nord"),
+            theme("ash", "ash"),
+            theme("tokyo-night", "Tokyo Night"),
+            theme("catppuccin", "Catppuccin"),
+        ];
+
+        let page = paginate_sys_themes(themes.clone(), 0, 2);
+        assert_eq!(page.total, 4);
+        let dirs: Vec<&str> = page.items.iter().map(|t| t.dir.as_str()).collect();
+        assert_eq!(dirs, ["ash", "catppuccin"]);
+
+        let page = paginate_sys_themes(themes.into_iter().rev().collect(), 2, 2);
+        let dirs: Vec<&str> = page.items.iter().map(|t| t.dir.as_str()).collect();
+        assert_eq!(dirs, ["nord", "tokyo-night"]);
+    }
+
+    #[test]
+    fn test_match_theme_prefers_dir_then_sanitized_name_then_title() {
+        let theme = |dir: &str, title: &str| SysTheme {
+            dir: dir.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            image: String::new(),
+            image_path: None,
+            is_system: false,
+            is_custom: false,
+            colors: None,
+            is_dark: None,
+            symlink_ok: true,
+            modified_at: None,
+        };
+        let themes = vec![
+            theme("Nord", "Arctic"),
+            theme("nord", "Nord Light"),
+            theme("tokyo-night", "Tokyo Night"),
+            theme("matte", "Matte Black"),
+            theme("matte-black", "Graphite"),
+        ];
+        let found = |query: &str| match_theme(themes.clone(), query).map(|t| t.dir);
+
+        assert_eq!(found("matte").as_deref(), Some("matte"));
+        assert_eq!(found("Nord").as_deref(), Some("Nord"));
+        assert_eq!(found(" nord ").as_deref(), Some("nord"));
+        assert_eq!(found("NORD").as_deref(), Some("nord"));
+        assert_eq!(found("Tokyo Night").as_deref(), Some("tokyo-night"));
+        // A sanitized directory match beats another theme's title
+        assert_eq!(found("Matte Black").as_deref(), Some("matte-black"));
+        assert_eq!(found("arctic").as_deref(), Some("Nord"));
+        assert_eq!(found("GRAPHITE").as_deref(), Some("matte-black"));
+        assert_eq!(found("solarized"), None);
+        assert_eq!(found("  "), None);
+    }
+
+    #[test]
+    fn test_sort_sys_themes_title_is_case_insensitive() {
+        let theme = |dir: &str, title: &str, is_dark: Option<bool>| SysTheme {
+            dir: dir.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            image: String::new(),
+            image_path: None,
+            is_system: false,
+            is_custom: false,
+            colors: None,
+            is_dark,
+            symlink_ok: true,
+            modified_at: None,
+        };
+        let mut themes = vec![
+            theme("rose-pine", "rose pine", Some(false)),
+            theme("nord", "Nord", Some(true)),
+            theme("ash", "ASH", None),
+            theme("matte", "matte black", Some(true)),
+        ];
+        let dirs =
+            |themes: &[SysTheme]| -> Vec<String> { themes.iter().map(|t| t.dir.clone()).collect() };
+
+        sort_sys_themes(&mut themes, SortKey::Title);
+        assert_eq!(dirs(&themes), ["ash", "matte", "nord", "rose-pine"]);
+
+        sort_sys_themes(&mut themes, SortKey::Mode);
+        assert_eq!(dirs(&themes), ["matte", "nord", "rose-pine", "ash"]);
+
+        // Unparseable or missing timestamps go last, in title order
+        themes[0].modified_at = Some("2024-01-01T00:00:00Z".to_string());
+        themes[1].modified_at = Some("2024-06-01T02:00:00+02:00".to_string());
+        themes[2].modified_at = Some("not a date".to_string());
+        sort_sys_themes(&mut themes, SortKey::ModifiedDesc);
+        assert_eq!(dirs(&themes), ["nord", "matte", "ash", "rose-pine"]);
+    }
 }
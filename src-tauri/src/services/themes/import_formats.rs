@@ -0,0 +1,54 @@
+use crate::types::{ImportFormat, ImportInput};
+
+fn format(id: &str, label: &str, input: ImportInput, extensions: &[&str]) -> ImportFormat {
+    ImportFormat {
+        id: id.to_string(),
+        label: label.to_string(),
+        input,
+        extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+    }
+}
+
+/// Import formats available in this build, in the order the UI should offer them
+pub fn supported_import_formats() -> Vec<ImportFormat> {
+    vec![
+        format("json", "Omarchist theme JSON", ImportInput::File, &["json"]),
+        format("json_text", "Pasted theme JSON", ImportInput::Text, &[]),
+        format(
+            "zip",
+            "Omarchist theme archive",
+            ImportInput::File,
+            &["zip"],
+        ),
+        format(
+            "base16",
+            "Base16/Base24 scheme",
+            ImportInput::Text,
+            &["yaml", "yml"],
+        ),
+    ]
+}
+
+#[tauri::command]
+pub async fn get_supported_import_formats() -> Result<Vec<ImportFormat>, String> {
+    Ok(supported_import_formats())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_always_available_formats_are_listed() {
+        let formats = supported_import_formats();
+        let json = formats.iter().find(|f| f.id == "json").unwrap();
+        assert_eq!(json.input, ImportInput::File);
+        assert_eq!(json.extensions, vec!["json".to_string()]);
+        assert!(formats
+            .iter()
+            .any(|f| f.id == "json_text" && f.input == ImportInput::Text));
+
+        let serialized = serde_json::to_value(json).unwrap();
+        assert_eq!(serialized["input"], "file");
+    }
+}
@@ -1,11 +1,23 @@
 // Theme-related services
+pub mod active_theme;
+pub mod base16;
+pub mod color;
 pub mod color_extraction;
 pub mod custom_themes;
 pub mod get_current_theme;
 pub mod get_sys_themes;
 pub mod get_themes;
+pub mod import_formats;
 pub mod optimized_theme_loader;
+pub mod recents;
+pub mod theme_archive;
 pub mod theme_cache;
+pub mod theme_diff;
+pub mod theme_lint;
+pub mod theme_preview;
+pub mod theme_size;
+pub mod theme_watcher;
+pub mod themes_dir;
 
 // Re-export commonly used types
 pub use color_extraction::ColorExtractor;
@@ -1,7 +1,9 @@
 use super::color_extraction::ColorExtractor;
-use super::get_sys_themes::SysTheme;
+use super::get_sys_themes::{
+    extract_theme_colors, sort_by_title, theme_modified_at, SysTheme, COLOR_SOURCE_FILES,
+};
+use super::theme_cache::{CacheConfig, HitCounters};
 use crate::types::ThemeColors;
-use dirs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -21,10 +23,95 @@ pub struct ThemeMetadata {
     pub has_image: bool,
 }
 
+impl From<&SysTheme> for ThemeMetadata {
+    fn from(theme: &SysTheme) -> Self {
+        Self {
+            dir: theme.dir.clone(),
+            title: theme.title.clone(),
+            is_system: theme.is_system,
+            is_custom: theme.is_custom,
+            has_colors: theme.colors.is_some(),
+            has_image: !theme.image.is_empty(),
+        }
+    }
+}
+
+/// Colors persisted to disk with the modification time of the file they came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedColors {
+    source_mtime: u128,
+    colors: Option<ThemeColors>,
+}
+
+/// On-disk color store loaded into memory while persistence is enabled
+#[derive(Debug)]
+struct PersistentColorStore {
+    path: PathBuf,
+    entries: HashMap<String, PersistedColors>,
+    dirty: bool,
+}
+
+/// In-memory colors bounded by `capacity`, evicting the least recently used entry
+#[derive(Debug)]
+struct LruColors {
+    /// Colors per theme directory with the tick of their last use
+    entries: HashMap<String, (Option<ThemeColors>, u64)>,
+    tick: u64,
+    capacity: usize,
+}
+
+impl LruColors {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            tick: 0,
+            capacity: capacity.max(1),
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn get(&mut self, theme_dir: &str) -> Option<Option<ThemeColors>> {
+        let tick = self.next_tick();
+        let (colors, last_used) = self.entries.get_mut(theme_dir)?;
+        *last_used = tick;
+        Some(colors.clone())
+    }
+
+    fn insert(&mut self, theme_dir: String, colors: Option<ThemeColors>) {
+        let tick = self.next_tick();
+        if !self.entries.contains_key(&theme_dir) {
+            self.evict_to(self.capacity - 1);
+        }
+        self.entries.insert(theme_dir, (colors, tick));
+    }
+
+    /// Drop least recently used entries until at most `len` remain
+    fn evict_to(&mut self, len: usize) {
+        while self.entries.len() > len {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(dir, _)| dir.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
 /// Color extraction cache to avoid recomputation
 #[derive(Debug, Clone)]
 pub struct ColorCache {
-    cache: Arc<RwLock<HashMap<String, Option<ThemeColors>>>>,
+    cache: Arc<RwLock<LruColors>>,
+    persistent: Arc<RwLock<Option<PersistentColorStore>>>,
+    /// Shared by clones, which the parallel loader hands to each task
+    counters: Arc<HitCounters>,
 }
 
 impl Default for ColorCache {
@@ -35,45 +122,238 @@ impl Default for ColorCache {
 
 impl ColorCache {
     pub fn new() -> Self {
+        Self::with_capacity(CacheConfig::default().max_cache_size)
+    }
+
+    /// Create a cache holding at most `capacity` theme directories in memory
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            cache: Arc::new(RwLock::new(HashMap::new())),
+            cache: Arc::new(RwLock::new(LruColors::new(capacity))),
+            persistent: Arc::new(RwLock::new(None)),
+            counters: Arc::new(HitCounters::default()),
+        }
+    }
+
+    /// Change the in-memory capacity, evicting least recently used entries if it shrank
+    pub async fn set_capacity(&self, capacity: usize) {
+        let mut cache = self.cache.write().await;
+        cache.capacity = capacity.max(1);
+        let capacity = cache.capacity;
+        cache.evict_to(capacity);
+    }
+
+    /// Persist extracted colors to `path`, loading any entries already stored there
+    pub async fn enable_persistence(&self, path: PathBuf) {
+        let entries = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Failed to parse persisted color cache, starting fresh: {e}");
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        log::info!(
+            "Loaded {} persisted color entries from {}",
+            entries.len(),
+            path.display()
+        );
+
+        *self.persistent.write().await = Some(PersistentColorStore {
+            path,
+            entries,
+            dirty: false,
+        });
+    }
+
+    /// Stop persisting extracted colors (the file on disk is left in place)
+    pub async fn disable_persistence(&self) {
+        *self.persistent.write().await = None;
+    }
+
+    /// Get persisted colors for a theme directory if its source file is unchanged.
+    /// Entries recorded for a different modification time are dropped.
+    async fn get_persisted(
+        &self,
+        theme_dir: &str,
+        source_mtime: u128,
+    ) -> Option<Option<ThemeColors>> {
+        let mut persistent = self.persistent.write().await;
+        let store = persistent.as_mut()?;
+        let entry = store.entries.get(theme_dir)?;
+
+        if entry.source_mtime == source_mtime {
+            return Some(entry.colors.clone());
+        }
+
+        store.entries.remove(theme_dir);
+        store.dirty = true;
+        None
+    }
+
+    /// Record colors for a theme directory in the persistent store, if enabled
+    async fn set_persisted(
+        &self,
+        theme_dir: String,
+        source_mtime: u128,
+        colors: Option<ThemeColors>,
+    ) {
+        if let Some(store) = self.persistent.write().await.as_mut() {
+            store.entries.insert(
+                theme_dir,
+                PersistedColors {
+                    source_mtime,
+                    colors,
+                },
+            );
+            store.dirty = true;
+        }
+    }
+
+    /// Write pending persistent entries to disk
+    pub async fn flush(&self) -> Result<(), String> {
+        let mut persistent = self.persistent.write().await;
+        let Some(store) = persistent.as_mut().filter(|store| store.dirty) else {
+            return Ok(());
+        };
+
+        if let Some(parent) = store.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create color cache directory: {e}"))?;
         }
+        let content = serde_json::to_string(&store.entries)
+            .map_err(|e| format!("Failed to serialize color cache: {e}"))?;
+        fs::write(&store.path, content).map_err(|e| format!("Failed to write color cache: {e}"))?;
+
+        store.dirty = false;
+        Ok(())
     }
 
-    /// Get cached colors for a theme directory
+    /// Get cached colors for a theme directory, marking them as recently used and counting
+    /// a hit or a miss
     pub async fn get(&self, theme_dir: &str) -> Option<Option<ThemeColors>> {
-        let cache = self.cache.read().await;
-        cache.get(theme_dir).cloned()
+        let mut cache = self.cache.write().await;
+        let colors = cache.get(theme_dir);
+        self.counters.record(colors.is_some());
+        colors
+    }
+
+    /// Whether colors for a theme directory are cached, without counting a lookup
+    pub async fn contains(&self, theme_dir: &str) -> bool {
+        self.cache.read().await.entries.contains_key(theme_dir)
+    }
+
+    /// Lookup counts as (hits, misses) since startup or the last reset
+    pub fn hit_counts(&self) -> (u64, u64) {
+        self.counters.get()
+    }
+
+    /// Zero the lookup counts
+    pub fn reset_hit_counts(&self) {
+        self.counters.reset();
     }
 
-    /// Cache colors for a theme directory
+    /// Cache colors for a theme directory, evicting the least recently used one when full
     pub async fn set(&self, theme_dir: String, colors: Option<ThemeColors>) {
         let mut cache = self.cache.write().await;
         cache.insert(theme_dir, colors);
     }
 
+    /// Remove cached colors for a theme directory
+    pub async fn remove(&self, theme_dir: &str) -> bool {
+        if let Some(store) = self.persistent.write().await.as_mut() {
+            store.dirty |= store.entries.remove(theme_dir).is_some();
+        }
+        let mut cache = self.cache.write().await;
+        cache.entries.remove(theme_dir).is_some()
+    }
+
     /// Clear the cache
     pub async fn clear(&self) {
+        if let Some(store) = self.persistent.write().await.as_mut() {
+            store.entries.clear();
+            store.dirty = true;
+        }
         let mut cache = self.cache.write().await;
-        cache.clear();
+        cache.entries.clear();
     }
 
     /// Get cache size
     pub async fn size(&self) -> usize {
         let cache = self.cache.read().await;
-        cache.len()
+        cache.entries.len()
+    }
+}
+
+/// Preview image data URLs keyed by image path, reused while the file's mtime is unchanged
+/// so full scans don't re-read and re-encode every image
+#[derive(Debug, Clone, Default)]
+pub struct ImageCache {
+    entries: Arc<std::sync::Mutex<HashMap<PathBuf, (u128, String)>>>,
+    counters: Arc<HitCounters>,
+}
+
+impl ImageCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, (u128, String)>> {
+        // Entries are plain values, so a panic elsewhere can't leave them half-updated
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Data URL for an image, encoding the file only when it changed since it was cached
+    pub fn data_url(&self, image_path: &Path) -> Result<String, String> {
+        let mtime = fs::metadata(image_path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|since_epoch| since_epoch.as_nanos());
+
+        if let Some(mtime) = mtime {
+            if let Some((cached_mtime, data_url)) = self.lock().get(image_path) {
+                if *cached_mtime == mtime {
+                    self.counters.record(true);
+                    return Ok(data_url.clone());
+                }
+            }
+        }
+        self.counters.record(false);
+
+        let data_url = OptimizedThemeLoader::convert_image_to_data_url(image_path)?;
+        if let Some(mtime) = mtime {
+            self.lock()
+                .insert(image_path.to_path_buf(), (mtime, data_url.clone()));
+        }
+        Ok(data_url)
+    }
+
+    /// Lookup counts as (hits, misses) since startup or the last reset
+    pub fn hit_counts(&self) -> (u64, u64) {
+        self.counters.get()
+    }
+
+    /// Drop all cached data URLs
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Number of cached images
+    pub fn size(&self) -> usize {
+        self.lock().len()
     }
 }
 
 /// Optimized theme loader with parallel processing and caching
 pub struct OptimizedThemeLoader {
     color_cache: ColorCache,
+    image_cache: ImageCache,
 }
 
 impl OptimizedThemeLoader {
     pub fn new() -> Self {
         Self {
             color_cache: ColorCache::new(),
+            image_cache: ImageCache::new(),
         }
     }
 
@@ -102,16 +382,19 @@ impl OptimizedThemeLoader {
 
     /// Load themes with parallel processing for better performance
     pub async fn load_themes_parallel(&self) -> Result<Vec<SysTheme>, String> {
-        let home_dir =
-            dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
-        let themes_dir = home_dir.join(".config/omarchy/themes");
+        let themes_dir = super::themes_dir::themes_dir()?;
 
         if !themes_dir.exists() {
             return Err(format!("Themes directory does not exist: {themes_dir:?}"));
         }
 
+        self.load_themes_from_dir(&themes_dir).await
+    }
+
+    /// Load all themes found directly under the given directory in parallel
+    pub async fn load_themes_from_dir(&self, themes_dir: &Path) -> Result<Vec<SysTheme>, String> {
         // Collect all theme directory paths
-        let theme_paths = self.collect_theme_paths(&themes_dir)?;
+        let theme_paths = self.collect_theme_paths(themes_dir)?;
 
         if theme_paths.is_empty() {
             return Ok(Vec::new());
@@ -127,8 +410,9 @@ impl OptimizedThemeLoader {
 
         for path in theme_paths {
             let color_cache = self.color_cache.clone();
+            let image_cache = self.image_cache.clone();
             let handle = tokio::spawn(async move {
-                Self::generate_theme_from_directory_async(&path, color_cache).await
+                Self::generate_theme_from_directory_async(&path, color_cache, image_cache).await
             });
             handles.push(handle);
         }
@@ -154,15 +438,34 @@ impl OptimizedThemeLoader {
             );
         }
 
+        if let Err(e) = self.color_cache.flush().await {
+            log::warn!("Failed to persist color cache: {e}");
+        }
+
+        // Tasks finish in any order; sort so results are stable between scans
+        sort_by_title(&mut themes);
+
         log::info!("Successfully loaded {} themes in parallel", themes.len());
         Ok(themes)
     }
 
+    /// Load a single theme directory, sharing the color cache with full scans
+    pub async fn load_theme(&self, theme_dir: &Path) -> Result<SysTheme, String> {
+        let theme = Self::generate_theme_from_directory_async(
+            theme_dir,
+            self.color_cache.clone(),
+            self.image_cache.clone(),
+        )
+        .await?;
+        if let Err(e) = self.color_cache.flush().await {
+            log::warn!("Failed to persist color cache: {e}");
+        }
+        Ok(theme)
+    }
+
     /// Load only theme metadata for faster initial responses
     pub async fn load_theme_metadata_only(&self) -> Result<Vec<ThemeMetadata>, String> {
-        let home_dir =
-            dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
-        let themes_dir = home_dir.join(".config/omarchy/themes");
+        let themes_dir = super::themes_dir::themes_dir()?;
 
         if !themes_dir.exists() {
             return Err(format!("Themes directory does not exist: {themes_dir:?}"));
@@ -218,7 +521,13 @@ impl OptimizedThemeLoader {
             let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
             let path = entry.path();
 
-            if path.is_dir() {
+            // Dot directories are staging areas of creates, imports and overwrites
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            // Keep dangling links too, so broken system themes show up as such
+            if path.is_dir() || path.is_symlink() && fs::metadata(&path).is_err() {
                 theme_paths.push(path);
             }
         }
@@ -289,6 +598,7 @@ impl OptimizedThemeLoader {
     async fn generate_theme_from_directory_async(
         theme_dir: &Path,
         color_cache: ColorCache,
+        image_cache: ImageCache,
     ) -> Result<SysTheme, String> {
         let dir_name = theme_dir
             .file_name()
@@ -309,20 +619,27 @@ impl OptimizedThemeLoader {
                 .unwrap_or(false)
         };
 
+        // Following the link fails when a system theme's target has gone missing
+        let symlink_ok = fs::metadata(theme_dir).is_ok();
+
         // Extract colors with caching
         let colors = Self::extract_theme_colors_cached(theme_dir, is_custom, &color_cache).await;
 
         // Load image asynchronously
-        let image_path = Self::load_theme_image_async(theme_dir).await;
+        let (image, image_path) = Self::load_theme_image_async(theme_dir, image_cache).await;
 
         Ok(SysTheme {
             dir: dir_name.to_string(),
             title,
             description: format!("Auto-generated theme from {dir_name}"),
-            image: image_path,
+            image,
+            image_path,
             is_system,
             is_custom,
+            is_dark: colors.as_ref().and_then(ColorExtractor::is_dark),
             colors,
+            symlink_ok,
+            modified_at: theme_modified_at(theme_dir),
         })
     }
 
@@ -340,99 +657,80 @@ impl OptimizedThemeLoader {
             return cached_colors;
         }
 
+        // Then the persisted cache, valid only while the source file is unchanged
+        let source_mtime = Self::color_source_mtime(theme_dir);
+        if let Some(mtime) = source_mtime {
+            if let Some(persisted) = color_cache.get_persisted(&dir_name, mtime).await {
+                log::debug!("Using persisted colors for theme: {dir_name}");
+                color_cache.set(dir_name, persisted.clone()).await;
+                return persisted;
+            }
+        }
+
         // Extract colors if not cached
-        let colors = Self::extract_theme_colors_direct(theme_dir, is_custom);
+        let colors = extract_theme_colors(theme_dir, is_custom);
 
         // Cache the result (even if None)
         color_cache.set(dir_name.clone(), colors.clone()).await;
+        if let Some(mtime) = source_mtime {
+            color_cache
+                .set_persisted(dir_name.clone(), mtime, colors.clone())
+                .await;
+        }
         log::debug!("Cached colors for theme: {dir_name}");
 
         colors
     }
 
-    /// Direct color extraction (moved from original implementation)
-    fn extract_theme_colors_direct(theme_dir: &Path, is_custom: bool) -> Option<ThemeColors> {
-        if is_custom {
-            // For custom themes, try to extract from custom_theme.json
-            let custom_theme_path = theme_dir.join("custom_theme.json");
-            if custom_theme_path.exists() {
-                match fs::read_to_string(&custom_theme_path) {
-                    Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
-                        Ok(theme_data) => {
-                            if let Some(colors) =
-                                ColorExtractor::extract_from_custom_theme(&theme_data)
-                            {
-                                return Some(colors);
-                            }
-                        },
-                        Err(e) => {
-                            log::warn!(
-                                "Failed to parse custom theme JSON at {custom_theme_path:?}: {e}"
-                            );
-                        },
-                    },
-                    Err(e) => {
-                        log::warn!(
-                            "Failed to read custom theme file at {custom_theme_path:?}: {e}"
-                        );
-                    },
-                }
-            }
-        }
-
-        // For system themes or fallback, try to extract from alacritty.toml
-        let alacritty_config_path = theme_dir.join("alacritty.toml");
-        if alacritty_config_path.exists() {
-            if let Some(colors) =
-                ColorExtractor::extract_from_alacritty_config(&alacritty_config_path)
-            {
-                return Some(colors);
-            }
-        }
-
-        None
+    /// Latest modification time (ns since the epoch) of the files colors are extracted from
+    fn color_source_mtime(theme_dir: &Path) -> Option<u128> {
+        COLOR_SOURCE_FILES
+            .iter()
+            .filter_map(|file| fs::metadata(theme_dir.join(file)).ok()?.modified().ok())
+            .filter_map(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|since_epoch| since_epoch.as_nanos())
+            .max()
     }
 
-    /// Load theme image asynchronously
-    async fn load_theme_image_async(theme_dir: &Path) -> String {
+    /// Load theme image asynchronously, returning the data URL and the source file path
+    async fn load_theme_image_async(
+        theme_dir: &Path,
+        image_cache: ImageCache,
+    ) -> (String, Option<String>) {
         // This is I/O bound, so we can spawn it as a blocking task
         let theme_dir_path = theme_dir.to_path_buf();
         let theme_dir_display = theme_dir.display().to_string();
 
-        match tokio::task::spawn_blocking(move || Self::find_and_convert_image(&theme_dir_path))
-            .await
+        match tokio::task::spawn_blocking(move || {
+            Self::find_and_convert_image(&theme_dir_path, &image_cache)
+        })
+        .await
         {
-            Ok(Ok(image_path)) => image_path,
+            Ok(Ok(image)) => image,
             Ok(Err(e)) => {
                 log::warn!("Failed to load image for theme {theme_dir_display}: {e}");
-                String::new()
+                (String::new(), None)
             },
             Err(e) => {
                 log::warn!("Image loading task failed for theme {theme_dir_display}: {e}");
-                String::new()
+                (String::new(), None)
             },
         }
     }
 
-    /// Find and convert image to data URL (blocking operation)
-    fn find_and_convert_image(theme_dir: &Path) -> Result<String, String> {
-        if let Ok(entries) = fs::read_dir(theme_dir) {
-            for entry in entries.flatten() {
-                let file_path = entry.path();
-                if file_path.is_file() {
-                    if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
-                        let ext_lower = extension.to_lowercase();
-                        if matches!(
-                            ext_lower.as_str(),
-                            "png" | "jpg" | "jpeg" | "webp" | "gif" | "svg"
-                        ) {
-                            return Self::convert_image_to_data_url(&file_path);
-                        }
-                    }
-                }
-            }
+    /// Find and convert image to data URL along with its path (blocking operation)
+    fn find_and_convert_image(
+        theme_dir: &Path,
+        image_cache: &ImageCache,
+    ) -> Result<(String, Option<String>), String> {
+        match super::get_sys_themes::preview_image_candidates(theme_dir).first() {
+            Some(file_path) => {
+                let data_url = image_cache.data_url(file_path)?;
+                let image_path = super::get_sys_themes::absolute_image_path(file_path);
+                Ok((data_url, Some(image_path)))
+            },
+            None => Ok((String::new(), None)),
         }
-        Ok(String::new())
     }
 
     /// Convert a local image file to a base64 data URL
@@ -441,9 +739,6 @@ impl OptimizedThemeLoader {
             return Err(format!("Image file does not exist: {image_path:?}"));
         }
 
-        let image_data =
-            fs::read(image_path).map_err(|e| format!("Failed to read image file: {e}"))?;
-
         // Determine MIME type based on file extension
         let mime_type = match image_path.extension().and_then(|ext| ext.to_str()) {
             Some("png") => "image/png",
@@ -454,57 +749,55 @@ impl OptimizedThemeLoader {
             _ => "image/png", // Default to PNG
         };
 
-        let base64_data = Self::base64_encode(&image_data);
-        Ok(format!("data:{mime_type};base64,{base64_data}"))
-    }
-
-    /// Optimized base64 encoding function with pre-allocated capacity
-    fn base64_encode(data: &[u8]) -> String {
-        if data.is_empty() {
-            return String::new();
+        // SVG is text, so it embeds smaller URL-encoded than as base64
+        if mime_type == "image/svg+xml" {
+            return super::theme_preview::svg_data_url(image_path);
         }
+        super::theme_preview::preview_data_url(image_path, mime_type)
+    }
 
-        const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-
-        // Pre-allocate with exact capacity to avoid reallocations
-        let output_len = data.len().div_ceil(3) * 4;
-        let mut result = String::with_capacity(output_len);
+    /// Clear the color and preview image caches
+    pub async fn clear_cache(&self) {
+        self.color_cache.clear().await;
+        self.image_cache.clear();
+        log::info!("Color extraction and image caches cleared");
+    }
 
-        for chunk in data.chunks(3) {
-            let mut buf = [0u8; 3];
-            for (i, &byte) in chunk.iter().enumerate() {
-                buf[i] = byte;
-            }
+    /// Persist extracted colors to `path` across launches, or stop persisting with `None`
+    pub async fn set_color_cache_persistence(&self, path: Option<PathBuf>) {
+        match path {
+            Some(path) => self.color_cache.enable_persistence(path).await,
+            None => self.color_cache.disable_persistence().await,
+        }
+    }
 
-            let b = ((buf[0] as u32) << 16) | ((buf[1] as u32) << 8) | (buf[2] as u32);
+    /// Limit how many theme directories keep their extracted colors in memory
+    pub async fn set_color_cache_capacity(&self, capacity: usize) {
+        self.color_cache.set_capacity(capacity).await;
+    }
 
-            result.push(CHARS[((b >> 18) & 63) as usize] as char);
-            result.push(CHARS[((b >> 12) & 63) as usize] as char);
-            result.push(if chunk.len() > 1 {
-                CHARS[((b >> 6) & 63) as usize] as char
-            } else {
-                '='
-            });
-            result.push(if chunk.len() > 2 {
-                CHARS[(b & 63) as usize] as char
-            } else {
-                '='
-            });
+    /// Evict cached colors for a single theme directory
+    pub async fn invalidate_theme_colors(&self, dir: &str) {
+        if self.color_cache.remove(dir).await {
+            log::info!("Evicted cached colors for theme: {dir}");
         }
-
-        result
     }
 
-    /// Clear the color cache
-    pub async fn clear_cache(&self) {
-        self.color_cache.clear().await;
-        log::info!("Color extraction cache cleared");
+    /// Check whether colors for a theme directory are cached
+    pub async fn has_cached_colors(&self, dir: &str) -> bool {
+        self.color_cache.contains(dir).await
     }
 
-    /// Get cache statistics
-    pub async fn get_cache_stats(&self) -> (usize,) {
+    /// Get cache statistics as (size, hits, misses)
+    pub async fn get_cache_stats(&self) -> (usize, u64, u64) {
         let size = self.color_cache.size().await;
-        (size,)
+        let (hits, misses) = self.color_cache.hit_counts();
+        (size, hits, misses)
+    }
+
+    /// Zero the color cache hit and miss counts
+    pub fn reset_cache_stats(&self) {
+        self.color_cache.reset_hit_counts();
     }
 }
 
@@ -521,6 +814,69 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn test_persisted_colors_survive_new_loader_until_file_changes() {
+        const NORMAL: &str = "[colors.normal]\nred = \"#ff0000\"\ngreen = \"#00ff00\"\n\
+            yellow = \"#ffff00\"\nblue = \"#0000ff\"\nmagenta = \"#ff00ff\"\ncyan = \"#00ffff\"\n";
+        let temp_dir = TempDir::new().unwrap();
+        let themes_dir = temp_dir.path().join("themes");
+        let theme_dir = themes_dir.join("persisted");
+        fs::create_dir_all(&theme_dir).unwrap();
+        let config_path = theme_dir.join("alacritty.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[colors.primary]\nbackground = \"#123456\"\nforeground = \"#fedcba\"\n{NORMAL}"
+            ),
+        )
+        .unwrap();
+        let store_path = temp_dir.path().join("color_cache.json");
+
+        let loader = OptimizedThemeLoader::new();
+        loader
+            .set_color_cache_persistence(Some(store_path.clone()))
+            .await;
+        loader.load_themes_from_dir(&themes_dir).await.unwrap();
+        assert!(store_path.is_file());
+
+        // Change the content but keep the mtime: a fresh loader must serve the persisted colors
+        let mtime = fs::metadata(&config_path).unwrap().modified().unwrap();
+        fs::write(
+            &config_path,
+            format!(
+                "[colors.primary]\nbackground = \"#000000\"\nforeground = \"#ffffff\"\n{NORMAL}"
+            ),
+        )
+        .unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&config_path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+
+        let loader = OptimizedThemeLoader::new();
+        loader
+            .set_color_cache_persistence(Some(store_path.clone()))
+            .await;
+        let themes = loader.load_themes_from_dir(&themes_dir).await.unwrap();
+        let colors = themes[0].colors.as_ref().unwrap();
+        assert_eq!(colors.primary.background, "#123456");
+
+        // A changed mtime invalidates the persisted entry
+        fs::File::options()
+            .write(true)
+            .open(&config_path)
+            .unwrap()
+            .set_modified(mtime + std::time::Duration::from_secs(5))
+            .unwrap();
+        let loader = OptimizedThemeLoader::new();
+        loader.set_color_cache_persistence(Some(store_path)).await;
+        let themes = loader.load_themes_from_dir(&themes_dir).await.unwrap();
+        let colors = themes[0].colors.as_ref().unwrap();
+        assert_eq!(colors.primary.background, "#000000");
+    }
+
     #[tokio::test]
     async fn test_color_cache() {
         let cache = ColorCache::new();
@@ -585,6 +941,41 @@ mod tests {
         assert!(metadata.has_image);
     }
 
+    #[test]
+    fn test_collect_theme_paths_skips_staging_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("nord")).unwrap();
+        fs::create_dir(temp_dir.path().join(".create-1234")).unwrap();
+
+        let loader = OptimizedThemeLoader::new();
+        let paths = loader.collect_theme_paths(temp_dir.path()).unwrap();
+
+        assert_eq!(paths, vec![temp_dir.path().join("nord")]);
+    }
+
+    #[tokio::test]
+    async fn test_color_cache_evicts_least_recently_used() {
+        let cache = ColorCache::with_capacity(2);
+        cache.set("first".to_string(), None).await;
+        cache.set("second".to_string(), None).await;
+        cache.set("third".to_string(), None).await;
+
+        assert_eq!(cache.size().await, 2);
+        assert!(cache.get("first").await.is_none());
+
+        // Touching "second" makes "third" the oldest entry
+        assert!(cache.get("second").await.is_some());
+        cache.set("fourth".to_string(), None).await;
+        assert!(cache.get("second").await.is_some());
+        assert!(cache.get("third").await.is_none());
+        assert!(cache.get("fourth").await.is_some());
+
+        // Shrinking drops the least recently used entry ("second" was touched first)
+        cache.set_capacity(1).await;
+        assert_eq!(cache.size().await, 1);
+        assert!(cache.get("fourth").await.is_some());
+    }
+
     #[tokio::test]
     async fn test_extract_theme_colors_cached() {
         let temp_dir = TempDir::new().unwrap();
@@ -615,6 +1006,50 @@ mod tests {
         assert_eq!(c1.primary.background, c2.primary.background);
     }
 
+    #[tokio::test]
+    async fn test_extract_theme_colors_cached_reads_kitty_only_theme() {
+        let temp_dir = TempDir::new().unwrap();
+        let theme_dir = temp_dir.path().join("kitty-theme");
+        fs::create_dir(&theme_dir).unwrap();
+        let kitty_config = "background #1a1b26\nforeground #c0caf5\ncolor1 #f7768e\ncolor2 #9ece6a\ncolor3 #e0af68\ncolor4 #7aa2f7\ncolor5 #bb9af7\ncolor6 #7dcfff\n";
+        fs::write(theme_dir.join("kitty.conf"), kitty_config).unwrap();
+
+        let cache = ColorCache::new();
+        let colors = OptimizedThemeLoader::extract_theme_colors_cached(&theme_dir, false, &cache)
+            .await
+            .unwrap();
+        assert_eq!(colors.primary.background, "#1a1b26");
+        assert_eq!(colors.terminal.blue, "#7aa2f7");
+        assert!(OptimizedThemeLoader::color_source_mtime(&theme_dir).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_extract_theme_colors_cached_reads_wezterm_color_tables() {
+        let temp_dir = TempDir::new().unwrap();
+        let color_table = r##"return {
+  foreground = "#cdd6f4",
+  background = "#1e1e2e",
+  ansi = {
+    "#45475a", "#f38ba8", "#a6e3a1", "#f9e2af",
+    "#89b4fa", "#f5c2e7", "#94e2d5", "#bac2de",
+  },
+}
+"##;
+        let cache = ColorCache::new();
+        for file_name in ["wezterm.lua", "colors.lua"] {
+            let theme_dir = temp_dir.path().join(file_name.replace('.', "-"));
+            fs::create_dir(&theme_dir).unwrap();
+            fs::write(theme_dir.join(file_name), color_table).unwrap();
+
+            let colors =
+                OptimizedThemeLoader::extract_theme_colors_cached(&theme_dir, false, &cache)
+                    .await
+                    .unwrap();
+            assert_eq!(colors.primary.background, "#1e1e2e", "{file_name}");
+            assert_eq!(colors.terminal.red, "#f38ba8", "{file_name}");
+        }
+    }
+
     #[test]
     fn test_has_image_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -646,15 +1081,36 @@ mod tests {
         assert!(result.len() > 30); // Should have base64 encoded data
     }
 
-    #[test]
-    fn test_base64_encode() {
-        let data = b"hello world";
-        let encoded = OptimizedThemeLoader::base64_encode(data);
-        assert_eq!(encoded, "aGVsbG8gd29ybGQ=");
+    #[tokio::test]
+    async fn test_image_data_url_is_reused_until_the_file_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let theme_dir = temp_dir.path().join("pictured");
+        fs::create_dir(&theme_dir).unwrap();
+        let image_path = theme_dir.join("preview.png");
+        fs::write(&image_path, b"first").unwrap();
+
+        let loader = OptimizedThemeLoader::new();
+        let first = loader.load_theme(&theme_dir).await.unwrap();
+        assert_eq!(loader.image_cache.hit_counts(), (0, 1));
+        let second = loader.load_theme(&theme_dir).await.unwrap();
+        assert_eq!(loader.image_cache.hit_counts(), (1, 1));
+        assert_eq!(first.image, second.image);
+        assert_eq!(loader.image_cache.size(), 1);
+
+        // A rewritten file is encoded again
+        fs::write(&image_path, b"second").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&image_path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        let third = loader.load_theme(&theme_dir).await.unwrap();
+        assert_eq!(loader.image_cache.hit_counts(), (1, 2));
+        assert_ne!(third.image, first.image);
 
-        let empty_data = b"";
-        let empty_encoded = OptimizedThemeLoader::base64_encode(empty_data);
-        assert_eq!(empty_encoded, "");
+        loader.clear_cache().await;
+        assert_eq!(loader.image_cache.size(), 0);
     }
 }
 
@@ -684,7 +1140,7 @@ async fn test_cache_statistics() {
     let loader = OptimizedThemeLoader::new();
 
     // Initially cache should be empty
-    let (cache_size,) = loader.get_cache_stats().await;
+    let (cache_size, _, _) = loader.get_cache_stats().await;
     assert_eq!(cache_size, 0);
 
     // Add something to cache
@@ -693,13 +1149,34 @@ async fn test_cache_statistics() {
     cache.set("test-theme".to_string(), Some(colors)).await;
 
     // Cache size should increase
-    let (cache_size,) = loader.get_cache_stats().await;
+    let (cache_size, _, _) = loader.get_cache_stats().await;
     assert_eq!(cache_size, 1);
 
     // Clear cache
     loader.clear_cache().await;
 
     // Cache should be empty again
-    let (cache_size,) = loader.get_cache_stats().await;
+    let (cache_size, _, _) = loader.get_cache_stats().await;
     assert_eq!(cache_size, 0);
+
+    // Lookups from concurrent tasks on clones all land in the shared counters
+    cache.set("hit".to_string(), None).await;
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                cache
+                    .get(if i % 2 == 0 { "hit" } else { "miss" })
+                    .await
+                    .is_some()
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    assert_eq!(loader.get_cache_stats().await, (1, 4, 4));
+
+    loader.reset_cache_stats();
+    assert_eq!(loader.get_cache_stats().await, (1, 0, 0));
 }
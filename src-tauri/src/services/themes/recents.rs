@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Record of when a theme was last applied
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppliedThemeRecord {
+    pub last_applied_at: String,
+}
+
+/// Persistent store of theme apply history, keyed by theme directory name
+pub struct ThemeRecents {
+    store_path: PathBuf,
+    themes_dir: PathBuf,
+}
+
+impl ThemeRecents {
+    /// Create a store using the default omarchist config location and the themes directory
+    pub fn new() -> Result<Self, String> {
+        let config_dir = super::themes_dir::config_dir()?;
+
+        Ok(Self::with_paths(
+            config_dir.join("omarchist").join("recents.json"),
+            super::themes_dir::themes_dir()?,
+        ))
+    }
+
+    /// Create a store with explicit file and themes directory locations
+    pub fn with_paths(store_path: PathBuf, themes_dir: PathBuf) -> Self {
+        Self {
+            store_path,
+            themes_dir,
+        }
+    }
+
+    /// Load all apply records (missing or unreadable file yields an empty history)
+    pub fn load(&self) -> HashMap<String, AppliedThemeRecord> {
+        let Ok(content) = fs::read_to_string(&self.store_path) else {
+            return HashMap::new();
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Failed to parse theme recents, starting fresh: {e}");
+            HashMap::new()
+        })
+    }
+
+    fn save(&self, records: &HashMap<String, AppliedThemeRecord>) -> Result<(), String> {
+        if let Some(parent) = self.store_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create recents directory: {e}"))?;
+        }
+
+        let content = serde_json::to_string_pretty(records)
+            .map_err(|e| format!("Failed to serialize theme recents: {e}"))?;
+        fs::write(&self.store_path, content)
+            .map_err(|e| format!("Failed to write theme recents: {e}"))
+    }
+
+    /// Record that a theme was applied now
+    pub fn record_applied(&self, dir: &str) -> Result<(), String> {
+        let mut records = self.load();
+        records.insert(
+            dir.to_string(),
+            AppliedThemeRecord {
+                last_applied_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        self.save(&records)
+    }
+
+    /// Get when a theme was last applied, or None if never applied or since deleted
+    pub fn last_applied(&self, dir: &str) -> Option<String> {
+        let mut records = self.load();
+
+        if !self.themes_dir.join(dir).exists() {
+            // Drop history for themes that no longer exist
+            if records.remove(dir).is_some() {
+                if let Err(e) = self.save(&records) {
+                    log::warn!("Failed to prune recents for deleted theme '{dir}': {e}");
+                }
+            }
+            return None;
+        }
+
+        records.remove(dir).map(|record| record.last_applied_at)
+    }
+}
+
+#[tauri::command]
+pub async fn get_theme_last_applied(dir: String) -> Result<Option<String>, String> {
+    let recents = ThemeRecents::new()?;
+    Ok(recents.last_applied(&dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_recents(temp: &TempDir) -> ThemeRecents {
+        ThemeRecents::with_paths(
+            temp.path().join("omarchist").join("recents.json"),
+            temp.path().join("themes"),
+        )
+    }
+
+    #[test]
+    fn test_record_applied_sets_timestamp() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("themes").join("nord")).unwrap();
+        let recents = create_recents(&temp);
+
+        assert_eq!(recents.last_applied("nord"), None);
+
+        recents.record_applied("nord").unwrap();
+        let applied = recents
+            .last_applied("nord")
+            .expect("timestamp should be set");
+        assert!(chrono::DateTime::parse_from_rfc3339(&applied).is_ok());
+    }
+
+    #[test]
+    fn test_deleted_theme_is_omitted() {
+        let temp = TempDir::new().unwrap();
+        let theme_dir = temp.path().join("themes").join("gone");
+        fs::create_dir_all(&theme_dir).unwrap();
+        let recents = create_recents(&temp);
+
+        recents.record_applied("gone").unwrap();
+        fs::remove_dir_all(&theme_dir).unwrap();
+
+        assert_eq!(recents.last_applied("gone"), None);
+        assert!(!recents.load().contains_key("gone"));
+    }
+}
@@ -0,0 +1,175 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Extensions of already-compressed files that are stored as-is
+const STORED_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "gif"];
+
+/// Name of the metadata file that marks the root of a theme
+const METADATA_FILE: &str = "custom_theme.json";
+
+/// Files under `dir` relative to it, sorted; symlinked directories are not followed
+fn collect_files(dir: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {e}", dir.display()))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read file type: {e}"))?;
+        let path = entry.path();
+        let relative_path = relative.join(entry.file_name());
+
+        if file_type.is_dir() {
+            // Thumbnails are a local cache and get rebuilt on demand
+            if entry.file_name() == super::theme_preview::THUMBNAILS_DIR {
+                continue;
+            }
+            collect_files(&path, &relative_path, files)?;
+        } else if path.is_file() {
+            files.push(relative_path);
+        }
+    }
+
+    files.sort();
+    Ok(())
+}
+
+/// Zip entry name for a path relative to the theme directory
+fn entry_name(top_level: &str, relative: &Path) -> String {
+    let mut name = top_level.to_string();
+    for component in relative.components() {
+        name.push('/');
+        name.push_str(&component.as_os_str().to_string_lossy());
+    }
+    name
+}
+
+/// Write every file of `theme_dir` into a zip at `dest`, under a `top_level/` folder.
+/// Images are stored without recompression.
+pub fn write_theme_archive(theme_dir: &Path, top_level: &str, dest: &Path) -> Result<(), String> {
+    let mut files = Vec::new();
+    collect_files(theme_dir, Path::new(""), &mut files)?;
+
+    let file = File::create(dest)
+        .map_err(|e| format!("Failed to create archive {}: {e}", dest.display()))?;
+    let mut writer = ZipWriter::new(file);
+
+    let result = (|| {
+        for relative in &files {
+            let is_stored = relative
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .is_some_and(|ext| STORED_EXTENSIONS.contains(&ext.as_str()));
+            let method = if is_stored {
+                CompressionMethod::Stored
+            } else {
+                CompressionMethod::Deflated
+            };
+            let options = SimpleFileOptions::default().compression_method(method);
+
+            writer
+                .start_file(entry_name(top_level, relative), options)
+                .map_err(|e| format!("Failed to add {} to archive: {e}", relative.display()))?;
+            let mut source = File::open(theme_dir.join(relative))
+                .map_err(|e| format!("Failed to open {}: {e}", relative.display()))?;
+            io::copy(&mut source, &mut writer)
+                .map_err(|e| format!("Failed to write {} to archive: {e}", relative.display()))?;
+        }
+
+        writer
+            .finish()
+            .map_err(|e| format!("Failed to finish archive: {e}"))?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
+
+/// Folder inside the archive that holds the theme: the root itself when `custom_theme.json`
+/// sits at the top, or the single top-level folder containing it
+fn theme_root(entries: &[PathBuf]) -> Result<PathBuf, String> {
+    if entries
+        .iter()
+        .any(|entry| entry == Path::new(METADATA_FILE))
+    {
+        return Ok(PathBuf::new());
+    }
+
+    let mut top_levels = entries.iter().filter_map(|entry| entry.components().next());
+    let Some(first) = top_levels.next() else {
+        return Err("Archive is empty".to_string());
+    };
+    let root = PathBuf::from(first.as_os_str());
+    let single_folder = top_levels.all(|component| component.as_os_str() == root.as_os_str());
+
+    if single_folder && entries.contains(&root.join(METADATA_FILE)) {
+        Ok(root)
+    } else {
+        Err(format!("Archive has no {METADATA_FILE} at the top level"))
+    }
+}
+
+/// Extract the theme stored in a zip into `dest`, dropping any single top-level folder.
+/// Entries that would land outside `dest` make the whole archive invalid.
+pub fn extract_theme_archive(archive_path: &Path, dest: &Path) -> Result<(), String> {
+    let file = File::open(archive_path)
+        .map_err(|e| format!("Failed to open archive {}: {e}", archive_path.display()))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Failed to read archive: {e}"))?;
+
+    let mut entries = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive entry: {e}"))?;
+        let path = entry.enclosed_name().ok_or_else(|| {
+            format!(
+                "Archive entry '{}' escapes the theme directory",
+                entry.name()
+            )
+        })?;
+        entries.push(path);
+    }
+    let root = theme_root(&entries)?;
+
+    for (index, path) in entries.iter().enumerate() {
+        let Ok(relative) = path.strip_prefix(&root) else {
+            continue;
+        };
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive entry: {e}"))?;
+        let target = dest.join(relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create directory {}: {e}", relative.display()))?;
+            continue;
+        }
+        if entry.is_symlink() {
+            log::warn!("Skipping symlink {} in theme archive", relative.display());
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {}: {e}", parent.display()))?;
+        }
+        let mut output = File::create(&target)
+            .map_err(|e| format!("Failed to create {}: {e}", relative.display()))?;
+        io::copy(&mut entry, &mut output)
+            .map_err(|e| format!("Failed to extract {}: {e}", relative.display()))?;
+    }
+
+    Ok(())
+}
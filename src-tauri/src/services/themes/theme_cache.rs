@@ -1,6 +1,13 @@
-use crate::services::themes::get_sys_themes::SysTheme;
+use crate::services::themes::get_sys_themes::{
+    invalidate_theme_colors, sort_by_title, theme_cache_key, SysTheme,
+};
+use crate::services::themes::optimized_theme_loader::ThemeMetadata;
+use crate::services::util::atomic_file::write_atomic;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
@@ -37,6 +44,49 @@ pub struct CachedTheme {
     pub metadata_only: bool,
 }
 
+/// Lightweight metadata entries served before the full themes are available
+#[derive(Debug, Default)]
+struct MetadataCache {
+    entries: HashMap<String, ThemeMetadata>,
+    cached_at: Option<SystemTime>,
+}
+
+/// On-disk copy of a full theme scan, reused on the next launch while still fresh
+#[derive(Debug, Serialize, Deserialize)]
+struct ThemeCacheSnapshot {
+    saved_at: SystemTime,
+    themes: Vec<SysTheme>,
+}
+
+/// Hit and miss counts for cache lookups, safe to bump from concurrent tasks
+#[derive(Debug, Default)]
+pub struct HitCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl HitCounters {
+    /// Count one lookup
+    pub fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current (hits, misses)
+    pub fn get(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Zero both counts
+    pub fn reset(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
 /// Thread-safe theme cache service
 #[derive(Debug)]
 pub struct ThemeCache {
@@ -46,6 +96,12 @@ pub struct ThemeCache {
     config: Arc<RwLock<CacheConfig>>,
     /// Last full cache refresh timestamp
     last_full_refresh: Arc<RwLock<Option<SystemTime>>>,
+    /// Metadata-only view kept in step with `themes`
+    metadata: Arc<RwLock<MetadataCache>>,
+    /// Snapshot file written after each full refresh; `None` disables persistence
+    snapshot_path: Arc<RwLock<Option<PathBuf>>>,
+    /// Lookups served from (or missing in) the cache
+    counters: Arc<HitCounters>,
 }
 
 impl ThemeCache {
@@ -55,6 +111,9 @@ impl ThemeCache {
             themes: Arc::new(RwLock::new(HashMap::new())),
             config: Arc::new(RwLock::new(CacheConfig::default())),
             last_full_refresh: Arc::new(RwLock::new(None)),
+            metadata: Arc::new(RwLock::new(MetadataCache::default())),
+            snapshot_path: Arc::new(RwLock::new(None)),
+            counters: Arc::new(HitCounters::default()),
         }
     }
 
@@ -64,22 +123,48 @@ impl ThemeCache {
             themes: Arc::new(RwLock::new(HashMap::new())),
             config: Arc::new(RwLock::new(config)),
             last_full_refresh: Arc::new(RwLock::new(None)),
+            metadata: Arc::new(RwLock::new(MetadataCache::default())),
+            snapshot_path: Arc::new(RwLock::new(None)),
+            counters: Arc::new(HitCounters::default()),
         }
     }
 
-    /// Get all cached themes
+    /// Get all cached themes, ordered by title so repeated reads (and pages) line up
     pub async fn get_themes(&self) -> Result<Vec<SysTheme>, String> {
         let themes = self.themes.read().await;
-        let cached_themes: Vec<SysTheme> =
+        let mut cached_themes: Vec<SysTheme> =
             themes.values().map(|cached| cached.theme.clone()).collect();
+        sort_by_title(&mut cached_themes);
 
         Ok(cached_themes)
     }
 
-    /// Get a specific theme by directory name
+    /// All cached themes if the cache is valid and not empty, counting a hit or a miss
+    pub async fn get_valid_themes(&self) -> Option<Vec<SysTheme>> {
+        let valid = self.is_cache_valid().await && !self.is_empty().await;
+        self.counters.record(valid);
+        if !valid {
+            return None;
+        }
+        self.get_themes().await.ok()
+    }
+
+    /// Get a specific theme by directory name, counting a hit or a miss
     pub async fn get_theme(&self, dir: &str) -> Option<SysTheme> {
         let themes = self.themes.read().await;
-        themes.get(dir).map(|cached| cached.theme.clone())
+        let theme = themes.get(dir).map(|cached| cached.theme.clone());
+        self.counters.record(theme.is_some());
+        theme
+    }
+
+    /// Lookup counts as (hits, misses) since startup or the last reset
+    pub fn hit_counts(&self) -> (u64, u64) {
+        self.counters.get()
+    }
+
+    /// Zero the lookup counts
+    pub fn reset_hit_counts(&self) {
+        self.counters.reset();
     }
 
     /// Cache a single theme
@@ -92,9 +177,16 @@ impl ThemeCache {
             // Remove oldest entry if at capacity
             if let Some(oldest_key) = self.find_oldest_entry(&themes).await {
                 themes.remove(&oldest_key);
+                self.metadata.write().await.entries.remove(&oldest_key);
             }
         }
 
+        self.metadata
+            .write()
+            .await
+            .entries
+            .insert(theme.dir.clone(), ThemeMetadata::from(&theme));
+
         let cached_theme = CachedTheme {
             theme: theme.clone(),
             cached_at: SystemTime::now(),
@@ -105,22 +197,75 @@ impl ThemeCache {
         Ok(())
     }
 
-    /// Cache multiple themes
+    /// Replace one theme's entry in place, or insert it. Other entries and the full refresh
+    /// timestamp are left alone, so editing a theme doesn't require rescanning the rest.
+    /// When a new theme has to evict another, the listing is no longer complete, so the next
+    /// listing rescans instead.
+    pub async fn update_cached_theme(&self, theme: SysTheme) {
+        let mut themes = self.themes.write().await;
+        let mut metadata = self.metadata.write().await;
+
+        if !themes.contains_key(&theme.dir)
+            && themes.len() >= self.config.read().await.max_cache_size
+        {
+            if let Some(oldest_key) = self.find_oldest_entry(&themes).await {
+                themes.remove(&oldest_key);
+                metadata.entries.remove(&oldest_key);
+                metadata.cached_at = None;
+                *self.last_full_refresh.write().await = None;
+            }
+        }
+
+        metadata
+            .entries
+            .insert(theme.dir.clone(), ThemeMetadata::from(&theme));
+        themes.insert(
+            theme.dir.clone(),
+            CachedTheme {
+                theme,
+                cached_at: SystemTime::now(),
+                metadata_only: false,
+            },
+        );
+    }
+
+    /// Cache multiple themes as a full refresh, then update the snapshot when persisting
     pub async fn cache_themes(
         &self,
         themes_list: Vec<SysTheme>,
         metadata_only: bool,
     ) -> Result<(), String> {
+        self.cache_themes_at(themes_list, metadata_only, SystemTime::now())
+            .await;
+
+        if let Err(e) = self.save_snapshot().await {
+            log::warn!("Failed to persist theme cache: {e}");
+        }
+        Ok(())
+    }
+
+    /// Cache themes as a full refresh that happened at `now`
+    async fn cache_themes_at(
+        &self,
+        themes_list: Vec<SysTheme>,
+        metadata_only: bool,
+        now: SystemTime,
+    ) {
         let mut themes = self.themes.write().await;
         let config = self.config.read().await;
-        let now = SystemTime::now();
+
+        let mut metadata = self.metadata.write().await;
 
         // Clear cache if we're at capacity and adding many themes
         if themes.len() + themes_list.len() > config.max_cache_size {
             themes.clear();
+            metadata.entries.clear();
         }
 
         for theme in themes_list {
+            metadata
+                .entries
+                .insert(theme.dir.clone(), ThemeMetadata::from(&theme));
             let cached_theme = CachedTheme {
                 theme: theme.clone(),
                 cached_at: now,
@@ -132,8 +277,98 @@ impl ThemeCache {
         // Update last full refresh timestamp
         let mut last_refresh = self.last_full_refresh.write().await;
         *last_refresh = Some(now);
+        metadata.cached_at = Some(now);
+    }
 
-        Ok(())
+    /// Persist full refreshes to `path` and trust it on startup, or stop with `None`
+    pub async fn set_persistence(&self, path: Option<PathBuf>) {
+        *self.snapshot_path.write().await = path;
+    }
+
+    /// Write the cached themes to the snapshot file, if persistence is enabled
+    pub async fn save_snapshot(&self) -> Result<(), String> {
+        let Some(path) = self.snapshot_path.read().await.clone() else {
+            return Ok(());
+        };
+        let Some(saved_at) = *self.last_full_refresh.read().await else {
+            return Ok(());
+        };
+
+        let mut themes = self.get_themes().await?;
+        themes.sort_by(|a, b| a.dir.cmp(&b.dir));
+        let content = serde_json::to_string(&ThemeCacheSnapshot { saved_at, themes })
+            .map_err(|e| format!("Failed to serialize theme cache: {e}"))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create theme cache directory: {e}"))?;
+        }
+        write_atomic(&path, content).map_err(|e| format!("Failed to write theme cache: {e}"))
+    }
+
+    /// Fill the cache from the snapshot file when it is younger than the cache duration.
+    /// Entries keep the snapshot's age, so they expire as if never restarted.
+    /// Returns whether the snapshot was used.
+    pub async fn load_snapshot(&self) -> Result<bool, String> {
+        let Some(path) = self.snapshot_path.read().await.clone() else {
+            return Ok(false);
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(format!("Failed to read theme cache: {e}")),
+        };
+        let snapshot: ThemeCacheSnapshot = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse theme cache: {e}"))?;
+
+        let cache_duration =
+            Duration::from_secs(self.config.read().await.cache_duration_minutes * 60);
+        // A snapshot from the future (clock change) is not trusted either
+        let fresh = SystemTime::now()
+            .duration_since(snapshot.saved_at)
+            .is_ok_and(|age| age < cache_duration);
+        if !fresh || snapshot.themes.is_empty() {
+            log::info!("Ignoring stale theme cache snapshot at {}", path.display());
+            return Ok(false);
+        }
+
+        let count = snapshot.themes.len();
+        self.cache_themes_at(snapshot.themes, false, snapshot.saved_at)
+            .await;
+        log::info!("Loaded {count} themes from {}", path.display());
+        Ok(true)
+    }
+
+    /// Cache metadata from a metadata-only scan
+    pub async fn cache_metadata(&self, metadata_list: Vec<ThemeMetadata>) {
+        let mut metadata = self.metadata.write().await;
+        metadata.entries = metadata_list
+            .into_iter()
+            .map(|meta| (meta.dir.clone(), meta))
+            .collect();
+        metadata.cached_at = Some(SystemTime::now());
+    }
+
+    /// Get cached theme metadata sorted by directory, or None if empty or expired
+    pub async fn get_metadata(&self) -> Option<Vec<ThemeMetadata>> {
+        let cache_duration =
+            Duration::from_secs(self.config.read().await.cache_duration_minutes * 60);
+        let metadata = self.metadata.read().await;
+        let cached_at = metadata.cached_at?;
+        if metadata.entries.is_empty() {
+            return None;
+        }
+
+        let elapsed = SystemTime::now()
+            .duration_since(cached_at)
+            .unwrap_or(Duration::from_secs(u64::MAX));
+        if elapsed >= cache_duration {
+            return None;
+        }
+
+        let mut entries: Vec<ThemeMetadata> = metadata.entries.values().cloned().collect();
+        entries.sort_by(|a, b| a.dir.cmp(&b.dir));
+        Some(entries)
     }
 
     /// Check if the cache is valid (not expired)
@@ -177,20 +412,40 @@ impl ThemeCache {
 
         themes.clear();
         *last_refresh = None;
+        *self.metadata.write().await = MetadataCache::default();
+
+        // The snapshot describes the dropped state, so it must not be reloaded either
+        if let Some(path) = self.snapshot_path.read().await.as_ref() {
+            if let Err(e) = fs::remove_file(path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("Failed to remove theme cache snapshot: {e}");
+                }
+            }
+        }
     }
 
-    /// Invalidate a specific theme
+    /// Invalidate a specific theme (by directory or display name) and its cached colors
     pub async fn invalidate_theme(&self, dir: &str) {
-        let mut themes = self.themes.write().await;
-        themes.remove(dir);
-        log::info!("Invalidated cache for theme: {dir}");
+        let key = theme_cache_key(dir);
+        self.themes.write().await.remove(&key);
+        self.metadata.write().await.entries.remove(&key);
+        invalidate_theme_colors(&key).await;
+        log::info!("Invalidated cache for theme: {key}");
     }
 
-    /// Invalidate multiple themes by directory names
+    /// Invalidate multiple themes by directory names, including their cached colors
     pub async fn invalidate_themes(&self, dirs: &[String]) {
-        let mut themes = self.themes.write().await;
-        for dir in dirs {
-            themes.remove(dir);
+        let keys: Vec<String> = dirs.iter().map(|dir| theme_cache_key(dir)).collect();
+        {
+            let mut themes = self.themes.write().await;
+            let mut metadata = self.metadata.write().await;
+            for key in &keys {
+                themes.remove(key);
+                metadata.entries.remove(key);
+            }
+        }
+        for key in &keys {
+            invalidate_theme_colors(key).await;
         }
         log::info!("Invalidated cache for {} themes", dirs.len());
     }
@@ -204,8 +459,10 @@ impl ThemeCache {
             .map(|(key, _)| key.clone())
             .collect();
 
+        let mut metadata = self.metadata.write().await;
         for key in &custom_theme_keys {
             themes.remove(key);
+            metadata.entries.remove(key);
         }
 
         log::info!(
@@ -223,8 +480,10 @@ impl ThemeCache {
             .map(|(key, _)| key.clone())
             .collect();
 
+        let mut metadata = self.metadata.write().await;
         for key in &system_theme_keys {
             themes.remove(key);
+            metadata.entries.remove(key);
         }
 
         log::info!(
@@ -233,15 +492,15 @@ impl ThemeCache {
         );
     }
 
-    /// Trigger background refresh after cache invalidation
+    /// Rescan the whole themes directory; reserved for explicit refreshes, single theme
+    /// edits go through `update_cached_theme`
     pub async fn trigger_background_refresh(&self) -> Result<Vec<SysTheme>, String> {
         log::info!("Triggering background cache refresh");
 
-        // Import the optimized theme loader
-        use crate::services::themes::optimized_theme_loader::OptimizedThemeLoader;
-
-        let loader = OptimizedThemeLoader::new();
-        let themes = loader.load_themes_parallel().await?;
+        // Reuse the global loader so its color and image caches survive the refresh
+        let themes = crate::services::themes::get_sys_themes::get_theme_loader()
+            .load_themes_parallel()
+            .await?;
 
         // Cache the refreshed themes
         self.cache_themes(themes.clone(), false).await?;
@@ -351,9 +610,13 @@ mod tests {
             title: title.to_string(),
             description: format!("Test theme {}", title),
             image: String::new(),
+            image_path: None,
             is_system: false,
             is_custom: false,
             colors: None,
+            is_dark: None,
+            symlink_ok: true,
+            modified_at: None,
         }
     }
 
@@ -476,6 +739,36 @@ mod tests {
         assert!(theme2_cached.is_some());
     }
 
+    #[tokio::test]
+    async fn test_metadata_cache_follows_full_cache() {
+        let cache = ThemeCache::new();
+        assert!(cache.get_metadata().await.is_none());
+
+        // Directories don't exist on disk, so a served result can't come from a rescan
+        let mut pictured = create_test_theme("meta-b", "Meta B");
+        pictured.image = "data:image/png;base64,AAAA".to_string();
+        let themes = vec![create_test_theme("meta-a", "Meta A"), pictured];
+        cache.cache_themes(themes, false).await.unwrap();
+
+        let metadata = cache
+            .get_metadata()
+            .await
+            .expect("metadata should be cached");
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata[0].dir, "meta-a");
+        assert_eq!(metadata[0].title, "Meta A");
+        assert!(!metadata[0].has_image);
+        assert!(metadata[1].has_image);
+
+        cache.invalidate_theme("meta-a").await;
+        let metadata = cache.get_metadata().await.unwrap();
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata[0].dir, "meta-b");
+
+        cache.invalidate().await;
+        assert!(cache.get_metadata().await.is_none());
+    }
+
     #[tokio::test]
     async fn test_cache_expiration() {
         let config = CacheConfig {
@@ -529,4 +822,136 @@ mod tests {
         assert_eq!(retrieved_config.background_refresh_interval, 30);
         assert_eq!(retrieved_config.max_cache_size, 200);
     }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip_and_staleness() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("cache/theme_cache.json");
+
+        let cache = ThemeCache::new();
+        cache.set_persistence(Some(path.clone())).await;
+        cache
+            .cache_themes(
+                vec![
+                    create_test_theme("nord", "Nord"),
+                    create_test_theme("ash", "Ash"),
+                ],
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(path.exists());
+
+        let restarted = ThemeCache::new();
+        restarted.set_persistence(Some(path.clone())).await;
+        assert!(restarted.load_snapshot().await.unwrap());
+        assert!(restarted.is_cache_valid().await);
+        let mut dirs: Vec<String> = restarted
+            .get_themes()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.dir)
+            .collect();
+        dirs.sort();
+        assert_eq!(dirs, ["ash", "nord"]);
+        assert_eq!(restarted.get_theme("nord").await.unwrap().title, "Nord");
+
+        // Older than the cache duration: ignored
+        let stale = ThemeCacheSnapshot {
+            saved_at: SystemTime::now() - Duration::from_secs(60 * 60),
+            themes: vec![create_test_theme("old", "Old")],
+        };
+        fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+        let cold = ThemeCache::new();
+        cold.set_persistence(Some(path.clone())).await;
+        assert!(!cold.load_snapshot().await.unwrap());
+        assert!(cold.is_empty().await);
+
+        // Invalidating drops the snapshot too
+        restarted.invalidate().await;
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_update_cached_theme_replaces_only_that_entry() {
+        let cache = ThemeCache::new();
+        cache
+            .cache_themes(
+                vec![
+                    create_test_theme("theme1", "Theme 1"),
+                    create_test_theme("theme2", "Theme 2"),
+                ],
+                false,
+            )
+            .await
+            .unwrap();
+        let refreshed_at = cache.get_cache_stats().await.last_refresh;
+        let untouched_at = cache.themes.read().await["theme2"].cached_at;
+
+        thread::sleep(StdDuration::from_millis(10));
+        cache
+            .update_cached_theme(create_test_theme("theme1", "Renamed"))
+            .await;
+        cache
+            .update_cached_theme(create_test_theme("theme3", "Theme 3"))
+            .await;
+
+        assert_eq!(cache.len().await, 3);
+        assert_eq!(cache.get_theme("theme1").await.unwrap().title, "Renamed");
+        assert_eq!(cache.get_theme("theme2").await.unwrap().title, "Theme 2");
+        assert_eq!(cache.themes.read().await["theme2"].cached_at, untouched_at);
+        assert_eq!(cache.get_cache_stats().await.last_refresh, refreshed_at);
+        assert!(cache.is_cache_valid().await);
+
+        let metadata = cache.get_metadata().await.unwrap();
+        assert_eq!(metadata.len(), 3);
+        assert_eq!(metadata[0].title, "Renamed");
+    }
+
+    #[tokio::test]
+    async fn test_update_cached_theme_eviction_forces_rescan() {
+        let cache = ThemeCache::with_config(CacheConfig {
+            max_cache_size: 2,
+            ..Default::default()
+        });
+        cache
+            .cache_themes(
+                vec![
+                    create_test_theme("theme1", "Theme 1"),
+                    create_test_theme("theme2", "Theme 2"),
+                ],
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(cache.get_valid_themes().await.is_some());
+
+        cache
+            .update_cached_theme(create_test_theme("theme3", "Theme 3"))
+            .await;
+
+        assert_eq!(cache.len().await, 2);
+        assert!(cache.get_valid_themes().await.is_none());
+        assert!(cache.get_metadata().await.is_none());
+        assert!(cache.get_theme("theme3").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_hit_counts() {
+        let cache = ThemeCache::new();
+        assert!(cache.get_valid_themes().await.is_none());
+        cache
+            .cache_themes(vec![create_test_theme("theme1", "Theme 1")], false)
+            .await
+            .unwrap();
+
+        assert_eq!(cache.get_valid_themes().await.unwrap().len(), 1);
+        assert!(cache.get_theme("theme1").await.is_some());
+        assert!(cache.get_theme("missing").await.is_none());
+        assert_eq!(cache.hit_counts(), (2, 2));
+
+        cache.reset_hit_counts();
+        assert_eq!(cache.hit_counts(), (0, 0));
+    }
 }
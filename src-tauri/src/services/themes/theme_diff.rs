@@ -0,0 +1,117 @@
+use super::color_extraction::ColorExtractor;
+use super::get_sys_themes::{get_sys_theme_by_name, theme_cache_key};
+use crate::services::cache::cache_manager::get_theme_cache;
+use crate::types::{ColorChange, ColorChangeKind, ThemeColors, ThemeDiff};
+
+/// Palette slots in display order, with their normalized colors
+fn palette_slots(colors: Option<&ThemeColors>) -> Vec<(&'static str, Option<String>)> {
+    let color = |pick: fn(&ThemeColors) -> Option<&String>| {
+        colors
+            .and_then(pick)
+            .map(|c| ColorExtractor::normalize_color(c).unwrap_or_else(|| c.to_lowercase()))
+    };
+    vec![
+        ("primary.background", color(|c| Some(&c.primary.background))),
+        ("primary.foreground", color(|c| Some(&c.primary.foreground))),
+        ("terminal.red", color(|c| Some(&c.terminal.red))),
+        ("terminal.green", color(|c| Some(&c.terminal.green))),
+        ("terminal.yellow", color(|c| Some(&c.terminal.yellow))),
+        ("terminal.blue", color(|c| Some(&c.terminal.blue))),
+        ("terminal.magenta", color(|c| Some(&c.terminal.magenta))),
+        ("terminal.cyan", color(|c| Some(&c.terminal.cyan))),
+        ("accent", color(|c| c.accent.as_ref())),
+    ]
+}
+
+/// Slots whose color differs between two palettes; a missing palette counts as every
+/// slot being added or removed
+pub fn diff_theme_colors(
+    before: Option<&ThemeColors>,
+    after: Option<&ThemeColors>,
+) -> Vec<ColorChange> {
+    palette_slots(before)
+        .into_iter()
+        .zip(palette_slots(after))
+        .filter_map(|((slot, before), (_, after))| {
+            let kind = match (&before, &after) {
+                (None, Some(_)) => ColorChangeKind::Added,
+                (Some(_), None) => ColorChangeKind::Removed,
+                (Some(a), Some(b)) if a != b => ColorChangeKind::Changed,
+                _ => return None,
+            };
+            Some(ColorChange {
+                slot: slot.to_string(),
+                kind,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+/// Extracted colors of a theme, from the theme cache when it is valid
+async fn load_theme_colors(name: &str) -> Result<Option<ThemeColors>, String> {
+    let key = theme_cache_key(name);
+    if let Ok(cache) = get_theme_cache().await {
+        if cache.is_cache_valid().await {
+            if let Some(theme) = cache.get_theme(&key).await {
+                return Ok(theme.colors);
+            }
+        }
+    }
+
+    get_sys_theme_by_name(key)
+        .await?
+        .map(|theme| theme.colors)
+        .ok_or_else(|| format!("Theme '{name}' not found"))
+}
+
+/// Compare the palettes of two themes
+pub async fn diff_themes(name_a: &str, name_b: &str) -> Result<ThemeDiff, String> {
+    let colors_a = load_theme_colors(name_a).await?;
+    let colors_b = load_theme_colors(name_b).await?;
+
+    Ok(ThemeDiff {
+        theme_a: name_a.to_string(),
+        theme_b: name_b.to_string(),
+        changes: diff_theme_colors(colors_a.as_ref(), colors_b.as_ref()),
+    })
+}
+
+/// Palette differences between two themes (read-only)
+#[tauri::command]
+pub async fn get_theme_diff(name_a: String, name_b: String) -> Result<ThemeDiff, String> {
+    diff_themes(&name_a, &name_b).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_theme_colors_reports_changed_and_missing_slots() {
+        let mut dark = ColorExtractor::get_fallback_colors();
+        dark.accent = Some("#7aa2f7".to_string());
+        let mut light = dark.clone();
+        light.primary.background = "#FAFAFA".to_string();
+        light.terminal.red = dark.terminal.red.to_uppercase();
+        light.accent = None;
+
+        // Case-only differences are not changes
+        let changes = diff_theme_colors(Some(&dark), Some(&light));
+        let slots: Vec<&str> = changes.iter().map(|c| c.slot.as_str()).collect();
+        assert_eq!(slots, ["primary.background", "accent"]);
+        assert_eq!(changes[0].kind, ColorChangeKind::Changed);
+        assert_eq!(changes[0].after.as_deref(), Some("#fafafa"));
+        assert_eq!(changes[1].kind, ColorChangeKind::Removed);
+
+        let removed = diff_theme_colors(Some(&light), None);
+        assert_eq!(removed.len(), 8);
+        assert!(removed.iter().all(|c| c.kind == ColorChangeKind::Removed));
+        assert!(removed.iter().all(|c| c.after.is_none()));
+
+        let added = diff_theme_colors(None, Some(&light));
+        assert!(added.iter().all(|c| c.kind == ColorChangeKind::Added));
+        assert!(diff_theme_colors(Some(&dark), Some(&dark)).is_empty());
+    }
+}
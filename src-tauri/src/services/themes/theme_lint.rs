@@ -0,0 +1,372 @@
+use super::color_extraction::ColorExtractor;
+use crate::types::{
+    ContrastWarning, LintFinding, LintSeverity, ThemeColors, ValidationError, ValidationSeverity,
+};
+use serde_json::Value;
+
+/// Background/foreground contrast below this is flagged
+const MIN_TEXT_CONTRAST: f64 = 3.0;
+
+/// WCAG AA minimum for normal text; colors below it are reported by `validate_theme_contrast`
+const WCAG_AA_CONTRAST: f64 = 4.5;
+
+/// Terminal color slots compared between the normal and bright palettes
+const TERMINAL_SLOTS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+fn finding(severity: LintSeverity, code: &str, message: String) -> LintFinding {
+    LintFinding {
+        severity,
+        code: code.to_string(),
+        message,
+    }
+}
+
+/// Normalized color at `path` below the theme's alacritty colors
+fn alacritty_color(apps: &Value, path: &[&str]) -> Option<String> {
+    let mut value = apps.get("alacritty")?.get("colors")?;
+    for key in path {
+        value = value.get(key)?;
+    }
+    ColorExtractor::normalize_color(value.as_str()?)
+}
+
+/// Flag likely palette mistakes in a theme's app data and extracted colors
+pub fn lint_palette(apps: &Value, colors: Option<&ThemeColors>) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+
+    let background = alacritty_color(apps, &["primary", "background"])
+        .or_else(|| colors.map(|c| c.primary.background.clone()));
+    let foreground = alacritty_color(apps, &["primary", "foreground"])
+        .or_else(|| colors.map(|c| c.primary.foreground.clone()));
+
+    if let (Some(background), Some(foreground)) = (&background, &foreground) {
+        if let Some(ratio) = ColorExtractor::contrast_ratio(background, foreground) {
+            if ratio < MIN_TEXT_CONTRAST {
+                findings.push(finding(
+                    LintSeverity::Warning,
+                    "low_contrast",
+                    format!(
+                        "Foreground {foreground} on background {background} has a contrast ratio of {ratio:.2}"
+                    ),
+                ));
+            }
+        }
+    }
+
+    for palette in ["normal", "bright"] {
+        let slots: Vec<(&str, String)> = TERMINAL_SLOTS
+            .iter()
+            .filter_map(|slot| Some((*slot, alacritty_color(apps, &[palette, slot])?)))
+            .collect();
+        for (i, (slot, color)) in slots.iter().enumerate() {
+            if let Some((other, _)) = slots[i + 1..].iter().find(|(_, c)| c == color) {
+                findings.push(finding(
+                    LintSeverity::Warning,
+                    "duplicate_color",
+                    format!("{palette} {slot} and {palette} {other} are both {color}"),
+                ));
+            }
+        }
+    }
+
+    for slot in TERMINAL_SLOTS {
+        let (Some(normal), Some(bright)) = (
+            alacritty_color(apps, &["normal", slot]),
+            alacritty_color(apps, &["bright", slot]),
+        ) else {
+            continue;
+        };
+        let (Some(normal_lum), Some(bright_lum)) = (
+            ColorExtractor::relative_luminance(&normal),
+            ColorExtractor::relative_luminance(&bright),
+        ) else {
+            continue;
+        };
+        if bright_lum < normal_lum {
+            findings.push(finding(
+                LintSeverity::Info,
+                "bright_darker_than_normal",
+                format!("bright {slot} ({bright}) is darker than normal {slot} ({normal})"),
+            ));
+        }
+    }
+
+    let accent =
+        alacritty_color(apps, &["accent"]).or_else(|| colors.and_then(|c| c.accent.clone()));
+    if let (Some(accent), Some(background)) = (accent, background) {
+        if accent == background {
+            findings.push(finding(
+                LintSeverity::Warning,
+                "accent_matches_background",
+                format!("Accent {accent} is the same as the background"),
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Check the primary foreground and each terminal color against the background of a
+/// theme's alacritty colors, reporting every pair below the WCAG AA ratio
+pub fn validate_theme_contrast(theme_data: &Value) -> Vec<ContrastWarning> {
+    let Some(background) = alacritty_color(theme_data, &["primary", "background"]) else {
+        return Vec::new();
+    };
+
+    let foreground = alacritty_color(theme_data, &["primary", "foreground"])
+        .map(|color| ("foreground".to_string(), color));
+    let terminal = TERMINAL_SLOTS[1..7].iter().filter_map(|slot| {
+        alacritty_color(theme_data, &["normal", slot]).map(|color| (slot.to_string(), color))
+    });
+
+    foreground
+        .into_iter()
+        .chain(terminal)
+        .filter_map(|(element, color)| {
+            let ratio = ColorExtractor::contrast_ratio(&color, &background)?;
+            (ratio < WCAG_AA_CONTRAST).then(|| ContrastWarning {
+                element,
+                color,
+                background: background.clone(),
+                ratio,
+            })
+        })
+        .collect()
+}
+
+/// Top-level theme_data keys that hold theme-wide data rather than an app's settings
+pub const NON_APP_KEYS: [&str; 3] = ["meta", "files", "accent"];
+
+/// Whether a color field value is usable: empty (unset), a CSS-style color, or hex digits
+/// with an optional '#' and alpha
+fn looks_like_color(value: &str) -> bool {
+    let value = value.trim();
+    if value.is_empty() || ColorExtractor::normalize_color(value).is_some() {
+        return true;
+    }
+    let digits = value.strip_prefix('#').unwrap_or(value);
+    matches!(digits.len(), 3 | 4 | 6 | 8) && digits.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Whether `value` has the JSON schema `type`
+fn matches_schema_type(value: &Value, schema_type: &str) -> bool {
+    match schema_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        _ => true,
+    }
+}
+
+/// Check one value against its schema, recursing into object properties.
+/// `null` is always accepted, since it deletes the key on update.
+fn validate_value(
+    app: &str,
+    field: &str,
+    schema: &Value,
+    value: &Value,
+    errors: &mut Vec<ValidationError>,
+) {
+    if value.is_null() {
+        return;
+    }
+    let mut error = |message: String| {
+        errors.push(ValidationError {
+            app: app.to_string(),
+            field: field.to_string(),
+            severity: ValidationSeverity::Error,
+            message,
+        })
+    };
+
+    if let Some(schema_type) = schema.get("type").and_then(Value::as_str) {
+        if !matches_schema_type(value, schema_type) {
+            error(format!("Expected {schema_type}"));
+            return;
+        }
+    }
+    if schema.get("format").and_then(Value::as_str) == Some("color") {
+        if let Some(color) = value.as_str().filter(|color| !looks_like_color(color)) {
+            error(format!("'{color}' is not a valid hex color"));
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            error(format!("'{value}' is not one of the allowed values"));
+        }
+    }
+
+    let Some(object) = value.as_object() else {
+        return;
+    };
+    for required in schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter(|key| !object.contains_key(*key))
+    {
+        errors.push(ValidationError {
+            app: app.to_string(),
+            field: join_field(field, required),
+            severity: ValidationSeverity::Error,
+            message: "Required field is missing".to_string(),
+        });
+    }
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (key, child) in object {
+            if let Some(child_schema) = properties.get(key) {
+                validate_value(app, &join_field(field, key), child_schema, child, errors);
+            }
+        }
+    }
+}
+
+fn join_field(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+/// Check an app's theme data against its generator schema: types, required fields,
+/// allowed values and color formats. Fields the schema doesn't describe are ignored.
+pub fn validate_app_data(app: &str, schema: &Value, data: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    validate_value(app, "", schema, data, &mut errors);
+    errors
+}
+
+#[tauri::command]
+pub async fn check_theme_contrast(theme_data: Value) -> Result<Vec<ContrastWarning>, String> {
+    Ok(validate_theme_contrast(&theme_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_duplicate_red_is_flagged() {
+        let apps = json!({
+            "alacritty": {
+                "colors": {
+                    "primary": { "background": "#1e1e2e", "foreground": "#cdd6f4" },
+                    "normal": {
+                        "red": "#f38ba8", "green": "#a6e3a1", "yellow": "#f9e2af",
+                        "blue": "#89b4fa", "magenta": "#F38BA8", "cyan": "#94e2d5"
+                    }
+                }
+            }
+        });
+
+        let findings = lint_palette(&apps, None);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].code, "duplicate_color");
+        assert_eq!(findings[0].severity, LintSeverity::Warning);
+        assert!(findings[0].message.contains("red"));
+        assert!(findings[0].message.contains("magenta"));
+    }
+
+    #[test]
+    fn test_contrast_bright_and_accent_findings() {
+        let apps = json!({
+            "alacritty": {
+                "colors": {
+                    "primary": { "background": "#202020", "foreground": "#303030" },
+                    "normal": { "blue": "#89b4fa" },
+                    "bright": { "blue": "#1e3a8a" },
+                    "accent": "#202020"
+                }
+            }
+        });
+
+        let codes: Vec<String> = lint_palette(&apps, None)
+            .into_iter()
+            .map(|f| f.code)
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                "low_contrast",
+                "bright_darker_than_normal",
+                "accent_matches_background"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contrast_warnings_for_unreadable_colors() {
+        let theme_data = json!({
+            "alacritty": {
+                "colors": {
+                    "primary": { "background": "1e1e2e", "foreground": "#CDD6F4" },
+                    "normal": {
+                        "red": "#f38ba8", "green": "#a6e3a1", "yellow": "#f9e2af",
+                        "blue": "#000080", "magenta": "#f5c2e7", "cyan": "#333"
+                    }
+                }
+            }
+        });
+
+        let warnings = validate_theme_contrast(&theme_data);
+        let elements: Vec<&str> = warnings.iter().map(|w| w.element.as_str()).collect();
+        assert_eq!(elements, ["blue", "cyan"]);
+        assert!(warnings.iter().all(|w| w.ratio < 4.5));
+        assert_eq!(warnings[1].color, "#333");
+        assert_eq!(warnings[1].background, "#1e1e2e");
+
+        assert!(validate_theme_contrast(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_validate_app_data_reports_fields() {
+        let schema = json!({
+            "type": "object",
+            "required": ["colors"],
+            "properties": {
+                "colors": {
+                    "type": "object",
+                    "required": ["background"],
+                    "properties": {
+                        "background": { "type": "string", "format": "color" },
+                        "text": { "type": "string", "format": "color" },
+                        "alpha": { "type": "string", "format": "color" }
+                    }
+                },
+                "position": { "type": "string", "enum": ["top", "bottom"] },
+                "width": { "type": "integer" }
+            }
+        });
+
+        let errors = validate_app_data(
+            "waybar",
+            &schema,
+            &json!({
+                "colors": { "text": "blue-ish", "alpha": "EAEAEA88", "unknown": 5 },
+                "position": "left",
+                "width": "wide"
+            }),
+        );
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["colors.background", "colors.text", "position", "width"]
+        );
+        assert!(errors
+            .iter()
+            .all(|e| e.app == "waybar" && e.severity == ValidationSeverity::Error));
+
+        let valid = json!({ "colors": { "background": "#1a1b26", "text": null } });
+        assert!(validate_app_data("waybar", &schema, &valid).is_empty());
+        assert_eq!(validate_app_data("waybar", &schema, &json!({})).len(), 1);
+    }
+}
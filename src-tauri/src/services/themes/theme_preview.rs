@@ -0,0 +1,322 @@
+use crate::services::util::base64;
+use crate::types::ThemeColors;
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+use regex::Regex;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// Width of rendered theme previews
+pub const PREVIEW_WIDTH: u32 = 320;
+/// Height of rendered theme previews
+pub const PREVIEW_HEIGHT: u32 = 180;
+/// Width of a standalone palette swatch image
+pub const PALETTE_SWATCH_WIDTH: u32 = 200;
+/// Height of a standalone palette swatch image
+pub const PALETTE_SWATCH_HEIGHT: u32 = 40;
+/// Height of the swatch strip overlaid on a wallpaper preview
+const SWATCH_STRIP_HEIGHT: u32 = 36;
+/// Blur applied to the wallpaper behind the swatches
+const WALLPAPER_BLUR_SIGMA: f32 = 4.0;
+/// Largest image file inlined as a data URL as-is; bigger files are downscaled or refused
+pub const MAX_DATA_URL_IMAGE_BYTES: u64 = 8 * 1024 * 1024;
+/// Longest side of the stand-in preview rendered for an oversized image
+const OVERSIZED_PREVIEW_DIMENSION: u32 = 1280;
+/// Directory inside `backgrounds/` holding cached thumbnails
+pub const THUMBNAILS_DIR: &str = ".thumbnails";
+
+/// Decode an image file of any supported format
+pub fn decode_image(path: &Path) -> Result<image::DynamicImage, String> {
+    image::ImageReader::open(path)
+        .map_err(|e| format!("Failed to open image {}: {e}", path.display()))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image {}: {e}", path.display()))?
+        .decode()
+        .map_err(|e| format!("Failed to decode image {}: {e}", path.display()))
+}
+
+/// Palette colors shown as swatches, in display order
+pub fn swatch_colors(colors: &ThemeColors) -> Vec<Rgba<u8>> {
+    [
+        Some(&colors.primary.background),
+        Some(&colors.primary.foreground),
+        Some(&colors.terminal.red),
+        Some(&colors.terminal.green),
+        Some(&colors.terminal.yellow),
+        Some(&colors.terminal.blue),
+        Some(&colors.terminal.magenta),
+        Some(&colors.terminal.cyan),
+        colors.accent.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|hex| {
+        let (r, g, b) = super::ColorExtractor::hex_to_rgb(hex)?;
+        Some(Rgba([r, g, b, 255]))
+    })
+    .collect()
+}
+
+/// Render the palette as equal-width vertical bands
+pub fn render_swatch_strip(colors: &ThemeColors, width: u32, height: u32) -> RgbaImage {
+    let swatches = swatch_colors(colors);
+    let mut strip = RgbaImage::new(width, height);
+    if swatches.is_empty() {
+        return strip;
+    }
+
+    for (x, _, pixel) in strip.enumerate_pixels_mut() {
+        let index = (x as usize * swatches.len()) / width as usize;
+        *pixel = swatches[index.min(swatches.len() - 1)];
+    }
+    strip
+}
+
+/// Composite the palette swatch strip onto a blurred crop of `background`.
+/// Without a background the plain swatch strip fills the whole preview.
+pub fn render_wallpaper_preview(
+    background: Option<&Path>,
+    colors: &ThemeColors,
+) -> Result<RgbaImage, String> {
+    let Some(background) = background else {
+        return Ok(render_swatch_strip(colors, PREVIEW_WIDTH, PREVIEW_HEIGHT));
+    };
+
+    let wallpaper = decode_image(background)?
+        .resize_to_fill(PREVIEW_WIDTH, PREVIEW_HEIGHT, FilterType::Triangle)
+        .blur(WALLPAPER_BLUR_SIGMA);
+    let mut preview = wallpaper.to_rgba8();
+
+    let strip = render_swatch_strip(colors, PREVIEW_WIDTH, SWATCH_STRIP_HEIGHT);
+    image::imageops::overlay(
+        &mut preview,
+        &strip,
+        0,
+        i64::from(PREVIEW_HEIGHT - SWATCH_STRIP_HEIGHT),
+    );
+    Ok(preview)
+}
+
+/// Downscale an image so its longest side is at most `max_dimension`, encoded as JPEG
+pub fn render_thumbnail(path: &Path, max_dimension: u32) -> Result<Vec<u8>, String> {
+    let image = decode_image(path)?;
+    let image = if image.width().max(image.height()) > max_dimension {
+        image.resize(max_dimension, max_dimension, FilterType::Triangle)
+    } else {
+        image
+    };
+    encode_image(&image, image::ImageFormat::Jpeg)
+}
+
+/// Read an image file for inlining, refusing files over `MAX_DATA_URL_IMAGE_BYTES` rather
+/// than holding them (and their base64 copy) in memory
+pub fn read_image_capped(path: &Path) -> Result<Vec<u8>, String> {
+    let file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open image {}: {e}", path.display()))?;
+    let mut bytes = Vec::new();
+    // Read one byte past the cap so a file that grew since it was listed is still caught
+    file.take(MAX_DATA_URL_IMAGE_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read image {}: {e}", path.display()))?;
+    if bytes.len() as u64 > MAX_DATA_URL_IMAGE_BYTES {
+        return Err(format!(
+            "Image {} is larger than {} MB; load it with get_background_thumbnail instead",
+            path.display(),
+            MAX_DATA_URL_IMAGE_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Encode an image file as a base64 data URL for a theme preview. Files over
+/// `MAX_DATA_URL_IMAGE_BYTES` are downscaled to a JPEG first instead of being inlined whole.
+pub fn preview_data_url(path: &Path, mime_type: &str) -> Result<String, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Failed to read image file {}: {e}", path.display()))?
+        .len();
+    if size > MAX_DATA_URL_IMAGE_BYTES {
+        log::info!(
+            "Downscaling {} ({size} bytes) for its preview",
+            path.display()
+        );
+        let thumbnail = render_thumbnail(path, OVERSIZED_PREVIEW_DIMENSION)?;
+        return Ok(format!(
+            "data:image/jpeg;base64,{}",
+            base64::encode(&thumbnail)
+        ));
+    }
+
+    let bytes = read_image_capped(path)?;
+    Ok(format!(
+        "data:{mime_type};base64,{}",
+        base64::encode(&bytes)
+    ))
+}
+
+/// Embed an SVG file as a URL-encoded `data:image/svg+xml,` URL, which is smaller than base64
+/// for text. `<script>` elements are stripped first.
+pub fn svg_data_url(path: &Path) -> Result<String, String> {
+    let bytes = read_image_capped(path)?;
+    let source = String::from_utf8(bytes)
+        .map_err(|_| format!("SVG file {} is not valid UTF-8", path.display()))?;
+    Ok(format!(
+        "data:image/svg+xml,{}",
+        percent_encode(&strip_svg_scripts(&source))
+    ))
+}
+
+/// Remove `<script>` elements (paired or self-closing) from SVG source
+fn strip_svg_scripts(source: &str) -> String {
+    let scripts =
+        Regex::new(r"(?is)<script\b[^>]*?/>|<script\b.*?</script\s*>").expect("valid regex");
+    scripts.replace_all(source, "").into_owned()
+}
+
+/// Percent-encode everything but characters that are safe unescaped in a data URL
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~!*'():@=+$,/;?&".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Pixel dimensions of an image, read from its header without decoding it
+pub fn image_dimensions(path: &Path) -> Result<(u32, u32), String> {
+    image::ImageReader::open(path)
+        .map_err(|e| format!("Failed to open image {}: {e}", path.display()))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image {}: {e}", path.display()))?
+        .into_dimensions()
+        .map_err(|e| format!("Failed to read image size of {}: {e}", path.display()))
+}
+
+/// Whether an image file is an animated GIF (more than one frame) or WebP (`ANIM` flag),
+/// judged from its container structure without decoding any frames
+pub fn is_animated(path: &Path) -> bool {
+    let Ok(bytes) = fs::read(path) else {
+        return false;
+    };
+    if bytes.starts_with(b"GIF8") {
+        gif_frame_count(&bytes) > 1
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        webp_is_animated(&bytes)
+    } else {
+        false
+    }
+}
+
+/// Count GIF image descriptors, stopping early once a second frame is seen
+fn gif_frame_count(bytes: &[u8]) -> usize {
+    // Skip the header and logical screen descriptor, plus the global color table if any
+    let Some(&flags) = bytes.get(10) else {
+        return 0;
+    };
+    let mut pos = 13;
+    if flags & 0x80 != 0 {
+        pos += 3 << ((flags & 0x07) + 1);
+    }
+
+    // Data sub-blocks are length-prefixed and end with a zero length
+    let skip_sub_blocks = |mut pos: usize| -> Option<usize> {
+        loop {
+            let len = *bytes.get(pos)? as usize;
+            pos += 1 + len;
+            if len == 0 {
+                return Some(pos);
+            }
+        }
+    };
+
+    let mut frames = 0;
+    while frames < 2 {
+        match bytes.get(pos) {
+            // Extension: introducer, label, then sub-blocks
+            Some(0x21) => match skip_sub_blocks(pos + 2) {
+                Some(next) => pos = next,
+                None => break,
+            },
+            // Image descriptor, optional local color table, LZW code size, then sub-blocks
+            Some(0x2C) => {
+                frames += 1;
+                let Some(&local_flags) = bytes.get(pos + 9) else {
+                    break;
+                };
+                pos += 10;
+                if local_flags & 0x80 != 0 {
+                    pos += 3 << ((local_flags & 0x07) + 1);
+                }
+                match skip_sub_blocks(pos + 1) {
+                    Some(next) => pos = next,
+                    None => break,
+                }
+            },
+            _ => break,
+        }
+    }
+    frames
+}
+
+/// Whether a WebP file's extended header has the animation flag or it has an `ANIM` chunk
+fn webp_is_animated(bytes: &[u8]) -> bool {
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let fourcc = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes([
+            bytes[pos + 4],
+            bytes[pos + 5],
+            bytes[pos + 6],
+            bytes[pos + 7],
+        ]) as usize;
+        match fourcc {
+            b"VP8X" if bytes.get(pos + 8).is_some_and(|flags| flags & 0x02 != 0) => return true,
+            b"ANIM" | b"ANMF" => return true,
+            _ => {},
+        }
+        // Chunks are padded to an even size
+        pos += 8 + size + (size & 1);
+    }
+    false
+}
+
+/// Image format for a background file extension we can write
+pub fn background_format(extension: &str) -> Option<image::ImageFormat> {
+    match extension.to_lowercase().as_str() {
+        "png" => Some(image::ImageFormat::Png),
+        "jpg" | "jpeg" => Some(image::ImageFormat::Jpeg),
+        "webp" => Some(image::ImageFormat::WebP),
+        "bmp" => Some(image::ImageFormat::Bmp),
+        "gif" => Some(image::ImageFormat::Gif),
+        _ => None,
+    }
+}
+
+/// Encode an image in `format`, dropping alpha where the format has none
+pub fn encode_image(
+    image: &image::DynamicImage,
+    format: image::ImageFormat,
+) -> Result<Vec<u8>, String> {
+    let image = match format {
+        image::ImageFormat::Jpeg => image::DynamicImage::ImageRgb8(image.to_rgb8()),
+        _ => image::DynamicImage::ImageRgba8(image.to_rgba8()),
+    };
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), format)
+        .map_err(|e| format!("Failed to encode {format:?}: {e}"))?;
+    Ok(bytes)
+}
+
+/// Encode an image as PNG bytes
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+    Ok(bytes)
+}
@@ -0,0 +1,192 @@
+use super::get_sys_themes::{get_sys_themes, theme_cache_key_in, SysTheme};
+use super::themes_dir::themes_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// A theme together with the disk space its directory uses
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SysThemeWithSize {
+    #[serde(flatten)]
+    pub theme: SysTheme,
+    pub size_bytes: u64,
+}
+
+/// Theme directory sizes, remembered until the directory changes
+#[derive(Default)]
+pub struct ThemeSizeCache {
+    entries: Mutex<HashMap<PathBuf, (SystemTime, u64)>>,
+}
+
+impl ThemeSizeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total size of the files in `theme_dir`, walked again only when its mtime moves
+    pub fn size_of(&self, theme_dir: &Path) -> Result<u64, String> {
+        let stamp = change_stamp(theme_dir)?;
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(&(cached_stamp, size)) = entries.get(theme_dir) {
+            if cached_stamp == stamp {
+                return Ok(size);
+            }
+        }
+
+        let size = dir_size(theme_dir);
+        entries.insert(theme_dir.to_path_buf(), (stamp, size));
+        Ok(size)
+    }
+
+    /// Number of theme directories with a remembered size
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Latest mtime of a theme directory and its direct subdirectories, since adding a
+/// background only touches `backgrounds/`
+fn change_stamp(theme_dir: &Path) -> Result<SystemTime, String> {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+    let mut stamp = modified(theme_dir).map_err(|e| {
+        format!(
+            "Failed to read theme directory {}: {e}",
+            theme_dir.display()
+        )
+    })?;
+
+    if let Ok(entries) = fs::read_dir(theme_dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                if let Ok(subdir_modified) = modified(&entry.path()) {
+                    stamp = stamp.max(subdir_modified);
+                }
+            }
+        }
+    }
+    Ok(stamp)
+}
+
+/// Sum the sizes of the files under `dir`. `dir` itself may be a symlink (system themes
+/// link into the omarchy install), but links inside it are not followed so files a theme
+/// only points at are not counted twice.
+pub fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => {
+                entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+            },
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Global cache shared by the size commands
+static THEME_SIZES: OnceLock<ThemeSizeCache> = OnceLock::new();
+
+fn get_theme_size_cache() -> &'static ThemeSizeCache {
+    THEME_SIZES.get_or_init(ThemeSizeCache::new)
+}
+
+/// Disk space used by one theme (by directory or display name), in bytes
+#[tauri::command]
+pub async fn get_theme_size(name: String) -> Result<u64, String> {
+    let themes_dir = themes_dir()?;
+    let dir = theme_cache_key_in(&themes_dir, &name);
+    if dir.is_empty() || dir.contains(['/', '\\']) {
+        return Err(format!("Invalid theme directory '{dir}'"));
+    }
+
+    let theme_dir = themes_dir.join(dir);
+    tokio::task::spawn_blocking(move || get_theme_size_cache().size_of(&theme_dir))
+        .await
+        .map_err(|e| format!("Theme size task failed: {e}"))?
+}
+
+/// Every theme along with the disk space its directory uses
+#[tauri::command]
+pub async fn get_themes_with_sizes() -> Result<Vec<SysThemeWithSize>, String> {
+    let themes = get_sys_themes().await?;
+    let themes_dir = themes_dir()?;
+
+    tokio::task::spawn_blocking(move || {
+        themes
+            .into_iter()
+            .map(|theme| {
+                let size_bytes = get_theme_size_cache()
+                    .size_of(&themes_dir.join(&theme.dir))
+                    .unwrap_or_else(|e| {
+                        log::warn!("Failed to measure theme '{}': {e}", theme.dir);
+                        0
+                    });
+                SysThemeWithSize { theme, size_bytes }
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Theme size task failed: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_theme_size_skips_inner_symlinks_and_tracks_changes() {
+        let temp = TempDir::new().unwrap();
+        let shared = temp.path().join("shared.png");
+        fs::write(&shared, vec![0u8; 4096]).unwrap();
+
+        let theme_dir = temp.path().join("themes").join("sized");
+        fs::create_dir_all(theme_dir.join("backgrounds")).unwrap();
+        fs::write(theme_dir.join("alacritty.toml"), vec![b'x'; 100]).unwrap();
+        fs::write(
+            theme_dir.join("backgrounds").join("wall.png"),
+            vec![0u8; 1000],
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&shared, theme_dir.join("backgrounds").join("linked.png"))
+            .unwrap();
+
+        // A system theme is a symlink to its directory, which is still measured
+        let linked_theme = temp.path().join("themes").join("linked");
+        std::os::unix::fs::symlink(&theme_dir, &linked_theme).unwrap();
+
+        let cache = ThemeSizeCache::new();
+        assert_eq!(cache.size_of(&theme_dir).unwrap(), 1100);
+        assert_eq!(cache.size_of(&linked_theme).unwrap(), 1100);
+        assert_eq!(cache.len(), 2);
+
+        // Adding a background bumps the subdirectory mtime, so the size is walked again
+        let backgrounds = theme_dir.join("backgrounds");
+        fs::write(backgrounds.join("more.png"), vec![0u8; 500]).unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::open(&backgrounds)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(cache.size_of(&theme_dir).unwrap(), 1600);
+
+        assert!(cache.size_of(&temp.path().join("missing")).is_err());
+    }
+}
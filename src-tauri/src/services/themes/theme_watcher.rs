@@ -0,0 +1,362 @@
+use super::get_sys_themes::{
+    extract_theme_colors, invalidate_theme_colors, reload_cached_theme_dir, COLOR_SOURCE_FILES,
+};
+use crate::types::ThemeColors;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// Quiet period after the last filesystem event before the cache is invalidated
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The running watcher; dropping it closes the event channel and ends the debounce task
+static THEME_WATCHER: Mutex<Option<RecommendedWatcher>> = Mutex::new(None);
+
+/// Per-theme color watchers started by `watch_theme`, keyed by theme directory name
+static THEME_COLOR_WATCHERS: Mutex<Option<HashMap<String, RecommendedWatcher>>> = Mutex::new(None);
+
+/// Payload of the `theme-colors-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeColorsChanged {
+    pub theme: String,
+    pub colors: Option<ThemeColors>,
+}
+
+/// Theme directory name (first component under `themes_dir`) that a changed path belongs to;
+/// `None` inside dot-prefixed staging directories
+fn theme_dir_for(themes_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(themes_dir).ok()?;
+    let first = relative.components().next()?;
+    let dir = first.as_os_str().to_string_lossy().into_owned();
+    // Dot directories are staging areas of creates, imports and overwrites
+    (!dir.starts_with('.')).then_some(dir)
+}
+
+/// Wait for the next burst of events and collect the paths it touched, once `debounce`
+/// passes without another event. Returns `None` once the watcher is gone and no events
+/// are pending.
+async fn next_paths_burst(
+    events: &mut UnboundedReceiver<PathBuf>,
+    debounce: Duration,
+) -> Option<Vec<PathBuf>> {
+    let mut paths = vec![events.recv().await?];
+    while let Ok(Some(path)) = tokio::time::timeout(debounce, events.recv()).await {
+        paths.push(path);
+    }
+    Some(paths)
+}
+
+/// Wait for the next burst of events and collect the theme directories it touched
+async fn next_burst(
+    events: &mut UnboundedReceiver<PathBuf>,
+    themes_dir: &Path,
+    debounce: Duration,
+) -> Option<HashSet<String>> {
+    let paths = next_paths_burst(events, debounce).await?;
+    Some(
+        paths
+            .iter()
+            .filter_map(|path| theme_dir_for(themes_dir, path))
+            .collect(),
+    )
+}
+
+/// Create a watcher that forwards the paths of created, modified and removed files
+fn forwarding_watcher(sender: UnboundedSender<PathBuf>) -> Result<RecommendedWatcher, String> {
+    notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+        Ok(event) => {
+            if matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                for path in event.paths {
+                    let _ = sender.send(path);
+                }
+            }
+        },
+        Err(e) => log::warn!("Theme watcher error: {e}"),
+    })
+    .map_err(|e| format!("Failed to create theme watcher: {e}"))
+}
+
+/// Invalidate changed themes after each burst of events, then refresh the cache once
+async fn run_debouncer(mut events: UnboundedReceiver<PathBuf>, themes_dir: PathBuf) {
+    while let Some(dirs) = next_burst(&mut events, &themes_dir, DEBOUNCE).await {
+        if dirs.is_empty() {
+            continue;
+        }
+        log::info!("Theme files changed on disk: {dirs:?}");
+        // Only the touched themes are reloaded; a full rescan is left to explicit refreshes
+        for dir in &dirs {
+            reload_cached_theme_dir(dir).await;
+        }
+    }
+    log::info!("Theme watcher stopped");
+}
+
+/// Start watching `themes_dir` for changes. Returns false if a watcher is already running.
+pub fn start_watching(themes_dir: &Path) -> Result<bool, String> {
+    let mut current = THEME_WATCHER
+        .lock()
+        .map_err(|e| format!("Failed to lock theme watcher: {e}"))?;
+    if current.is_some() {
+        return Ok(false);
+    }
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut watcher = forwarding_watcher(sender)?;
+    watcher
+        .watch(themes_dir, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {e}", themes_dir.display()))?;
+
+    tauri::async_runtime::spawn(run_debouncer(receiver, themes_dir.to_path_buf()));
+    *current = Some(watcher);
+    log::info!("Watching {} for theme changes", themes_dir.display());
+    Ok(true)
+}
+
+/// Stop the running watcher. Returns false if none was running.
+pub fn stop_watching() -> Result<bool, String> {
+    let mut current = THEME_WATCHER
+        .lock()
+        .map_err(|e| format!("Failed to lock theme watcher: {e}"))?;
+    Ok(current.take().is_some())
+}
+
+/// Re-extract a theme's colors after each burst of changes to the files they come from
+async fn run_color_watch<F>(
+    mut events: UnboundedReceiver<PathBuf>,
+    theme_dir: PathBuf,
+    dir: String,
+    debounce: Duration,
+    on_change: F,
+) where
+    F: Fn(ThemeColorsChanged) + Send + 'static,
+{
+    while let Some(paths) = next_paths_burst(&mut events, debounce).await {
+        let is_color_source = |path: &PathBuf| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| COLOR_SOURCE_FILES.contains(&name))
+        };
+        if !paths.iter().any(is_color_source) {
+            continue;
+        }
+
+        invalidate_theme_colors(&dir).await;
+        let is_custom = theme_dir.join("custom_theme.json").is_file();
+        let colors = extract_theme_colors(&theme_dir, is_custom);
+        on_change(ThemeColorsChanged {
+            theme: dir.clone(),
+            colors,
+        });
+    }
+    log::info!("Stopped watching colors of theme '{dir}'");
+}
+
+/// Watch one theme directory, calling `on_change` with re-extracted colors after each
+/// debounced burst of edits to its color files. Returns false if it is already watched.
+fn start_color_watch<F>(
+    theme_dir: &Path,
+    dir: &str,
+    debounce: Duration,
+    on_change: F,
+) -> Result<bool, String>
+where
+    F: Fn(ThemeColorsChanged) + Send + 'static,
+{
+    let mut watchers = THEME_COLOR_WATCHERS
+        .lock()
+        .map_err(|e| format!("Failed to lock theme color watchers: {e}"))?;
+    let watchers = watchers.get_or_insert_with(HashMap::new);
+    if watchers.contains_key(dir) {
+        return Ok(false);
+    }
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut watcher = forwarding_watcher(sender)?;
+    // Color files sit at the top of the theme directory
+    watcher
+        .watch(theme_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {e}", theme_dir.display()))?;
+
+    tauri::async_runtime::spawn(run_color_watch(
+        receiver,
+        theme_dir.to_path_buf(),
+        dir.to_string(),
+        debounce,
+        on_change,
+    ));
+    watchers.insert(dir.to_string(), watcher);
+    log::info!("Watching colors of theme '{dir}'");
+    Ok(true)
+}
+
+/// Stop watching a theme's colors. Returns false if it wasn't watched.
+fn stop_color_watch(dir: &str) -> Result<bool, String> {
+    let mut watchers = THEME_COLOR_WATCHERS
+        .lock()
+        .map_err(|e| format!("Failed to lock theme color watchers: {e}"))?;
+    Ok(watchers
+        .as_mut()
+        .is_some_and(|watchers| watchers.remove(dir).is_some()))
+}
+
+/// Start the theme directory watcher (no-op if it is already running)
+#[tauri::command]
+pub async fn start_theme_watcher() -> Result<(), String> {
+    start_watching(&super::themes_dir::themes_dir()?)?;
+    Ok(())
+}
+
+/// Stop the theme directory watcher (no-op if it is not running)
+#[tauri::command]
+pub async fn stop_theme_watcher() -> Result<(), String> {
+    stop_watching()?;
+    Ok(())
+}
+
+/// Emit `theme-colors-changed` with freshly extracted colors whenever the theme's color
+/// files change (no-op if the theme is already watched)
+#[tauri::command]
+pub async fn watch_theme(app_handle: AppHandle, name: String) -> Result<(), String> {
+    let themes_dir = super::themes_dir::themes_dir()?;
+    let dir = super::get_sys_themes::theme_cache_key_in(&themes_dir, &name);
+    let theme_dir = themes_dir.join(&dir);
+    if dir.is_empty() || dir.contains(['/', '\\']) || !theme_dir.is_dir() {
+        return Err(format!("Theme '{name}' not found"));
+    }
+
+    start_color_watch(&theme_dir, &dir, DEBOUNCE, move |change| {
+        if let Err(e) = app_handle.emit("theme-colors-changed", &change) {
+            log::warn!("Failed to emit theme-colors-changed: {e}");
+        }
+    })?;
+    Ok(())
+}
+
+/// Stop the color watch started by `watch_theme` (no-op if the theme isn't watched)
+#[tauri::command]
+pub async fn unwatch_theme(name: String) -> Result<(), String> {
+    let dir = super::get_sys_themes::theme_cache_key(&name);
+    stop_color_watch(&dir)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_dir_for_maps_paths_to_theme_directories() {
+        let themes_dir = Path::new("/home/user/.config/omarchy/themes");
+        assert_eq!(
+            theme_dir_for(themes_dir, &themes_dir.join("nord/backgrounds/1.png")).as_deref(),
+            Some("nord")
+        );
+        assert_eq!(
+            theme_dir_for(themes_dir, &themes_dir.join("nord")).as_deref(),
+            Some("nord")
+        );
+        assert_eq!(theme_dir_for(themes_dir, themes_dir), None);
+        assert_eq!(
+            theme_dir_for(
+                themes_dir,
+                &themes_dir.join(".create-1234/custom_theme.json")
+            ),
+            None
+        );
+        assert_eq!(theme_dir_for(themes_dir, Path::new("/tmp/nord")), None);
+    }
+
+    #[tokio::test]
+    async fn test_color_watch_reports_debounced_color_changes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let theme_dir = temp.path().join("tuned");
+        std::fs::create_dir(&theme_dir).unwrap();
+        let write_background = |background: &str| {
+            let config = format!(
+                r##"[colors.primary]
+background = "{background}"
+foreground = "#eeeeee"
+
+[colors.normal]
+red = "#cc0000"
+green = "#00cc00"
+yellow = "#cccc00"
+blue = "#0000cc"
+magenta = "#cc00cc"
+cyan = "#00cccc"
+"##
+            );
+            std::fs::write(theme_dir.join("alacritty.toml"), config).unwrap();
+        };
+        write_background("#000000");
+
+        let (sender, mut changes) = mpsc::unbounded_channel();
+        let dir = format!("tuned-{}", std::process::id());
+        let debounce = Duration::from_millis(100);
+        assert!(
+            start_color_watch(&theme_dir, &dir, debounce, move |change| {
+                let _ = sender.send(change);
+            })
+            .unwrap()
+        );
+        assert!(!start_color_watch(&theme_dir, &dir, debounce, |_| {}).unwrap());
+
+        // Rapid saves arrive as one update with the last colors; other files are ignored
+        std::fs::write(theme_dir.join("notes.txt"), "ignored").unwrap();
+        for background in ["#111111", "#222222", "#333333"] {
+            write_background(background);
+        }
+        let change = tokio::time::timeout(Duration::from_secs(5), changes.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(change.theme, dir);
+        assert_eq!(change.colors.unwrap().primary.background, "#333333");
+        assert!(
+            tokio::time::timeout(Duration::from_millis(300), changes.recv())
+                .await
+                .is_err()
+        );
+
+        assert!(stop_color_watch(&dir).unwrap());
+        assert!(!stop_color_watch(&dir).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_bursts_are_debounced_per_theme() {
+        let themes_dir = Path::new("/themes");
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        for path in ["nord/a.toml", "nord/b.toml", "ash/c.css", "nord"] {
+            sender.send(themes_dir.join(path)).unwrap();
+        }
+
+        let burst = next_burst(&mut receiver, themes_dir, Duration::from_millis(20))
+            .await
+            .unwrap();
+        assert_eq!(
+            burst,
+            HashSet::from(["nord".to_string(), "ash".to_string()])
+        );
+
+        // A later event starts a new burst; a closed channel ends the debouncer
+        sender.send(themes_dir.join("ash/d.css")).unwrap();
+        drop(sender);
+        let burst = next_burst(&mut receiver, themes_dir, Duration::from_millis(20))
+            .await
+            .unwrap();
+        assert_eq!(burst, HashSet::from(["ash".to_string()]));
+        assert!(
+            next_burst(&mut receiver, themes_dir, Duration::from_millis(20))
+                .await
+                .is_none()
+        );
+    }
+}
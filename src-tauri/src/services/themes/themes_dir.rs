@@ -0,0 +1,84 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Themes directory configured in `AppCacheConfig`, used instead of the default when set
+static THEMES_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Override the themes directory; `None` restores the default location
+pub fn set_themes_dir_override(dir: Option<PathBuf>) {
+    match THEMES_DIR_OVERRIDE.write() {
+        Ok(mut current) => *current = dir,
+        Err(e) => log::warn!("Failed to update themes directory override: {e}"),
+    }
+}
+
+/// The user's config directory: `XDG_CONFIG_HOME` when it is set to an absolute path,
+/// otherwise `~/.config`
+pub fn config_dir() -> Result<PathBuf, String> {
+    resolve_config_dir(std::env::var_os("XDG_CONFIG_HOME"), dirs::home_dir())
+}
+
+/// The directory holding omarchy themes: the configured override when there is one,
+/// otherwise `omarchy/themes` in the config directory
+pub fn themes_dir() -> Result<PathBuf, String> {
+    let configured = THEMES_DIR_OVERRIDE.read().ok().and_then(|dir| dir.clone());
+    match configured {
+        Some(dir) => Ok(dir),
+        None => Ok(config_dir()?.join("omarchy").join("themes")),
+    }
+}
+
+fn resolve_config_dir(
+    xdg_config_home: Option<OsString>,
+    home_dir: Option<PathBuf>,
+) -> Result<PathBuf, String> {
+    // The XDG spec says relative paths are invalid and should be ignored
+    if let Some(dir) = xdg_config_home.map(PathBuf::from) {
+        if dir.is_absolute() {
+            return Ok(dir);
+        }
+    }
+
+    let home_dir = home_dir.ok_or_else(|| "Failed to get home directory".to_string())?;
+    Ok(home_dir.join(".config"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xdg_config_home_redirects_config_dir() {
+        let home = Some(PathBuf::from("/home/user"));
+        assert_eq!(
+            resolve_config_dir(None, home.clone()).unwrap(),
+            PathBuf::from("/home/user/.config")
+        );
+        assert_eq!(
+            resolve_config_dir(Some("/srv/config".into()), home.clone()).unwrap(),
+            PathBuf::from("/srv/config")
+        );
+        assert_eq!(
+            resolve_config_dir(Some("/srv/config".into()), None).unwrap(),
+            PathBuf::from("/srv/config")
+        );
+        // Relative and empty values fall back to ~/.config
+        for ignored in ["relative/config", ""] {
+            assert_eq!(
+                resolve_config_dir(Some(ignored.into()), home.clone()).unwrap(),
+                PathBuf::from("/home/user/.config")
+            );
+        }
+        assert!(resolve_config_dir(None, None).is_err());
+    }
+
+    #[test]
+    fn test_themes_dir_follows_config_dir() {
+        // Only checks the default layout; the override is global state shared across tests
+        assert_eq!(
+            themes_dir().unwrap(),
+            config_dir().unwrap().join("omarchy").join("themes")
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Temporary file next to `path`, so the final rename stays on the same filesystem
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{file_name}.omarchist-tmp"))
+}
+
+/// Replace `path` with `contents` so readers see either the old file or the new one,
+/// never a truncated mix
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_atomic_with(path, |file| file.write_all(contents.as_ref()))
+}
+
+/// Like `write_atomic`, with the contents produced by `write`. If it fails, the
+/// temporary file is removed and `path` is left untouched.
+pub fn write_atomic_with(
+    path: &Path,
+    write: impl FnOnce(&mut fs::File) -> io::Result<()>,
+) -> io::Result<()> {
+    let temp_path = temp_path_for(path);
+    let result = fs::File::create(&temp_path).and_then(|mut file| {
+        write(&mut file)?;
+        file.sync_all()
+    });
+
+    match result.and_then(|()| fs::rename(&temp_path, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(e)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_write_keeps_previous_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("custom_theme.json");
+        write_atomic(&path, r#"{"name":"Good"}"#).unwrap();
+
+        // Simulate a crash halfway through the new contents
+        let result = write_atomic_with(&path, |file| {
+            file.write_all(br#"{"name":"Tru"#)?;
+            Err(io::Error::other("killed mid-write"))
+        });
+        assert!(result.is_err());
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"name":"Good"}"#);
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 1);
+
+        write_atomic(&path, r#"{"name":"Better"}"#).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"name":"Better"}"#);
+    }
+}
@@ -0,0 +1,42 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Encode bytes as standard, padded base64
+pub fn encode(data: &[u8]) -> String {
+    STANDARD.encode(data)
+}
+
+/// Decode standard base64, rejecting missing or misplaced padding and invalid characters
+pub fn decode(data: &str) -> Result<Vec<u8>, String> {
+    STANDARD
+        .decode(data)
+        .map_err(|e| format!("Failed to decode base64: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_encode() {
+        let data = b"hello world";
+        let encoded = encode(data);
+        assert_eq!(encoded, "aGVsbG8gd29ybGQ=");
+
+        let empty_data = b"";
+        let empty_encoded = encode(empty_data);
+        assert_eq!(empty_encoded, "");
+    }
+
+    #[test]
+    fn test_base64_decode_round_trips_and_validates() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+        assert_eq!(decode("aGVsbG8gd29ybGQ=").unwrap(), b"hello world");
+        assert_eq!(decode("").unwrap(), Vec::<u8>::new());
+
+        assert!(decode("aGVsbG8gd29ybGQ").is_err());
+        assert!(decode("aGVsbG8=d29ybGQ=").is_err());
+        assert!(decode("aGVs*G8gd29ybGQ=").is_err());
+    }
+}
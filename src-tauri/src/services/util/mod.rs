@@ -0,0 +1,3 @@
+// Small helpers shared across services
+pub mod atomic_file;
+pub mod base64;
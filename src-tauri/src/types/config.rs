@@ -2,17 +2,63 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::ThemeColors;
+
 /// Application settings structure
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AppSettings {
     /// Whether to automatically apply themes when entering edit mode
     pub auto_apply_theme: bool,
+    /// Whether editing a read-only system theme forks it into a custom theme
+    #[serde(default = "default_fork_system_theme_on_edit")]
+    pub fork_system_theme_on_edit: bool,
+    /// Whether custom theme metadata is stored as compact (minified) JSON
+    #[serde(default)]
+    pub compact_theme_json: bool,
+    /// How many earlier versions of each theme's metadata are kept for rollback
+    #[serde(default = "default_theme_backups_to_keep")]
+    pub theme_backups_to_keep: usize,
+    /// Widest background image, in pixels, that may be added to a theme
+    #[serde(default = "default_max_background_width")]
+    pub max_background_width: u32,
+    /// Tallest background image, in pixels, that may be added to a theme
+    #[serde(default = "default_max_background_height")]
+    pub max_background_height: u32,
+    /// Largest background image file, in megabytes, that may be added to a theme
+    #[serde(default = "default_max_background_megabytes")]
+    pub max_background_megabytes: u64,
+}
+
+fn default_fork_system_theme_on_edit() -> bool {
+    true
+}
+
+fn default_theme_backups_to_keep() -> usize {
+    5
+}
+
+fn default_max_background_width() -> u32 {
+    8000
+}
+
+fn default_max_background_height() -> u32 {
+    8000
+}
+
+fn default_max_background_megabytes() -> u64 {
+    40
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             auto_apply_theme: true,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         }
     }
 }
@@ -52,14 +98,35 @@ pub struct SettingsMetadata {
 }
 
 /// Application cache configuration that includes all cache settings
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppCacheConfig {
     /// Theme cache configuration
     pub theme_cache: crate::services::themes::theme_cache::CacheConfig,
-    /// Whether to enable cache persistence (future feature)
+    /// Whether to persist extracted theme colors across launches
     pub enable_persistence: bool,
-    /// Global cache directory path (future feature)
+    /// Directory for persisted caches (defaults to the app data directory)
     pub cache_directory: Option<String>,
+    /// Themes directory to read and write instead of `$XDG_CONFIG_HOME/omarchy/themes`
+    pub themes_directory: Option<String>,
+    /// Palette used for themes whose colors cannot be extracted
+    #[serde(default = "default_fallback_colors")]
+    pub fallback_colors: ThemeColors,
+}
+
+fn default_fallback_colors() -> ThemeColors {
+    crate::services::themes::color_extraction::ColorExtractor::get_fallback_colors()
+}
+
+impl Default for AppCacheConfig {
+    fn default() -> Self {
+        Self {
+            theme_cache: Default::default(),
+            enable_persistence: false,
+            cache_directory: None,
+            themes_directory: None,
+            fallback_colors: default_fallback_colors(),
+        }
+    }
 }
 
 /// Result of startup CLI processing
@@ -1,4 +1,5 @@
 // Centralized error type definitions
+use serde::ser::{Serialize, SerializeStruct, Serializer};
 use thiserror::Error;
 
 /// Main application error type that encompasses all possible errors
@@ -41,36 +42,74 @@ pub enum AppError {
     Generic(String),
 }
 
-/// Theme-specific error types
-#[derive(Debug, Error)]
+/// Theme-specific error types. Serialized as `{ "kind": "not_found", "message": "..." }`
+/// so the frontend can branch on the kind and still show the message.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
 pub enum ThemeError {
-    /// Theme not found
-    #[error("Theme '{0}' not found")]
+    /// Theme, background or backup does not exist
+    #[error("{0}")]
     NotFound(String),
 
-    /// Invalid theme format
-    #[error("Invalid theme format: {0}")]
-    InvalidFormat(String),
+    /// Target name is already taken
+    #[error("{0}")]
+    AlreadyExists(String),
+
+    /// Reading or writing files failed
+    #[error("{0}")]
+    Io(String),
+
+    /// Stored or incoming data could not be parsed or serialized
+    #[error("{0}")]
+    Parse(String),
 
-    /// Theme application failed
-    #[error("Failed to apply theme: {0}")]
-    ApplyFailed(String),
+    /// A config generator failed to produce output
+    #[error("{0}")]
+    Generation(String),
+
+    /// Input was rejected (bad names, unsupported formats, read-only themes)
+    #[error("{0}")]
+    Validation(String),
+}
 
-    /// Color extraction failed
-    #[error("Failed to extract colors: {0}")]
-    ColorExtractionFailed(String),
+impl ThemeError {
+    /// Machine-readable kind sent to the frontend
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ThemeError::NotFound(_) => "not_found",
+            ThemeError::AlreadyExists(_) => "already_exists",
+            ThemeError::Io(_) => "io",
+            ThemeError::Parse(_) => "parse",
+            ThemeError::Generation(_) => "generation",
+            ThemeError::Validation(_) => "validation",
+        }
+    }
 
-    /// Theme creation failed
-    #[error("Failed to create theme: {0}")]
-    CreationFailed(String),
+    /// Human-readable description
+    pub fn message(&self) -> &str {
+        match self {
+            ThemeError::NotFound(message)
+            | ThemeError::AlreadyExists(message)
+            | ThemeError::Io(message)
+            | ThemeError::Parse(message)
+            | ThemeError::Generation(message)
+            | ThemeError::Validation(message) => message,
+        }
+    }
+}
 
-    /// Theme update failed
-    #[error("Failed to update theme: {0}")]
-    UpdateFailed(String),
+impl Serialize for ThemeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ThemeError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
 
-    /// Theme deletion failed
-    #[error("Failed to delete theme: {0}")]
-    DeletionFailed(String),
+impl From<ThemeError> for String {
+    fn from(e: ThemeError) -> Self {
+        e.to_string()
+    }
 }
 
 /// Settings-specific error types
@@ -167,15 +206,3 @@ impl From<&str> for AppError {
         AppError::Generic(s.to_string())
     }
 }
-
-impl From<String> for ThemeError {
-    fn from(s: String) -> Self {
-        ThemeError::ApplyFailed(s)
-    }
-}
-
-impl From<&str> for ThemeError {
-    fn from(s: &str) -> Self {
-        ThemeError::ApplyFailed(s.to_string())
-    }
-}
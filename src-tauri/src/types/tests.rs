@@ -12,6 +12,12 @@ mod tests {
     fn test_app_settings_serialization() {
         let settings = AppSettings {
             auto_apply_theme: false,
+            fork_system_theme_on_edit: true,
+            compact_theme_json: false,
+            theme_backups_to_keep: 5,
+            max_background_width: 8000,
+            max_background_height: 8000,
+            max_background_megabytes: 40,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -35,6 +41,7 @@ mod tests {
                 magenta: "#ff00ff".to_string(),
                 cyan: "#00ffff".to_string(),
             },
+            accent: Some("#ff0000".to_string()),
         };
 
         let json = serde_json::to_string(&colors).unwrap();
@@ -42,6 +49,7 @@ mod tests {
 
         assert_eq!(colors.primary.background, deserialized.primary.background);
         assert_eq!(colors.terminal.red, deserialized.terminal.red);
+        assert_eq!(colors.accent, deserialized.accent);
     }
 
     #[test]
@@ -77,6 +85,20 @@ mod tests {
         assert!(matches!(app_error, AppError::Theme(_)));
     }
 
+    #[test]
+    fn test_theme_error_serializes_kind_and_message() {
+        let error = ThemeError::AlreadyExists("Theme 'nord' already exists".to_string());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "already_exists",
+                "message": "Theme 'nord' already exists"
+            })
+        );
+        assert_eq!(error.to_string(), "Theme 'nord' already exists");
+        assert_eq!(String::from(error), "Theme 'nord' already exists");
+    }
+
     #[test]
     fn test_custom_theme_serialization() {
         let theme = CustomTheme {
@@ -85,11 +107,27 @@ mod tests {
             modified_at: "2023-01-01T00:00:00Z".to_string(),
             apps: serde_json::json!({}),
             colors: None,
+            selected_background: None,
+            tags: vec!["dark".to_string()],
+            author: Some("someone".to_string()),
+            version: None,
+            description: String::new(),
+            preview: None,
         };
 
         let json = serde_json::to_string(&theme).unwrap();
         let deserialized: CustomTheme = serde_json::from_str(&json).unwrap();
 
         assert_eq!(theme.name, deserialized.name);
+        assert_eq!(theme.tags, deserialized.tags);
+        assert_eq!(theme.author, deserialized.author);
+
+        // Metadata written before tags/author/version existed still loads
+        let legacy: CustomTheme = serde_json::from_str(
+            r#"{"name":"old","created_at":"","modified_at":"","apps":{},"colors":null}"#,
+        )
+        .unwrap();
+        assert!(legacy.tags.is_empty());
+        assert!(legacy.author.is_none() && legacy.version.is_none());
     }
 }
@@ -27,6 +27,249 @@ pub struct CustomTheme {
     pub modified_at: String,
     pub apps: Value,                 // Dynamic structure for all app configurations
     pub colors: Option<ThemeColors>, // Extracted color palette
+    #[serde(default)]
+    pub selected_background: Option<String>, // File name in backgrounds/ used as wallpaper
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Free-form description; empty for themes created before descriptions were stored
+    #[serde(default)]
+    pub description: String,
+    /// Image file (in the theme directory or `backgrounds/`) to use as the thumbnail
+    #[serde(default)]
+    pub preview: Option<String>,
+}
+
+/// Per-item outcome of a batch theme operation
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchThemeResult {
+    pub name: String,
+    pub theme: Option<CustomTheme>,
+    pub error: Option<String>,
+}
+
+/// Result of checking one generated config with the target app's own validator
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppValidationResult {
+    pub app: String,
+    pub passed: bool,
+    pub stderr: String,
+}
+
+/// Optional features a registered config generator supports
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GeneratorCapabilities {
+    pub app: String,
+    pub can_parse: bool,
+    pub multi_file: bool,
+    pub supports_raw_append: bool,
+    pub validates_output: bool,
+    pub uses_accent: bool,
+}
+
+/// Baseline configuration for an app that has no data in a theme yet
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DefaultAppConfig {
+    pub app: String,
+    pub file_name: String,
+    pub theme_data: Value,
+    pub content: String,
+}
+
+/// Which app configs `regenerate_configs` rewrote for a theme
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RegeneratedConfigs {
+    pub regenerated: Vec<String>,
+    /// Apps the theme has no data for
+    pub skipped: Vec<String>,
+    /// `app: error` for generators that failed; their previous files are left in place
+    pub failed: Vec<String>,
+}
+
+/// A background image with the details the UI needs to warn about it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackgroundInfo {
+    pub filename: String,
+    /// Animated GIF or WebP; compositors may only show the first frame
+    pub is_animated: bool,
+    /// Pixel dimensions, `None` when the header can't be read
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub size_bytes: u64,
+}
+
+/// Raw image file contents with their MIME type, for building blob URLs
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RawImage {
+    pub bytes: Vec<u8>,
+    pub mime_type: String,
+}
+
+/// Outcome of adding background images: copied file names and (path, reason) for skipped ones
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct AddBackgroundsResult {
+    pub copied: Vec<String>,
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Outcome of removing byte-identical backgrounds: removed file names, the space they used
+/// and (file name, reason) for files that couldn't be read or removed
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct DeduplicatedBackgrounds {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+    pub failed: Vec<(String, String)>,
+}
+
+/// A theme palette as it was saved at some point in the past
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThemeColorsSnapshot {
+    pub modified_at: String,
+    pub colors: ThemeColors,
+}
+
+/// An earlier version of a theme's metadata kept in its backups directory
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThemeBackup {
+    /// Identifier to pass to `restore_theme_backup`
+    pub timestamp: String,
+    /// When the backed-up version was last modified
+    pub modified_at: String,
+}
+
+/// How `update_theme_advanced` combines arrays in incoming theme data with existing ones
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Incoming arrays replace existing ones wholesale
+    #[default]
+    Replace,
+    /// Incoming array elements are appended to existing arrays
+    ConcatArrays,
+}
+
+/// How a palette slot differs from one theme to another
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One palette slot (e.g. "primary.background" or "terminal.red") that differs between themes
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColorChange {
+    pub slot: String,
+    pub kind: ColorChangeKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Palette differences going from `theme_a` to `theme_b`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThemeDiff {
+    pub theme_a: String,
+    pub theme_b: String,
+    pub changes: Vec<ColorChange>,
+}
+
+/// Whether a theme data problem blocks saving or is only worth pointing out
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem with one field of an app's theme data, from `validate_theme_data`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ValidationError {
+    pub app: String,
+    /// Dotted path below the app, e.g. "colors.background"; empty for the app itself
+    pub field: String,
+    pub severity: ValidationSeverity,
+    pub message: String,
+}
+
+/// How serious an advisory lint finding is
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    Info,
+    Warning,
+}
+
+/// Advisory palette finding from `lint_theme`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LintFinding {
+    pub severity: LintSeverity,
+    pub code: String,
+    pub message: String,
+}
+
+/// Outcome of a single `preflight_theme` check
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PreflightStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One check made before applying a theme, e.g. `config:hyprland`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PreflightItem {
+    pub check: String,
+    pub status: PreflightStatus,
+    pub message: String,
+}
+
+/// Whether a theme is complete enough to apply; `ok` is false when any check failed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PreflightReport {
+    pub theme: String,
+    pub ok: bool,
+    pub items: Vec<PreflightItem>,
+}
+
+/// A theme color that is hard to read on the background (WCAG ratio below 4.5:1)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContrastWarning {
+    /// Which color was checked: "foreground" or a terminal slot such as "red"
+    pub element: String,
+    pub color: String,
+    pub background: String,
+    pub ratio: f64,
+}
+
+/// Kind of input an import format expects from the user
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportInput {
+    File,
+    Text,
+    Url,
+}
+
+/// An import source compiled into this build
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportFormat {
+    pub id: String,
+    pub label: String,
+    pub input: ImportInput,
+    pub extensions: Vec<String>, // Accepted file extensions for file inputs
+}
+
+/// How often a color (or a bucket of near-identical colors) is used across themes
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColorUsage {
+    pub color: String,
+    pub count: usize,
+    pub themes: Vec<String>,
 }
 
 /// Complete color palette extracted from a theme
@@ -34,6 +277,9 @@ pub struct CustomTheme {
 pub struct ThemeColors {
     pub primary: PrimaryColors,
     pub terminal: TerminalColors,
+    /// Highlight color, distinct from the foreground (optional for older metadata)
+    #[serde(default)]
+    pub accent: Option<String>,
 }
 
 /// Primary colors (background and foreground) from terminal theme
@@ -74,6 +320,22 @@ pub struct AlacrittyPrimaryColors {
     pub dim_foreground: String,
 }
 
+/// One page of a list in display order, with the size of the whole list
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    /// Take up to `limit` items starting at `offset` from an already sorted list
+    pub fn from_sorted(items: Vec<T>, offset: usize, limit: usize) -> Self {
+        let total = items.len();
+        let items = items.into_iter().skip(offset).take(limit).collect();
+        Self { items, total }
+    }
+}
+
 /// Cache statistics for theme operations
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CacheStats {